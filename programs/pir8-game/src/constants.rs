@@ -9,7 +9,58 @@ pub const MAX_PLAYERS: u8 = 4;
 pub const MIN_PLAYERS: u8 = 2;
 pub const MAP_SIZE: usize = 10;
 pub const MAX_SHIPS_PER_PLAYER: usize = 6;
-pub const TURN_TIMEOUT_SECONDS: i64 = 45;
+// Distinct wallets a game records signing or paying for its instructions
+pub const MAX_LOGGED_SIGNERS: usize = 12;
+// SOL entry fee for games without a token stake (0.1 SOL)
+pub const SOL_ENTRY_FEE_LAMPORTS: u64 = 100_000_000;
+// Chess-clock time bank: starting budget per player and the increment banked per turn
+pub const TIME_BANK_SECONDS: u32 = 300;
+pub const TIME_INCREMENT_SECONDS: u32 = 10;
+// Once per game, a sequential player may call a parley to push back their own deadline
+pub const PARLEY_EXTENSION_SECONDS: i64 = 30;
+// How long simultaneous captains have to commit once a round opens
+pub const COMMIT_WINDOW_SECONDS: i64 = 60;
+// How long simultaneous captains have to reveal once every commitment is in
+pub const REVEAL_WINDOW_SECONDS: i64 = 60;
+pub const ACTION_POINTS_PER_TURN: u8 = 3;
+// Round after which the game is decided on score, and the sudden-death rounds allowed for ties
+pub const MAX_TURNS: u32 = 50;
+pub const OVERTIME_ROUNDS: u32 = 3;
+// Sudden death: after this round the map shrinks by one ring per round, down
+// to SUDDEN_DEATH_RINGS rings, and ships in the storm take damage each round
+pub const SUDDEN_DEATH_TURN: u32 = 40;
+pub const SUDDEN_DEATH_RINGS: u8 = 4;
+pub const SUDDEN_DEATH_STORM_DAMAGE: u32 = 25;
+// Rounds a weather front lasts before the next is drawn
+pub const WEATHER_MIN_ROUNDS: u8 = 2;
+pub const WEATHER_MAX_ROUNDS: u8 = 4;
+// Actions a player must take to be scored and share the pot
+pub const MIN_ACTIONS_FOR_POT: u16 = 5;
+// How long a resignation may be withdrawn for, where the config allows it
+pub const RESIGN_GRACE_SECONDS: i64 = 60;
+// Lamports rebated per action to players who finish, and the share of the SOL pot rebates may take
+pub const MOVE_REBATE_LAMPORTS: u64 = 5_000;
+pub const MAX_REBATE_POOL_BPS: u32 = 500;
+// Spectator tips: the most a game's pot may take in tips (5 SOL), and the last round they are accepted
+pub const MAX_TIPS_LAMPORTS: u64 = 5_000_000_000;
+pub const TIP_CUTOFF_TURN: u32 = 30;
+// Minimum time between an agent's strategy hash commitments
+pub const STRATEGY_HASH_COOLDOWN_SECONDS: i64 = 3600;
+// Longest agent metadata strings, in bytes after trimming; AgentRegistry::SPACE is sized for them
+pub const MAX_AGENT_NAME_LEN: usize = 32;
+pub const MAX_AGENT_VERSION_LEN: usize = 16;
+pub const MAX_AGENT_TWITTER_LEN: usize = 32;
+pub const MAX_AGENT_WEBSITE_LEN: usize = 64;
+// Agent operator bonds: how long a join locks the bond, strikes forgiven before
+// slashing starts, and the share of the bond each further strike sends to the pot
+pub const AGENT_BOND_LOCK_SECONDS: i64 = 7 * 86_400;
+pub const AGENT_BOND_FREE_STRIKES: u16 = 1;
+pub const AGENT_BOND_SLASH_BPS: u32 = 2_500;
+// Fixed-point scale for multipliers: 10_000 = 1.0x
+pub const BASIS_POINTS: u32 = 10_000;
+// The standalone rules module carries its own copies for client builds
+const _: () = assert!(BASIS_POINTS == crate::rules::BASIS_POINTS);
+const _: () = assert!(MAP_SIZE == crate::rules::MAP_SIZE as usize);
 
 // Ship building costs: (gold, crew, cannons, supplies)
 pub const SLOOP_COST: (u32, u32, u32, u32) = (500, 10, 5, 20);
@@ -1,45 +1,348 @@
+use crate::rules::RuleViolation;
 use anchor_lang::prelude::*;
 
+// Error codes are grouped into fixed numeric ranges per subsystem so client
+// SDKs can map them without depending on declaration order. Anchor offsets
+// custom errors by 6000; the first variant of each section pins its range
+// start. New errors go at the end of their section, never in the middle.
 #[error_code]
 pub enum GameError {
+    // ============================================================================
+    // MATCHMAKING & PROFILES (6000-6099)
+    // ============================================================================
     #[msg("Game is not active")]
-    GameNotActive,
+    GameNotActive = 0,
     #[msg("Game is full")]
     GameFull,
     #[msg("Not enough players to start")]
     NotEnoughPlayers,
     #[msg("Game already started")]
     GameAlreadyStarted,
+    #[msg("Game not joinable")]
+    GameNotJoinable,
+    #[msg("Game has not been completed")]
+    GameNotCompleted,
+    #[msg("Player not found in this game")]
+    PlayerNotInGame,
+    #[msg("Session key is not an authorized delegate")]
+    UnauthorizedDelegate,
+    #[msg("Invite list exceeds the player limit")]
+    InviteListTooLong,
+    #[msg("Minimum rating exceeds maximum rating")]
+    InvalidRatingBand,
+    #[msg("Player rating is outside this lobby's band")]
+    RatingOutOfRange,
+    #[msg("This lobby is invite-only")]
+    NotInvited,
+    #[msg("Player level is too low for this lobby")]
+    LevelTooLow,
+    #[msg("No open water available for a starting ship")]
+    NoValidSpawn,
+    #[msg("Starting loadout exceeds the configured limits")]
+    InvalidLoadout,
+    #[msg("Profile already settled for this game")]
+    ProfileAlreadySettled,
+    #[msg("Flag color or title not unlocked")]
+    CosmeticLocked,
+    #[msg("Player reputation is too low for this lobby")]
+    ReputationTooLow,
+    #[msg("This lobby only admits registered agents")]
+    AgentRequired,
+    #[msg("Agent has not played enough games for this lobby")]
+    AgentTooInexperienced,
+    #[msg("Strategy hash was updated too recently")]
+    StrategyHashCooldown,
+    #[msg("Too many games created or joined in the last hour")]
+    RateLimited,
+    #[msg("Objective target must be greater than zero, and tutorials have their own instruction")]
+    InvalidObjective,
+    #[msg("Signer does not hold this NFT")]
+    SkinNotOwned,
+    #[msg("NFT is not from an approved skin collection")]
+    SkinCollectionNotApproved,
+    #[msg("Wallet already signed or paid for another seat in this ranked game")]
+    SharedFundingWallet,
+    #[msg("Scoring weights out of range")]
+    InvalidScoringWeights,
+    #[msg("Agent bond is below this lobby's minimum")]
+    AgentBondTooLow,
+    #[msg("Agent bond is locked by a recent game")]
+    AgentBondLocked,
+    #[msg("Bond amount must be positive and no more than the posted bond")]
+    InvalidBondAmount,
+    #[msg("Only started or completed games can be forked")]
+    GameNotForkable,
+    #[msg("Practice forks play for no stakes and never count towards a profile")]
+    PracticeGame,
+    #[msg("The looking-for-game board is full")]
+    LfgBoardFull,
+    #[msg("Invalid looking-for-game entry: stake level or expiry out of range")]
+    InvalidLfgEntry,
+    #[msg("No live looking-for-game entry for that player")]
+    LfgEntryNotFound,
+    #[msg("These looking-for-game entries want different games")]
+    LfgEntriesIncompatible,
+    #[msg("This win is already on the decision-time leaderboard")]
+    DecisionTimeAlreadyRanked,
+    #[msg("Too few clocked decisions to rank this win")]
+    TooFewClockedDecisions,
+    #[msg("Lobby names are 1-32 lowercase letters, digits and inner hyphens")]
+    InvalidLobbyName,
+    #[msg("That lobby name belongs to another game")]
+    LobbyNameTaken,
+    #[msg("This lobby already has a name")]
+    LobbyAlreadyNamed,
+    #[msg("Agent name is empty, or a metadata field contains control characters")]
+    InvalidAgentMetadata,
+    #[msg("Agent name exceeds 32 bytes")]
+    AgentNameTooLong,
+    #[msg("Agent version exceeds 16 bytes")]
+    AgentVersionTooLong,
+    #[msg("Agent Twitter handle exceeds 32 bytes")]
+    AgentTwitterTooLong,
+    #[msg("Agent website exceeds 64 bytes")]
+    AgentWebsiteTooLong,
+    #[msg("This lobby has not been started")]
+    StartNotCommitted,
+    #[msg("The slot that seeds this game has not been hashed yet")]
+    SeedNotReady,
+
+    // ============================================================================
+    // COMBAT & TURNS (6100-6199)
+    // ============================================================================
     #[msg("Not your turn")]
-    NotPlayerTurn,
+    NotPlayerTurn = 100,
     #[msg("Ship not found")]
     ShipNotFound,
     #[msg("Ship not at specified location")]
     ShipNotAtLocation,
-    #[msg("Territory not controlled by player")]
-    TerritoryNotControlled,
-    #[msg("Insufficient resources")]
-    InsufficientResources,
-    #[msg("Fleet size limit reached")]
-    FleetSizeLimit,
-    #[msg("No adjacent controlled port")]
-    NoAdjacentPort,
     #[msg("Position occupied")]
     PositionOccupied,
-    #[msg("Game not joinable")]
-    GameNotJoinable,
     #[msg("Invalid coordinate")]
     InvalidCoordinate,
     #[msg("Ships not in range")]
     ShipsNotInRange,
-    #[msg("Unauthorized")]
-    Unauthorized,
     #[msg("No scan charges remaining")]
     NoScansRemaining,
     #[msg("Coordinate already scanned")]
     CoordinateAlreadyScanned,
-    #[msg("Session key is not an authorized delegate")]
-    UnauthorizedDelegate,
     #[msg("Ghost Fleet is already active")]
     GhostFleetAlreadyActive,
+    #[msg("No spyglass charges remaining")]
+    NoSpyglassCharges,
+    #[msg("Port is under blockade")]
+    PortBlockaded,
+    #[msg("Action batch is empty")]
+    EmptyActionBatch,
+    #[msg("Not enough action points for this batch")]
+    InsufficientActionPoints,
+    #[msg("EndTurn must be the last action in a batch")]
+    ActionAfterEndTurn,
+    #[msg("Action not available in this game's turn mode")]
+    WrongTurnMode,
+    #[msg("Wrong phase of the simultaneous round")]
+    WrongRoundPhase,
+    #[msg("Orders already committed this round")]
+    OrdersAlreadyCommitted,
+    #[msg("No orders committed this round")]
+    OrdersNotCommitted,
+    #[msg("Orders already revealed this round")]
+    OrdersAlreadyRevealed,
+    #[msg("Revealed orders do not match the commitment")]
+    CommitmentMismatch,
+    #[msg("Time bank exhausted")]
+    TimeBankExhausted,
+    #[msg("Current player still has time on the clock")]
+    TimeBankNotExhausted,
+    #[msg("Destination is beyond the ship's speed")]
+    MoveExceedsSpeed,
+    #[msg("Action not available in this game's objective")]
+    WrongObjective,
+    #[msg("Spectators cannot command a fleet")]
+    SpectatorCannotAct,
+    #[msg("Only eliminated players can cast a ghost curse")]
+    NotASpectator,
+    #[msg("Ghost curse already cast this game")]
+    GhostCurseUsed,
+    #[msg("Reveal window is still open")]
+    RevealWindowOpen,
+    #[msg("Action nonce is not the one expected; the action may already have landed")]
+    StaleActionNonce,
+    #[msg("Registry of a struck bonded agent must be passed as a writable remaining account")]
+    AgentBondAccountMissing,
+    #[msg("Path must step one neighbouring cell at a time from the ship to its destination")]
+    InvalidPath,
+    #[msg("Path passes through an island or an enemy ship")]
+    PathBlocked,
+    #[msg("Path enters an enemy ship's zone of control before its destination")]
+    ZoneOfControl,
+    #[msg("No resignation of yours is waiting to be withdrawn")]
+    NoResignationToCancel,
+    #[msg("Too late to withdraw the resignation")]
+    ResignationGraceOver,
+    #[msg("Already scanned this turn; scans are free but limited to one per turn")]
+    AlreadyScannedThisTurn,
+    #[msg("Parley already called this game")]
+    ParleyUsed,
+    #[msg("No open contract with that id")]
+    ContractNotFound,
+    #[msg("Contract already taken by another captain")]
+    ContractTaken,
+    #[msg("Finish or lose your current contract before taking another")]
+    AlreadyHoldingContract,
+    #[msg("Deterministic games cannot roll for the jackpot")]
+    JackpotInDeterministicGame,
+    #[msg("Commit window is still open")]
+    CommitWindowOpen,
+
+    // ============================================================================
+    // ECONOMY (6200-6299)
+    // ============================================================================
+    #[msg("Territory not controlled by player")]
+    TerritoryNotControlled = 200,
+    #[msg("Insufficient resources")]
+    InsufficientResources,
+    #[msg("Fleet size limit reached")]
+    FleetSizeLimit,
+    #[msg("No adjacent controlled port")]
+    NoAdjacentPort,
+    #[msg("Resources already collected this round")]
+    AlreadyCollectedThisRound,
+    #[msg("This port already has a ship under construction")]
+    ShipyardBusy,
+    #[msg("No construction order of yours at this port")]
+    NoConstructionAtPort,
+    #[msg("Game pot cannot fund a fee session")]
+    FeeSessionUnfunded,
+    #[msg("Entry fee must be paid in this game's stake asset")]
+    StakeAssetMismatch,
+    #[msg("Token entry fee must be greater than zero")]
+    InvalidEntryFee,
+    #[msg("Stake mint is non-transferable or has a permanent delegate")]
+    UnsupportedStakeMint,
+    #[msg("Only the winner can claim this pot")]
+    NotWinner,
+    #[msg("Nothing left to claim")]
+    NothingToClaim,
+    #[msg("Payout shares must total 100% and never increase for worse places")]
+    InvalidPayoutSchedule,
+    #[msg("You did not finish in that place")]
+    RankMismatch,
+    #[msg("Only islands, ports and treasure can be claimed")]
+    CellNotClaimable,
+    #[msg("Territory is controlled by another player")]
+    CellOwnedByOther,
+    #[msg("Location is not a port")]
+    NotAPort,
+    #[msg("Too many games in one bulk claim")]
+    TooManyGames,
+    #[msg("Payout memo is too long")]
+    MemoTooLong,
+    #[msg("Creator fee exceeds the configured maximum")]
+    CreatorFeeTooHigh,
+    #[msg("No treasure or flotsam to load here")]
+    NoCargoHere,
+    #[msg("Ship's hold is full")]
+    CargoHoldFull,
+    #[msg("Ship has no cargo aboard")]
+    NoCargoAboard,
+    #[msg("Ship is not at its home port")]
+    NotAtHomePort,
+    #[msg("A stake raise is already pending")]
+    RaisePending,
+    #[msg("No stake raise is pending")]
+    NoRaisePending,
+    #[msg("Already backing this stake raise")]
+    RaiseAlreadyAccepted,
+    #[msg("The stake raise's response window has closed")]
+    RaiseExpired,
+    #[msg("Stakes have been raised the maximum number of times")]
+    StakeRaiseLimit,
+    #[msg("Refund accounts must match the raise's backers in order")]
+    RefundAccountMismatch,
+    #[msg("Merkle proof does not match this distributor's root")]
+    InvalidPrizeProof,
+    #[msg("Claim exceeds the prizes left in this distributor")]
+    PrizeFundsExhausted,
+    #[msg("Prize claims are open until the reclaim time")]
+    PrizeClaimsStillOpen,
+    #[msg("Tips must be positive and keep the game's tips within 5 SOL")]
+    InvalidTip,
+    #[msg("Tips are closed for this game")]
+    TipsClosed,
+    #[msg("Captains cannot tip their own game")]
+    CaptainCannotTip,
+    #[msg("Liveness deposits are still held; settle them first")]
+    LivenessDepositsHeld,
+    #[msg("No liveness deposit is held for this seat")]
+    NoLivenessDeposit,
+    #[msg("Entry fee exceeds the configured cap")]
+    EntryFeeTooHigh,
+    #[msg("Entry fee would take you past your 24-hour wager cap")]
+    DailyWagerCapReached,
+    #[msg("Platform fees go to the config authority or the revenue splitter")]
+    InvalidTreasury,
+    #[msg("The revenue splitter holds no fees above rent exemption")]
+    NoFeesToDistribute,
+    #[msg("This prize tranche is still vesting")]
+    PrizeStillVesting,
+    #[msg("This prize tranche is frozen pending a dispute")]
+    PrizeFrozen,
+    #[msg("No vesting prize for that player")]
+    NoVestingPrize,
+    #[msg("This prize is past the longest a dispute may hold it")]
+    FreezeWindowClosed,
+    #[msg("Close this game's fee sessions first")]
+    FeeSessionsOpen,
+    #[msg("No jackpot roll is waiting in this game")]
+    NoJackpotRoll,
+    #[msg("The jackpot roll's slot has not been hashed yet")]
+    JackpotRollNotReady,
+    #[msg("A jackpot roll is still waiting to be revealed")]
+    JackpotRollPending,
+    #[msg("This seat has already opened its fee session for the game")]
+    FeeSessionUsed,
+    #[msg("A liveness deposit is refunded only to the wallet that paid it")]
+    LivenessPayerMismatch,
+
+    // ============================================================================
+    // ADMIN & INVARIANTS (6300+)
+    // ============================================================================
+    #[msg("Unauthorized")]
+    Unauthorized = 300,
+    #[msg("Config values out of range (multipliers 1-50000 bps, 1-6 starting ships, 8 skin collections)")]
+    InvalidConfig,
+    #[msg("Invariant violated: pot does not match vault lamports")]
+    InvariantPotMismatch,
+    #[msg("Invariant violated: territory ownership out of sync with map")]
+    InvariantTerritoryMismatch,
+    #[msg("Invariant violated: two ships share a cell")]
+    InvariantShipOverlap,
+    #[msg("Invariant violated: value exceeds its cap")]
+    InvariantResourceCap,
+    #[msg("Arithmetic overflow")]
+    ArithmeticOverflow,
+    #[msg("Export chunk index is past the end of the snapshot")]
+    ExportChunkOutOfRange,
+    #[msg("Vault balance does not match its ledger")]
+    VaultLedgerMismatch,
+    #[msg("Rulebook values out of range (ships need hull and a speed under the map size, ordered bonus brackets, thresholds 1-100%)")]
+    InvalidRulebook,
+    #[msg("Revenue shares must total 100%")]
+    InvalidRevenueSplit,
+    #[msg("Only an empty lobby or a fully settled game can be reset")]
+    GameNotResettable,
+}
+
+impl From<RuleViolation> for GameError {
+    fn from(violation: RuleViolation) -> Self {
+        match violation {
+            RuleViolation::OutOfBounds => GameError::InvalidCoordinate,
+            RuleViolation::ExceedsSpeed => GameError::MoveExceedsSpeed,
+            RuleViolation::OutOfRange => GameError::ShipsNotInRange,
+            RuleViolation::BrokenPath => GameError::InvalidPath,
+            RuleViolation::Blocked => GameError::PathBlocked,
+            RuleViolation::ZoneOfControl => GameError::ZoneOfControl,
+        }
+    }
 }
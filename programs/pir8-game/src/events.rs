@@ -1,15 +1,112 @@
-use crate::state::player::ShipType;
+use crate::state::config::ConfigParams;
+use crate::state::contract::Contract;
+use crate::state::diff::{CellDelta, ResourceDelta, ShipDelta};
+use crate::state::game::{GameMode, VictoryType};
+use crate::state::player::{Resources, ScanDepth, ShipType};
+use crate::state::revenue::RevenueSplit;
+use crate::state::rulebook::RulebookParams;
+use crate::state::scoring::ScoringWeights;
+use crate::state::tutorial::TutorialCheckpoint;
 use anchor_lang::prelude::*;
 
 #[event]
 pub struct PlayerJoined {
     pub player: Pubkey,
     pub player_count: u8,
+    pub flag_color: u8,
+    pub avatar_seed: u64,
+    pub title: u8,
+}
+
+#[event]
+pub struct StrategyHashUpdated {
+    pub agent: Pubkey,
+    pub version: String,
+    pub strategy_hash: [u8; 32],
+}
+
+#[event]
+pub struct AgentMetadataUpdated {
+    pub agent: Pubkey,
+    pub name: String,
+    pub version: String,
+}
+
+#[event]
+pub struct AgentBondChanged {
+    pub agent: Pubkey,
+    pub posted: u64,
+    pub withdrawn: u64,
+    pub bond: u64,
+}
+
+#[event]
+pub struct AgentStruck {
+    pub game_id: u64,
+    pub agent: Pubkey,
+    pub strikes: u16,
+    pub slashed: u64,
+    pub bond: u64,
+}
+
+#[event]
+pub struct InviteSent {
+    pub game_id: u64,
+    pub sender: Pubkey,
+    pub target: Pubkey,
+}
+
+#[event]
+pub struct InviteClosed {
+    pub game_id: u64,
+    pub sender: Pubkey,
+    pub target: Pubkey,
+    pub accepted: bool,
+}
+
+/// A lobby was started; it begins once `reveal_slot` has been hashed
+#[event]
+pub struct StartCommitted {
+    pub game_id: u64,
+    pub reveal_slot: u64,
 }
 
 #[event]
 pub struct GameStarted {
     pub player_count: u8,
+    pub rulebook_version: u32, // 0 when the game plays the built-in rules
+}
+
+#[event]
+pub struct GameForked {
+    pub source_game_id: u64,
+    pub game_id: u64,
+    pub forked_by: Pubkey,
+    pub turn_number: u32,
+}
+
+#[event]
+pub struct LfgPosted {
+    pub player: Pubkey,
+    pub rating: u32,
+    pub min_rating: u32,
+    pub max_rating: u32,
+    pub mode: GameMode,
+    pub stake_lamports: u64,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct LfgCancelled {
+    pub player: Pubkey,
+}
+
+#[event]
+pub struct LfgMatched {
+    pub game_id: u64,
+    pub players: Vec<Pubkey>,
+    pub mode: GameMode,
+    pub stake_lamports: u64,
 }
 
 #[event]
@@ -21,6 +118,7 @@ pub struct ShipMoved {
     pub from_y: u8,
     pub to_x: u8,
     pub to_y: u8,
+    pub skin_mint: Option<Pubkey>,
 }
 
 #[event]
@@ -39,6 +137,8 @@ pub struct TerritoryClaimed {
     pub player: Pubkey,
     pub territory_x: u8,
     pub territory_y: u8,
+    pub cell_type: u8, // TerritoryCellType discriminant
+    pub flavor_id: u16,
 }
 
 #[event]
@@ -59,11 +159,65 @@ pub struct ShipBuilt {
     pub position_y: u8,
 }
 
+/// One sudden-death round: the ring consumed this round, if any, and the
+/// ships battered by the storm
+#[event]
+pub struct MapShrunk {
+    pub game_id: u64,
+    pub turn_number: u32,
+    pub rings_consumed: u8,
+    pub cells_consumed: u8,
+    pub ships_hit: u8,
+}
+
+#[event]
+pub struct PortBlockaded {
+    pub game_id: u64,
+    pub owner: Pubkey,
+    pub port_x: u8,
+    pub port_y: u8,
+    pub blockaded_until_turn: u32,
+}
+
+#[event]
+pub struct ConstructionStarted {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub ship_type: ShipType,
+    pub port_x: u8,
+    pub port_y: u8,
+    pub turns_remaining: u8,
+}
+
+#[event]
+pub struct ConstructionCancelled {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub ship_type: ShipType,
+    pub port_x: u8,
+    pub port_y: u8,
+    pub refund: Resources,
+}
+
+/// A captain has come within VICTORY_WARNING_PERCENT of a victory condition
+#[event]
+pub struct VictoryThreatened {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub victory: VictoryType,
+    pub turn_number: u32,
+}
+
 #[event]
 pub struct GameCompleted {
     pub game_id: u64,
     pub winner: Pubkey,
+    pub victory: VictoryType,
+    /// Deprecated: `victory` as display text, kept for one release while
+    /// indexers move over
     pub victory_type: String,
+    pub scoring: ScoringWeights,
+    pub final_state_hash: [u8; 32],
 }
 
 #[event]
@@ -72,10 +226,35 @@ pub struct CoordinateScanned {
     pub player: Pubkey,
     pub coordinate_x: u8,
     pub coordinate_y: u8,
-    pub tile_type: String, // Don't reveal exact item
+    pub depth: ScanDepth,
+    pub is_land: bool,
+    pub cell_type: u8, // TerritoryCellType discriminant from CellType depth, else UNKNOWN_CELL_TYPE
+    pub flavor_id: u16,
+    pub owner: Option<Pubkey>,        // Full depth only
+    pub remaining_yield: Option<u16>, // Full depth only
     pub scan_charges_remaining: u8,
 }
 
+/// One enemy ship seen through a spyglass
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ShipSighting {
+    pub owner: Pubkey,
+    pub ship_type: ShipType,
+    pub position_x: u8,
+    pub position_y: u8,
+    pub health: u32,
+}
+
+/// Spyglass results for the observing player only; sweeps see through Fog
+#[event]
+pub struct SpyglassReport {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub ship_id: String,
+    pub sightings: Vec<ShipSighting>,
+    pub spyglass_charges_remaining: u8,
+}
+
 #[event]
 pub struct MoveExecuted {
     pub game_id: u64,
@@ -91,3 +270,483 @@ pub struct GhostFleetActivated {
     pub player: Pubkey,
     pub turns_remaining: u8,
 }
+
+#[event]
+pub struct OrdersCommitted {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub turn_number: u32,
+}
+
+#[event]
+pub struct OrdersRevealed {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub turn_number: u32,
+    pub order_count: u8,
+}
+
+#[event]
+pub struct RevealMissed {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub turn_number: u32,
+}
+
+#[event]
+pub struct CommitMissed {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub turn_number: u32,
+}
+
+#[event]
+pub struct RoundResolved {
+    pub game_id: u64,
+    pub turn_number: u32,
+    pub moves_applied: u8,
+    pub attacks_applied: u8,
+}
+
+/// Extra starting gold for a wallet on a losing streak
+#[event]
+pub struct PityBonusGranted {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub gold: u32,
+    pub losing_streak: u16,
+}
+
+#[event]
+pub struct ProfileProgressed {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub xp_gained: u64,
+    pub total_xp: u64,
+    pub level: u8,
+    pub leveled_up: bool,
+    pub reputation: u16,
+}
+
+#[event]
+pub struct ShipSunk {
+    pub game_id: u64,
+    pub owner: Pubkey,
+    pub ship_id: String,
+    pub ship_type: ShipType,
+    pub wreck_x: u8,
+    pub wreck_y: u8,
+    pub turn_number: u32,
+}
+
+#[event]
+pub struct ConfigUpdated {
+    pub authority: Pubkey,
+    pub params: ConfigParams,
+}
+
+#[event]
+pub struct RulebookUpdated {
+    pub mode: GameMode,
+    pub version: u32,
+    pub params: RulebookParams,
+}
+
+#[event]
+pub struct RevenueSplitUpdated {
+    pub split: RevenueSplit,
+}
+
+/// One sweep of the revenue splitter
+#[event]
+pub struct FeesDistributed {
+    pub amount: u64,
+    pub dev: u64,
+    pub prize_pool: u64,
+    pub jackpot: u64,
+    pub total_distributed: u64,
+}
+
+#[event]
+pub struct FeeSessionOpened {
+    pub game_id: u64,
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    pub budget_lamports: u64,
+}
+
+#[event]
+pub struct FeeSessionClosed {
+    pub game_id: u64,
+    pub owner: Pubkey,
+    pub spent_lamports: u64,
+    pub refunded_to_pot: u64,
+}
+
+#[event]
+pub struct PayoutClaimed {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub rank: u8,
+    pub payout: u64, // Paid now, after the platform fee
+    pub vested: u64, // Held back for release_vested_prize; see state::vesting
+    pub platform_fee: u64,
+    pub jackpot_contribution: u64,
+    pub token_payout: u64,
+    pub rebate: u64,         // Fee-free move rebate paid alongside the payout
+    pub destination: Pubkey, // Where the SOL payout went
+    pub memo: String,        // Claimant's tag, e.g. a tournament name
+}
+
+/// Half of a large payout held back until `unlocks_at`
+#[event]
+pub struct PrizeVesting {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub amount: u64,
+    pub unlocks_at: i64,
+}
+
+#[event]
+pub struct VestedPrizeReleased {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+/// The config authority froze or thawed a winner's unvested tranche
+#[event]
+pub struct VestingFrozen {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub frozen: bool,
+    pub frozen_until: i64, // When the freeze lapses on its own; 0 when thawed
+}
+
+/// Totals for a `claim_all_payouts` call; each game also emits `PayoutClaimed`
+#[event]
+pub struct PayoutsClaimed {
+    pub player: Pubkey,
+    pub games: u8,
+    pub total_payout: u64,
+}
+
+#[event]
+pub struct PrizeDistributorCreated {
+    pub distributor: Pubkey,
+    pub organizer: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub total: u64,
+    pub reclaim_after: i64,
+}
+
+#[event]
+pub struct PrizeClaimed {
+    pub distributor: Pubkey,
+    pub claimant: Pubkey,
+    pub index: u32,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PrizeDistributorClosed {
+    pub distributor: Pubkey,
+    pub organizer: Pubkey,
+    pub claim_count: u32,
+    pub reclaimed: u64,
+}
+
+#[event]
+pub struct GameClosed {
+    pub game_id: u64,
+    pub rent_returned_to: Pubkey,
+}
+
+#[event]
+pub struct CreatorFeeClaimed {
+    pub game_id: u64,
+    pub creator: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct JackpotWon {
+    pub game_id: u64,
+    pub winner: Pubkey,
+    pub amount: u64,
+    pub odds_bps: u32,
+}
+
+/// First place's jackpot roll waits on the hash of `reveal_slot`
+#[event]
+pub struct JackpotRollCommitted {
+    pub game_id: u64,
+    pub winner: Pubkey,
+    pub odds_bps: u32,
+    pub reveal_slot: u64,
+}
+
+/// A jackpot roll went unrevealed until its slot hash aged out, and was lost
+#[event]
+pub struct JackpotRollLapsed {
+    pub game_id: u64,
+    pub winner: Pubkey,
+}
+
+#[event]
+pub struct PlayerTimedOut {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub turn_number: u32,
+}
+
+#[event]
+pub struct ResignationCancelled {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub turn_number: u32,
+}
+
+#[event]
+pub struct PlayerResigned {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub turn_number: u32,
+}
+
+/// A capture-the-flagship player lost their flagship and now only spectates
+#[event]
+pub struct PlayerEliminated {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub flagship_id: String,
+    pub turn_number: u32,
+}
+
+/// The Golden Port scored for its holder at the end of a king-of-the-hill round
+#[event]
+pub struct VictoryPointsScored {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub points: u16,
+    pub total: u16,
+    pub turn_number: u32,
+}
+
+#[event]
+pub struct CargoLoaded {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub ship_id: String,
+    pub gold: u32,
+    pub cargo_gold: u32,
+}
+
+#[event]
+pub struct CargoDelivered {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub ship_id: String,
+    pub gold: u32,
+    pub delivered_gold: u32,
+}
+
+/// A sunk ship's cargo; `salvageable` is what was left on the wreck cell
+#[event]
+pub struct CargoSpilled {
+    pub game_id: u64,
+    pub owner: Pubkey,
+    pub ship_id: String,
+    pub gold: u32,
+    pub salvageable: u32,
+    pub x: u8,
+    pub y: u8,
+}
+
+/// A spectator's one-off squall: every ship around the cell takes light damage
+#[event]
+pub struct GhostCurseCast {
+    pub game_id: u64,
+    pub spectator: Pubkey,
+    pub x: u8,
+    pub y: u8,
+    pub ships_hit: u8,
+    pub turn_number: u32,
+}
+
+#[event]
+pub struct ContractOffered {
+    pub game_id: u64,
+    pub contract: Contract,
+}
+
+#[event]
+pub struct ContractAccepted {
+    pub game_id: u64,
+    pub contract_id: u16,
+    pub player: Pubkey,
+    pub deadline_turn: u32,
+}
+
+#[event]
+pub struct ContractCompleted {
+    pub game_id: u64,
+    pub contract_id: u16,
+    pub player: Pubkey,
+    pub reward_gold: u32,
+}
+
+/// Lapsed at its deadline, taken or not
+#[event]
+pub struct ContractExpired {
+    pub game_id: u64,
+    pub contract_id: u16,
+    pub holder: Option<Pubkey>,
+}
+
+#[event]
+pub struct ParleyCalled {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub turn_number: u32,
+    pub deadline: i64, // The player's new move deadline
+}
+
+#[event]
+pub struct RaiseProposed {
+    pub game_id: u64,
+    pub proposer: Pubkey,
+    pub amount: u64,
+    pub expires_turn: u32,
+}
+
+#[event]
+pub struct RaiseAccepted {
+    pub game_id: u64,
+    pub player: Pubkey,
+}
+
+/// Every active captain backed the raise; the escrow has joined the pot
+#[event]
+pub struct StakesRaised {
+    pub game_id: u64,
+    pub stake_per_player: u64,
+    pub total_pot: u64,
+}
+
+#[event]
+pub struct PotTipped {
+    pub game_id: u64,
+    pub tipper: Pubkey,
+    pub amount: u64,
+    pub tips_total: u64,
+    pub total_pot: u64,
+}
+
+#[event]
+pub struct LivenessDepositSettled {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub amount: u64,
+    pub refunded: bool, // False if it was forfeited to the crank fund
+    pub crank_tip: u64,
+}
+
+#[event]
+pub struct DecisionTimeRanked {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub average_decision_ms: u64,
+    pub rank: Option<u8>, // None if the time did not make the board
+}
+
+#[event]
+pub struct LobbyNamed {
+    pub game_id: u64,
+    pub name: String,
+}
+
+#[event]
+pub struct RaiseCancelled {
+    pub game_id: u64,
+    pub cancelled_by: Pubkey,
+    pub refunded: u64,
+}
+
+#[event]
+pub struct ShipSkinSet {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub ship_id: String,
+    pub nft_mint: Pubkey,
+    pub collection: Pubkey,
+}
+
+#[event]
+pub struct TutorialCheckpointReached {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub checkpoint: TutorialCheckpoint,
+    pub checkpoints_reached: u8,
+}
+
+#[event]
+pub struct OvertimeStarted {
+    pub game_id: u64,
+    pub players: Vec<Pubkey>,
+    pub ends_at_turn: u32,
+}
+
+#[event]
+pub struct GameDrawn {
+    pub game_id: u64,
+    pub co_winners: Vec<Pubkey>,
+    pub final_state_hash: [u8; 32],
+}
+
+/// A game's vault ledger, once `reconcile_vault` has matched it to the
+/// balance; also the instruction's return data
+#[event]
+#[derive(Clone)]
+pub struct VaultReconciled {
+    pub game_id: u64,
+    pub balance: u64, // Lamports held above rent exemption
+    pub total_deposited: u64,
+    pub total_paid_out: u64,
+    pub fees_accrued: u64,
+}
+
+/// One slice of an `export_state` snapshot; also the instruction's return data
+#[event]
+#[derive(Clone)]
+pub struct StateExported {
+    pub game_id: u64,
+    pub version: u8,
+    pub chunk_index: u16,
+    pub chunk_count: u16,
+    pub blob_len: u32,
+    pub blob_hash: [u8; 32],
+    pub data: Vec<u8>,
+}
+
+/// Compact tagged-binary mirror update; see `state::diff` for the payload layout
+#[event]
+pub struct StateDiff {
+    pub game_id: u64,
+    pub turn_number: u32,
+    pub data: Vec<u8>,
+}
+
+/// Everything one instruction changed, for games with compact events
+#[event]
+pub struct TurnSummary {
+    pub game_id: u64,
+    pub turn_number: u32,
+    pub current_player_index: u8,
+    pub status: u8,
+    pub ships: Vec<ShipDelta>,
+    pub resources: Vec<ResourceDelta>,
+    pub cells: Vec<CellDelta>,
+    pub scores: Vec<u32>, // Every seat's score under the game's weights
+}
@@ -1,6 +1,12 @@
 use crate::constants::GAME_SEED;
 use crate::errors::GameError;
-use crate::state::game::{GameStatus, PirateGame};
+use crate::events::{ConfigUpdated, RulebookUpdated};
+use crate::invariants::check_invariants;
+use crate::state::config::{validate_config_params, ConfigParams, GameConfig, CONFIG_SEED};
+use crate::state::game::{GameMode, GameStatus, PirateGame};
+use crate::state::rng::{RngAudit, RngStream};
+use crate::state::rulebook::{rulebook_seed, Rulebook, RulebookParams, RULEBOOK_SEED};
+use crate::state::simultaneous::RoundPhase;
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -25,10 +31,55 @@ pub fn reset_game(ctx: Context<ResetGame>) -> Result<()> {
         GameError::Unauthorized
     );
 
+    // Escrowed raise lamports belong to their backers; refund them first
+    require!(game.pending_raise.is_none(), GameError::RaisePending);
+    // ...and liveness deposits to their seats, which are about to be cleared
+    require!(game.liveness_escrow() == 0, GameError::LivenessDepositsHeld);
+    // ...and vesting prizes to their winners
+    require!(game.vesting.is_empty(), GameError::PrizeStillVesting);
+    // ...and fee session budgets to the pot
+    require!(game.open_fee_sessions == 0, GameError::FeeSessionsOpen);
+    // ...and a committed jackpot roll to its winner
+    require!(
+        game.pending_jackpot.is_none(),
+        GameError::JackpotRollPending
+    );
+    // ...and never while entry fees or unclaimed payouts are still in play
+    require!(game.is_resettable(), GameError::GameNotResettable);
+
     game.status = GameStatus::Waiting;
     game.player_count = 0;
     game.current_player_index = 0;
     game.turn_number = 0;
+    game.seed = 0;
+    game.rng_audit = RngAudit::default();
+    game.rng = RngStream::default();
+    game.start_reveal_slot = None;
+    game.turn_order.clear();
+    game.turn_slot = 0;
+    game.turn_started_at = 0;
+    game.scanned_this_turn = false;
+    game.parley_this_turn = false;
+    game.round_phase = RoundPhase::Commit;
+    game.pending_orders.clear();
+    game.spawn_positions.clear();
+    game.construction_queue.clear();
+    game.contracts.clear();
+    game.next_contract_id = 0;
+    game.overtime_players.clear();
+    game.standings.clear();
+    game.payout_claims = 0;
+    game.final_state_hash = [0; 32];
+    game.payout_pot = 0;
+    game.payout_token_pot = 0;
+    game.creator_fee_due = 0;
+    game.rebate_pool = 0;
+    game.stake_raises = 0;
+    game.pending_resignation = None;
+    game.tutorial_checkpoint = 0;
+    game.forked_from = None;
+    game.adopt_rulebook(None);
+    game.signer_log.clear();
     game.started_at = None;
     game.completed_at = None;
     game.winner = None;
@@ -36,5 +87,138 @@ pub fn reset_game(ctx: Context<ResetGame>) -> Result<()> {
     game.territory_map.clear();
 
     msg!("Game reset at {}", clock.unix_timestamp);
+    check_invariants(game)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        seeds = [CONFIG_SEED],
+        bump,
+        payer = authority,
+        space = GameConfig::SPACE
+    )]
+    pub config: Account<'info, GameConfig>,
+    /// Only the program's upgrade authority may create the config
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::Pir8Game>,
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+            @ GameError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = authority
+    )]
+    pub config: Account<'info, GameConfig>,
+    pub authority: Signer<'info>,
+}
+
+pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.authority = ctx.accounts.authority.key();
+    config.params = ConfigParams::default();
+    config.bump = ctx.bumps.config;
+
+    emit!(ConfigUpdated {
+        authority: config.authority,
+        params: config.params.clone(),
+    });
+
+    Ok(())
+}
+
+pub fn update_config(ctx: Context<UpdateConfig>, params: ConfigParams) -> Result<()> {
+    require!(validate_config_params(&params), GameError::InvalidConfig);
+
+    let config = &mut ctx.accounts.config;
+    config.params = params.clone();
+
+    emit!(ConfigUpdated {
+        authority: config.authority,
+        params,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(mode: GameMode)]
+pub struct InitializeRulebook<'info> {
+    #[account(
+        init,
+        seeds = [RULEBOOK_SEED, rulebook_seed(&mode).as_ref()],
+        bump,
+        payer = authority,
+        space = Rulebook::SPACE
+    )]
+    pub rulebook: Account<'info, Rulebook>,
+    /// Rulebooks belong to whoever holds the balance config
+    #[account(seeds = [CONFIG_SEED], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, GameConfig>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRulebook<'info> {
+    #[account(
+        mut,
+        seeds = [RULEBOOK_SEED, rulebook_seed(&rulebook.mode).as_ref()],
+        bump = rulebook.bump,
+        has_one = authority
+    )]
+    pub rulebook: Account<'info, Rulebook>,
+    pub authority: Signer<'info>,
+}
+
+/// Open a mode's rulebook at version 1 with the built-in balance values
+pub fn initialize_rulebook(ctx: Context<InitializeRulebook>, mode: GameMode) -> Result<()> {
+    let rulebook = &mut ctx.accounts.rulebook;
+    rulebook.authority = ctx.accounts.authority.key();
+    rulebook.mode = mode;
+    rulebook.version = 1;
+    rulebook.params = RulebookParams::default();
+    rulebook.bump = ctx.bumps.rulebook;
+
+    emit!(RulebookUpdated {
+        mode: rulebook.mode.clone(),
+        version: rulebook.version,
+        params: rulebook.params.clone(),
+    });
+
+    Ok(())
+}
+
+/// Patch a mode's balance values. Only games started afterwards see them.
+pub fn update_rulebook(ctx: Context<UpdateRulebook>, params: RulebookParams) -> Result<()> {
+    require!(params.is_valid(), GameError::InvalidRulebook);
+
+    let rulebook = &mut ctx.accounts.rulebook;
+    rulebook.version = rulebook
+        .version
+        .checked_add(1)
+        .ok_or(GameError::ArithmeticOverflow)?;
+    rulebook.params = params.clone();
+
+    emit!(RulebookUpdated {
+        mode: rulebook.mode.clone(),
+        version: rulebook.version,
+        params,
+    });
+
     Ok(())
 }
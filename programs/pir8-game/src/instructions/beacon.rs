@@ -0,0 +1,46 @@
+use crate::state::beacon::{TurnBeacon, TURN_BEACON_SEED};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct OpenTurnBeacon<'info> {
+    #[account(
+        init,
+        payer = player,
+        space = TurnBeacon::SPACE,
+        seeds = [TURN_BEACON_SEED, player.key().as_ref()],
+        bump
+    )]
+    pub beacon: Account<'info, TurnBeacon>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseTurnBeacon<'info> {
+    #[account(
+        mut,
+        seeds = [TURN_BEACON_SEED, player.key().as_ref()],
+        bump = beacon.bump,
+        has_one = player,
+        close = player
+    )]
+    pub beacon: Account<'info, TurnBeacon>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+}
+
+/// Open the caller's turn beacon. It starts pointing at no game and rings
+/// once a turn-changing instruction is sent with it, as the `beacon` account
+/// or among the remaining accounts.
+pub fn open_turn_beacon(ctx: Context<OpenTurnBeacon>) -> Result<()> {
+    let beacon = &mut ctx.accounts.beacon;
+    beacon.player = ctx.accounts.player.key();
+    beacon.bump = ctx.bumps.beacon;
+    Ok(())
+}
+
+/// Close the caller's beacon and return its rent
+pub fn close_turn_beacon(_ctx: Context<CloseTurnBeacon>) -> Result<()> {
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use crate::errors::GameError;
+use crate::events::{AgentBondChanged, AgentStruck};
+use crate::state::game::PirateGame;
+use crate::state::player::AgentRegistry;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{program::invoke, system_instruction};
+
+// ============================================================================
+// AGENT OPERATOR BONDS
+// ============================================================================
+//
+// An operator can post a refundable bond into their AgentRegistry, and
+// tournament lobbies may demand a minimum. Joining a game locks the bond for
+// AGENT_BOND_LOCK_SECONDS. Every timeout or missed reveal by a bonded seat
+// is a strike against the agent; after the free strikes, each one slashes a
+// share of the bond into the pot of the game it happened in.
+
+#[derive(Accounts)]
+pub struct ManageBond<'info> {
+    #[account(
+        mut,
+        seeds = [b"agent", owner.key().as_ref()],
+        bump,
+        has_one = owner
+    )]
+    pub agent: Account<'info, AgentRegistry>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn post_bond(ctx: Context<ManageBond>, amount: u64) -> Result<()> {
+    require!(amount > 0, GameError::InvalidBondAmount);
+
+    let owner = &ctx.accounts.owner;
+    let agent = &ctx.accounts.agent;
+    invoke(
+        &system_instruction::transfer(&owner.key(), &agent.key(), amount),
+        &[
+            owner.to_account_info(),
+            agent.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+    )?;
+
+    let agent = &mut ctx.accounts.agent;
+    agent.bond = agent.bond.saturating_add(amount);
+
+    emit!(AgentBondChanged {
+        agent: agent.owner,
+        posted: amount,
+        withdrawn: 0,
+        bond: agent.bond,
+    });
+    Ok(())
+}
+
+pub fn withdraw_bond(ctx: Context<ManageBond>, amount: u64) -> Result<()> {
+    let agent = &mut ctx.accounts.agent;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        amount > 0 && amount <= agent.bond,
+        GameError::InvalidBondAmount
+    );
+    require!(now >= agent.bond_locked_until, GameError::AgentBondLocked);
+
+    agent.bond -= amount;
+    agent.sub_lamports(amount)?;
+    ctx.accounts.owner.add_lamports(amount)?;
+
+    emit!(AgentBondChanged {
+        agent: agent.owner,
+        posted: 0,
+        withdrawn: amount,
+        bond: agent.bond,
+    });
+    Ok(())
+}
+
+/// Record a strike against each bonded seat in `struck`, moving any slash
+/// into the pot. Their registries must be among `registries`, writable.
+pub fn strike_agents<'info>(
+    game: &mut Account<'info, PirateGame>,
+    struck: &[Pubkey],
+    registries: &'info [AccountInfo<'info>],
+) -> Result<()> {
+    for player in struck {
+        let bonded = game
+            .players
+            .iter()
+            .any(|p| p.pubkey == *player && p.bonded_agent);
+        if !bonded {
+            continue;
+        }
+
+        let mut agent = registries
+            .iter()
+            .filter(|info| info.is_writable)
+            .filter_map(|info| Account::<AgentRegistry>::try_from(info).ok())
+            .find(|agent| agent.owner == *player)
+            .ok_or(GameError::AgentBondAccountMissing)?;
+
+        let slashed = agent.record_strike();
+        if slashed > 0 {
+            agent.sub_lamports(slashed)?;
+            game.add_lamports(slashed)?;
+            game.total_pot = game.total_pot.saturating_add(slashed);
+            game.ledger.deposit(slashed);
+        }
+        agent.exit(&crate::ID)?;
+
+        emit!(AgentStruck {
+            game_id: game.game_id,
+            agent: *player,
+            strikes: agent.strikes,
+            slashed,
+            bond: agent.bond,
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use crate::events::ContractAccepted;
+use crate::instructions::gameplay::MakeMove;
+use crate::invariants::check_invariants;
+use crate::state::contract::take_contract;
+use crate::state::diff::{emit_state_diff, StateSnapshot};
+use anchor_lang::prelude::*;
+
+/// Take one of the game's open privateer offers; see `state::contract`.
+/// Captains may accept on anyone's turn.
+pub fn accept_contract(ctx: Context<MakeMove>, contract_id: u16) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let snapshot = StateSnapshot::capture(game);
+    let player = ctx.accounts.player.key();
+
+    let deadline_turn = take_contract(game, player, contract_id)?;
+
+    emit!(ContractAccepted {
+        game_id: game.game_id,
+        contract_id,
+        player,
+        deadline_turn,
+    });
+
+    emit_state_diff(&snapshot, game);
+    check_invariants(game)?;
+    Ok(())
+}
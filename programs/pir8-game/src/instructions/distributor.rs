@@ -0,0 +1,176 @@
+use crate::errors::GameError;
+use crate::events::{PrizeClaimed, PrizeDistributorClosed, PrizeDistributorCreated};
+use crate::state::distributor::{
+    prize_leaf, verify_prize_proof, PrizeClaim, PrizeDistributor, PRIZE_CLAIM_SEED,
+    PRIZE_DISTRIBUTOR_SEED,
+};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{program::invoke, system_instruction};
+
+#[derive(Accounts)]
+#[instruction(distributor_id: u64)]
+pub struct CreatePrizeDistributor<'info> {
+    #[account(
+        init,
+        payer = organizer,
+        space = PrizeDistributor::SPACE,
+        seeds = [
+            PRIZE_DISTRIBUTOR_SEED,
+            organizer.key().as_ref(),
+            distributor_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub distributor: Account<'info, PrizeDistributor>,
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(index: u32)]
+pub struct ClaimPrize<'info> {
+    #[account(
+        mut,
+        seeds = [
+            PRIZE_DISTRIBUTOR_SEED,
+            distributor.organizer.as_ref(),
+            distributor.distributor_id.to_le_bytes().as_ref()
+        ],
+        bump = distributor.bump
+    )]
+    pub distributor: Account<'info, PrizeDistributor>,
+    #[account(
+        init,
+        payer = claimant,
+        space = PrizeClaim::SPACE,
+        seeds = [PRIZE_CLAIM_SEED, distributor.key().as_ref(), index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub claim: Account<'info, PrizeClaim>,
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePrizeDistributor<'info> {
+    #[account(
+        mut,
+        seeds = [
+            PRIZE_DISTRIBUTOR_SEED,
+            organizer.key().as_ref(),
+            distributor.distributor_id.to_le_bytes().as_ref()
+        ],
+        bump = distributor.bump,
+        has_one = organizer,
+        close = organizer
+    )]
+    pub distributor: Account<'info, PrizeDistributor>,
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+}
+
+/// Fund a distributor with `total` lamports, payable to the leaves under
+/// `merkle_root`; see `state::distributor`
+pub fn create_prize_distributor(
+    ctx: Context<CreatePrizeDistributor>,
+    distributor_id: u64,
+    merkle_root: [u8; 32],
+    total: u64,
+    reclaim_after: i64,
+) -> Result<()> {
+    require!(total > 0, GameError::NothingToClaim);
+
+    let organizer = &ctx.accounts.organizer;
+    let distributor = &ctx.accounts.distributor;
+    invoke(
+        &system_instruction::transfer(&organizer.key(), &distributor.key(), total),
+        &[
+            organizer.to_account_info(),
+            distributor.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+    )?;
+
+    let distributor = &mut ctx.accounts.distributor;
+    distributor.organizer = organizer.key();
+    distributor.distributor_id = distributor_id;
+    distributor.merkle_root = merkle_root;
+    distributor.total = total;
+    distributor.claimed = 0;
+    distributor.claim_count = 0;
+    distributor.reclaim_after = reclaim_after;
+    distributor.bump = ctx.bumps.distributor;
+
+    emit!(PrizeDistributorCreated {
+        distributor: distributor.key(),
+        organizer: distributor.organizer,
+        merkle_root,
+        total,
+        reclaim_after,
+    });
+    Ok(())
+}
+
+/// Pay the caller their leaf. The receipt PDA for `index` can only be
+/// created once, so each leaf pays out once.
+pub fn claim_prize(
+    ctx: Context<ClaimPrize>,
+    index: u32,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let claimant = ctx.accounts.claimant.key();
+    let distributor = &mut ctx.accounts.distributor;
+
+    let leaf = prize_leaf(index, &claimant, amount);
+    require!(
+        verify_prize_proof(&proof, &distributor.merkle_root, leaf),
+        GameError::InvalidPrizeProof
+    );
+    let claimed = distributor
+        .claimed
+        .checked_add(amount)
+        .filter(|claimed| *claimed <= distributor.total)
+        .ok_or(GameError::PrizeFundsExhausted)?;
+
+    distributor.claimed = claimed;
+    distributor.claim_count = distributor.claim_count.saturating_add(1);
+    distributor.sub_lamports(amount)?;
+    ctx.accounts.claimant.add_lamports(amount)?;
+
+    let claim = &mut ctx.accounts.claim;
+    claim.distributor = distributor.key();
+    claim.index = index;
+    claim.claimant = claimant;
+    claim.amount = amount;
+    claim.bump = ctx.bumps.claim;
+
+    emit!(PrizeClaimed {
+        distributor: distributor.key(),
+        claimant,
+        index,
+        amount,
+    });
+    Ok(())
+}
+
+/// Once the reclaim time has passed, close the distributor and return the
+/// unclaimed prizes and rent to the organizer
+pub fn close_prize_distributor(ctx: Context<ClosePrizeDistributor>) -> Result<()> {
+    let distributor = &ctx.accounts.distributor;
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now >= distributor.reclaim_after,
+        GameError::PrizeClaimsStillOpen
+    );
+
+    emit!(PrizeDistributorClosed {
+        distributor: distributor.key(),
+        organizer: distributor.organizer,
+        claim_count: distributor.claim_count,
+        reclaimed: distributor.total - distributor.claimed,
+    });
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use crate::constants::*;
+use crate::errors::GameError;
+use crate::events::StateExported;
+use crate::state::export::{encode_state, export_chunk, STATE_EXPORT_VERSION};
+use crate::state::game::PirateGame;
+use anchor_lang::prelude::*;
+
+/// Read-only snapshot of a game; meant to be simulated, chunk by chunk
+#[derive(Accounts)]
+pub struct ExportState<'info> {
+    #[account(
+        seeds = [GAME_SEED, game.game_id.to_le_bytes().as_ref()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, PirateGame>,
+}
+
+/// Serve one chunk of the canonical state blob as return data (and an event
+/// for indexers). Clients request chunks 0..chunk_count and check every
+/// chunk carries the same blob hash before reassembling.
+pub fn export_state(ctx: Context<ExportState>, chunk_index: u16) -> Result<StateExported> {
+    let game = &ctx.accounts.game;
+    let blob = encode_state(game);
+    let chunk = export_chunk(&blob, chunk_index).ok_or(GameError::ExportChunkOutOfRange)?;
+
+    let exported = StateExported {
+        game_id: game.game_id,
+        version: STATE_EXPORT_VERSION,
+        chunk_index,
+        chunk_count: chunk.chunk_count,
+        blob_len: chunk.blob_len,
+        blob_hash: chunk.blob_hash,
+        data: chunk.data.to_vec(),
+    };
+    emit!(exported.clone());
+    Ok(exported)
+}
@@ -0,0 +1,61 @@
+use crate::constants::*;
+use crate::errors::GameError;
+use crate::events::GameForked;
+use crate::invariants::check_invariants;
+use crate::state::fork::practice_fork;
+use crate::state::game::{GameStatus, PirateGame};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct ForkGame<'info> {
+    #[account(
+        seeds = [GAME_SEED, source.game_id.to_le_bytes().as_ref()],
+        bump = source.bump
+    )]
+    pub source: Account<'info, PirateGame>,
+    #[account(
+        init,
+        seeds = [GAME_SEED, game_id.to_le_bytes().as_ref()],
+        bump,
+        payer = player,
+        space = PirateGame::SPACE
+    )]
+    pub game: Account<'info, PirateGame>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Copy a started or completed game into a new practice game; see
+/// `state::fork`. Anyone may fork, paying the new account's rent.
+pub fn fork_game(ctx: Context<ForkGame>, game_id: u64) -> Result<()> {
+    let source = &ctx.accounts.source;
+    require!(
+        matches!(source.status, GameStatus::Active | GameStatus::Completed),
+        GameError::GameNotForkable
+    );
+
+    let forker = ctx.accounts.player.key();
+    let now = Clock::get()?.unix_timestamp;
+    let fork = practice_fork(source, game_id, forker, ctx.bumps.game, now);
+
+    let game = &mut ctx.accounts.game;
+    game.set_inner(fork);
+
+    msg!(
+        "Game {} forked from {} at turn {}",
+        game_id,
+        source.game_id,
+        game.turn_number
+    );
+    emit!(GameForked {
+        source_game_id: source.game_id,
+        game_id,
+        forked_by: forker,
+        turn_number: game.turn_number,
+    });
+
+    check_invariants(game)?;
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use crate::constants::*;
+use crate::errors::GameError;
+use crate::events::{InviteClosed, InviteSent};
+use crate::instructions::matchmaking::join_with_entry_fee;
+use crate::state::config::{GameConfig, CONFIG_SEED};
+use crate::state::game::PirateGame;
+use crate::state::invite::{Invite, INVITE_SEED};
+use crate::state::player::AgentRegistry;
+use crate::state::profile::{PlayerProfile, PROFILE_SEED};
+use crate::state::rulebook::{rulebook_seed, Rulebook, RULEBOOK_SEED};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(target: Pubkey)]
+pub struct SendInvite<'info> {
+    #[account(
+        seeds = [GAME_SEED, game.game_id.to_le_bytes().as_ref()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, PirateGame>,
+    #[account(
+        init,
+        payer = sender,
+        space = Invite::SPACE,
+        seeds = [INVITE_SEED, target.as_ref(), game.game_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub invite: Account<'info, Invite>,
+    #[account(mut)]
+    pub sender: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptInvite<'info> {
+    #[account(
+        mut,
+        close = sender,
+        seeds = [INVITE_SEED, target.key().as_ref(), game.game_id.to_le_bytes().as_ref()],
+        bump = invite.bump,
+        has_one = target,
+        has_one = sender
+    )]
+    pub invite: Account<'info, Invite>,
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.game_id.to_le_bytes().as_ref()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, PirateGame>,
+    #[account(
+        init_if_needed,
+        payer = target,
+        space = PlayerProfile::SPACE,
+        seeds = [PROFILE_SEED, target.key().as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+    /// Required by agents_only lobbies
+    #[account(seeds = [b"agent", target.key().as_ref()], bump)]
+    pub agent: Option<Account<'info, AgentRegistry>>,
+    /// Join throttle; defaults apply when omitted
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Option<Account<'info, GameConfig>>,
+    /// The mode's rulebook, copied into the game if this join fills the lobby;
+    /// built-in rules apply when omitted
+    #[account(seeds = [RULEBOOK_SEED, rulebook_seed(&game.mode).as_ref()], bump = rulebook.bump)]
+    pub rulebook: Option<Account<'info, Rulebook>>,
+    /// CHECK: receives the invite's rent; matched by has_one on the invite
+    #[account(mut)]
+    pub sender: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub target: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DeclineInvite<'info> {
+    #[account(
+        mut,
+        close = sender,
+        has_one = target,
+        has_one = sender
+    )]
+    pub invite: Account<'info, Invite>,
+    /// CHECK: receives the invite's rent; matched by has_one on the invite
+    #[account(mut)]
+    pub sender: UncheckedAccount<'info>,
+    pub target: Signer<'info>,
+}
+
+/// Drop an invitation to `target` into their inbox. Only the lobby's
+/// creator or a seated player may invite, and only while it is open.
+pub fn send_invite(ctx: Context<SendInvite>, target: Pubkey) -> Result<()> {
+    let game = &ctx.accounts.game;
+    let sender = ctx.accounts.sender.key();
+
+    require!(game.is_joinable(), GameError::GameNotJoinable);
+    require!(
+        sender == game.authority || game.players.iter().any(|p| p.pubkey == sender),
+        GameError::PlayerNotInGame
+    );
+
+    let invite = &mut ctx.accounts.invite;
+    invite.target = target;
+    invite.sender = sender;
+    invite.game_id = game.game_id;
+    invite.sent_at = Clock::get()?.unix_timestamp;
+    invite.bump = ctx.bumps.invite;
+
+    emit!(InviteSent {
+        game_id: game.game_id,
+        sender,
+        target,
+    });
+    Ok(())
+}
+
+/// Join the game an invite points at, closing the invite. The lobby's
+/// access rules still apply, so invite-only lobbies need their code.
+pub fn accept_invite(ctx: Context<AcceptInvite>, invite_code: Option<String>) -> Result<()> {
+    let profile = &mut ctx.accounts.profile;
+    profile.ensure_initialized(ctx.accounts.target.key(), ctx.bumps.profile);
+
+    join_with_entry_fee(
+        &mut ctx.accounts.game,
+        &ctx.accounts.target,
+        &ctx.accounts.system_program,
+        profile,
+        ctx.accounts.agent.as_deref(),
+        ctx.accounts.config.as_deref(),
+        ctx.accounts.rulebook.as_deref(),
+        invite_code.as_deref(),
+    )?;
+
+    emit!(InviteClosed {
+        game_id: ctx.accounts.invite.game_id,
+        sender: ctx.accounts.invite.sender,
+        target: ctx.accounts.invite.target,
+        accepted: true,
+    });
+    Ok(())
+}
+
+/// Turn an invite down, returning its rent to the sender
+pub fn decline_invite(ctx: Context<DeclineInvite>) -> Result<()> {
+    let invite = &ctx.accounts.invite;
+    emit!(InviteClosed {
+        game_id: invite.game_id,
+        sender: invite.sender,
+        target: invite.target,
+        accepted: false,
+    });
+    Ok(())
+}
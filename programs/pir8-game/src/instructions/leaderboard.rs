@@ -0,0 +1,80 @@
+use crate::constants::*;
+use crate::errors::GameError;
+use crate::events::DecisionTimeRanked;
+use crate::state::game::{GameObjective, GameStatus, PirateGame};
+use crate::state::leaderboard::{
+    DecisionLeaderboard, LeaderboardEntry, DECISION_LEADERBOARD_SEED, MIN_RANKED_DECISIONS,
+};
+use anchor_lang::prelude::*;
+
+/// Submit a winner of a completed game to the decision-time leaderboard.
+/// Permissionless so cranks can rank every winner once the game ends.
+#[derive(Accounts)]
+pub struct SubmitDecisionTime<'info> {
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = DecisionLeaderboard::SPACE,
+        seeds = [DECISION_LEADERBOARD_SEED],
+        bump
+    )]
+    pub leaderboard: Account<'info, DecisionLeaderboard>,
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.game_id.to_le_bytes().as_ref()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, PirateGame>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn submit_decision_time(ctx: Context<SubmitDecisionTime>, player: Pubkey) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    require!(
+        game.status == GameStatus::Completed,
+        GameError::GameNotCompleted
+    );
+    require!(
+        game.options.objective != GameObjective::Tutorial,
+        GameError::WrongObjective
+    );
+    require!(game.forked_from.is_none(), GameError::PracticeGame);
+    require!(game.is_winner(&player), GameError::NotWinner);
+
+    let game_id = game.game_id;
+    let seat = game
+        .players
+        .iter_mut()
+        .find(|p| p.pubkey == player)
+        .ok_or(GameError::PlayerNotInGame)?;
+    require!(
+        !seat.decision_time_ranked,
+        GameError::DecisionTimeAlreadyRanked
+    );
+    require!(
+        seat.clocked_decisions >= MIN_RANKED_DECISIONS,
+        GameError::TooFewClockedDecisions
+    );
+    seat.decision_time_ranked = true;
+
+    let entry = LeaderboardEntry {
+        player,
+        game_id,
+        average_decision_ms: seat.clocked_decision_ms().unwrap_or_default(),
+        decisions: seat.clocked_decisions,
+    };
+    let average_decision_ms = entry.average_decision_ms;
+    let leaderboard = &mut ctx.accounts.leaderboard;
+    leaderboard.bump = ctx.bumps.leaderboard;
+    let rank = leaderboard.submit(entry).map(|rank| rank as u8);
+
+    emit!(DecisionTimeRanked {
+        game_id,
+        player,
+        average_decision_ms,
+        rank,
+    });
+    Ok(())
+}
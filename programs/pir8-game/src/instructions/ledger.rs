@@ -0,0 +1,45 @@
+use crate::constants::*;
+use crate::errors::GameError;
+use crate::events::VaultReconciled;
+use crate::state::game::PirateGame;
+use anchor_lang::prelude::*;
+
+/// Read-only check of a game's vault against its ledger
+#[derive(Accounts)]
+pub struct ReconcileVault<'info> {
+    #[account(
+        seeds = [GAME_SEED, game.game_id.to_le_bytes().as_ref()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, PirateGame>,
+}
+
+/// Fail unless the lamports held above rent exemption equal what the ledger
+/// says came in less what went out, and that in turn equals the pot plus any
+/// raise escrow and liveness deposits. On success the ledger is served as return data (and an
+/// event), so monitoring can simulate this against every open game.
+pub fn reconcile_vault(ctx: Context<ReconcileVault>) -> Result<VaultReconciled> {
+    let game = &ctx.accounts.game;
+    let info = game.to_account_info();
+    let rent_exempt = Rent::get()?.minimum_balance(info.data_len());
+    let held = info.lamports().saturating_sub(rent_exempt);
+
+    let balance = game
+        .ledger
+        .balance()
+        .ok_or(GameError::VaultLedgerMismatch)?;
+    require!(
+        held == balance && balance == game.total_pot.saturating_add(game.held_in_escrow()),
+        GameError::VaultLedgerMismatch
+    );
+
+    let reconciled = VaultReconciled {
+        game_id: game.game_id,
+        balance,
+        total_deposited: game.ledger.total_deposited,
+        total_paid_out: game.ledger.total_paid_out,
+        fees_accrued: game.ledger.fees_accrued,
+    };
+    emit!(reconciled.clone());
+    Ok(reconciled)
+}
@@ -0,0 +1,190 @@
+use crate::constants::*;
+use crate::errors::GameError;
+use crate::events::{LfgCancelled, LfgMatched, LfgPosted};
+use crate::instructions::matchmaking::init_lobby;
+use crate::invariants::check_invariants;
+use crate::state::config::{params_or_default, GameConfig, CONFIG_SEED};
+use crate::state::game::{GameMode, GameOptions, LobbyAccess, PirateGame};
+use crate::state::lfg::{
+    LfgBoard, LfgEntry, LFG_BOARD_SEED, MAX_LFG_ENTRIES, MAX_LFG_EXPIRY_SECONDS,
+    MAX_LFG_STAKE_LEVEL,
+};
+use crate::state::profile::{PlayerProfile, PROFILE_SEED};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct PostLfg<'info> {
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = LfgBoard::SPACE,
+        seeds = [LFG_BOARD_SEED],
+        bump
+    )]
+    pub board: Account<'info, LfgBoard>,
+    /// Posting needs a rating, so only wallets that have played may post
+    #[account(
+        seeds = [PROFILE_SEED, player.key().as_ref()],
+        bump = profile.bump,
+        has_one = player
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelLfg<'info> {
+    #[account(mut, seeds = [LFG_BOARD_SEED], bump = board.bump)]
+    pub board: Account<'info, LfgBoard>,
+    pub player: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct MatchFromLfg<'info> {
+    #[account(mut, seeds = [LFG_BOARD_SEED], bump = board.bump)]
+    pub board: Account<'info, LfgBoard>,
+    #[account(
+        init,
+        seeds = [GAME_SEED, game_id.to_le_bytes().as_ref()],
+        bump,
+        payer = player,
+        space = PirateGame::SPACE
+    )]
+    pub game: Account<'info, PirateGame>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Option<Account<'info, GameConfig>>,
+    /// The matching player's profile, which carries their creation throttle
+    #[account(
+        mut,
+        seeds = [PROFILE_SEED, player.key().as_ref()],
+        bump = profile.bump,
+        has_one = player
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Post (or replace) the caller's entry on the board; see `state::lfg`
+pub fn post_lfg(
+    ctx: Context<PostLfg>,
+    min_rating: u32,
+    max_rating: u32,
+    mode: GameMode,
+    stake_level: u8,
+    expires_in: i64,
+) -> Result<()> {
+    require!(min_rating <= max_rating, GameError::InvalidRatingBand);
+    require!(
+        stake_level <= MAX_LFG_STAKE_LEVEL && (1..=MAX_LFG_EXPIRY_SECONDS).contains(&expires_in),
+        GameError::InvalidLfgEntry
+    );
+
+    let player = ctx.accounts.player.key();
+    let now = Clock::get()?.unix_timestamp;
+    let board = &mut ctx.accounts.board;
+    board.bump = ctx.bumps.board;
+    board.sweep_expired(now);
+    board.entries.retain(|e| e.player != player);
+    require!(
+        board.entries.len() < MAX_LFG_ENTRIES,
+        GameError::LfgBoardFull
+    );
+
+    let entry = LfgEntry {
+        player,
+        rating: ctx.accounts.profile.rating,
+        min_rating,
+        max_rating,
+        mode,
+        stake_level,
+        expires_at: now.saturating_add(expires_in),
+    };
+    emit!(LfgPosted {
+        player,
+        rating: entry.rating,
+        min_rating,
+        max_rating,
+        mode: entry.mode.clone(),
+        stake_lamports: entry.stake_lamports(),
+        expires_at: entry.expires_at,
+    });
+    board.entries.push(entry);
+    Ok(())
+}
+
+pub fn cancel_lfg(ctx: Context<CancelLfg>) -> Result<()> {
+    let player = ctx.accounts.player.key();
+    let board = &mut ctx.accounts.board;
+    let before = board.entries.len();
+    board.entries.retain(|e| e.player != player);
+    require!(board.entries.len() < before, GameError::LfgEntryNotFound);
+
+    emit!(LfgCancelled { player });
+    Ok(())
+}
+
+/// Pair the caller's entry with `opponent`'s into a new lobby that only the
+/// two of them may join, staked at their agreed level. The caller creates it
+/// and pays its rent.
+pub fn match_from_lfg(ctx: Context<MatchFromLfg>, game_id: u64, opponent: Pubkey) -> Result<()> {
+    let player = ctx.accounts.player.key();
+    let now = Clock::get()?.unix_timestamp;
+
+    let board = &mut ctx.accounts.board;
+    board.sweep_expired(now);
+    let mine = board
+        .take(&player, now)
+        .ok_or(GameError::LfgEntryNotFound)?;
+    let theirs = board
+        .take(&opponent, now)
+        .ok_or(GameError::LfgEntryNotFound)?;
+    require!(mine.matches(&theirs), GameError::LfgEntriesIncompatible);
+
+    let params = params_or_default(ctx.accounts.config.as_deref());
+    require!(
+        params.allows_entry_fee(mine.stake_lamports()),
+        GameError::EntryFeeTooHigh
+    );
+    require!(
+        ctx.accounts
+            .profile
+            .create_window
+            .try_record(now, params.max_creates_per_hour),
+        GameError::RateLimited
+    );
+
+    let options = GameOptions {
+        access: LobbyAccess {
+            invite_list: vec![player, opponent],
+            ..LobbyAccess::default()
+        },
+        ..GameOptions::default()
+    };
+    let game = &mut ctx.accounts.game;
+    init_lobby(
+        game,
+        game_id,
+        player,
+        mine.mode.clone(),
+        options,
+        &params,
+        now,
+        ctx.bumps.game,
+    );
+    game.stake_raises = mine.stake_level;
+
+    emit!(LfgMatched {
+        game_id,
+        players: vec![player, opponent],
+        stake_lamports: mine.stake_lamports(),
+        mode: mine.mode,
+    });
+
+    check_invariants(game)?;
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use crate::constants::*;
+use crate::events::LivenessDepositSettled;
+use crate::invariants::check_invariants;
+use crate::state::game::PirateGame;
+use crate::state::liveness::{
+    take_liveness_deposit, CrankFund, CRANK_FUND_SEED, CRANK_TIP_LAMPORTS,
+};
+use anchor_lang::prelude::*;
+
+/// Return or forfeit one seat's liveness deposit once the game is over.
+/// Permissionless so cranks can settle every seat; see `state::liveness`.
+#[derive(Accounts)]
+#[instruction(player: Pubkey)]
+pub struct SettleLivenessDeposit<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.game_id.to_le_bytes().as_ref()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, PirateGame>,
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        space = CrankFund::SPACE,
+        seeds = [CRANK_FUND_SEED],
+        bump
+    )]
+    pub crank_fund: Account<'info, CrankFund>,
+    /// CHECK: receives a refunded deposit; must be the wallet that paid it
+    #[account(mut)]
+    pub player_wallet: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn settle_liveness_deposit(ctx: Context<SettleLivenessDeposit>, player: Pubkey) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let (amount, refunded) = take_liveness_deposit(game, &player, ctx.accounts.player_wallet.key)?;
+
+    game.sub_lamports(amount)?;
+    let fund = &mut ctx.accounts.crank_fund;
+    fund.bump = ctx.bumps.crank_fund;
+    if refunded {
+        ctx.accounts.player_wallet.add_lamports(amount)?;
+    } else {
+        fund.add_lamports(amount)?;
+        fund.forfeited_total = fund.forfeited_total.saturating_add(amount);
+    }
+
+    // Tip the cranker only from what the fund holds above its rent
+    let fund_info = fund.to_account_info();
+    let rent_exempt = Rent::get()?.minimum_balance(fund_info.data_len());
+    let crank_tip = if fund_info.lamports() >= rent_exempt.saturating_add(CRANK_TIP_LAMPORTS) {
+        fund.sub_lamports(CRANK_TIP_LAMPORTS)?;
+        ctx.accounts.cranker.add_lamports(CRANK_TIP_LAMPORTS)?;
+        fund.tips_paid = fund.tips_paid.saturating_add(CRANK_TIP_LAMPORTS);
+        CRANK_TIP_LAMPORTS
+    } else {
+        0
+    };
+
+    emit!(LivenessDepositSettled {
+        game_id: game.game_id,
+        player,
+        amount,
+        refunded,
+        crank_tip,
+    });
+
+    check_invariants(game)?;
+    Ok(())
+}
@@ -1,11 +1,23 @@
 use crate::constants::*;
 use crate::errors::GameError;
-use crate::events::{GameStarted, PlayerJoined};
-use crate::state::game::{deploy_starting_fleets, GameMode, GameStatus, PirateGame};
-use crate::state::map::generate_strategic_map;
-use crate::state::player::{PlayerData, Resources};
+use crate::events::{GameStarted, PityBonusGranted, PlayerJoined};
+use crate::invariants::check_invariants;
+use crate::state::beacon::{ring_beacon, TurnBeacon, TURN_BEACON_SEED};
+use crate::state::config::{params_or_default, ConfigParams, GameConfig, CONFIG_SEED};
+use crate::state::game::{
+    begin_game, GameMode, GameObjective, GameOptions, GameStatus, LobbyAccess, PirateGame,
+};
+use crate::state::ledger::VaultLedger;
+use crate::state::liveness::LIVENESS_DEPOSIT_LAMPORTS;
+use crate::state::player::{initialize_player, AgentRegistry, PlayerData};
+use crate::state::profile::{PlayerProfile, PROFILE_SEED};
+use crate::state::rng::{seed_from_slot_hash, slot_hash_at, RngAudit, RngStream};
+use crate::state::rulebook::{rulebook_seed, Rulebook, RULEBOOK_SEED};
+use crate::state::simultaneous::RoundPhase;
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::{program::invoke, system_instruction};
+use anchor_lang::solana_program::{
+    hash::hash, program::invoke, system_instruction, sysvar::slot_hashes,
+};
 
 #[derive(Accounts)]
 #[instruction(game_id: u64)]
@@ -18,6 +30,18 @@ pub struct CreateGame<'info> {
         space = PirateGame::SPACE
     )]
     pub game: Account<'info, PirateGame>,
+    /// Balance values to copy into the game; defaults apply when omitted
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Option<Account<'info, GameConfig>>,
+    /// Creator's profile, which carries their creation throttle
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = PlayerProfile::SPACE,
+        seeds = [PROFILE_SEED, authority.key().as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, PlayerProfile>,
     #[account(mut)]
     pub authority: Signer<'info>,
     pub system_program: Program<'info, System>,
@@ -31,6 +55,24 @@ pub struct JoinGame<'info> {
         bump = game.bump
     )]
     pub game: Account<'info, PirateGame>,
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = PlayerProfile::SPACE,
+        seeds = [PROFILE_SEED, player.key().as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+    /// Required by agents_only lobbies; a bonded agent's bond is locked on joining
+    #[account(mut, seeds = [b"agent", player.key().as_ref()], bump)]
+    pub agent: Option<Account<'info, AgentRegistry>>,
+    /// Join throttle; defaults apply when omitted
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Option<Account<'info, GameConfig>>,
+    /// The mode's rulebook, copied into the game if this join fills the lobby;
+    /// built-in rules apply when omitted
+    #[account(seeds = [RULEBOOK_SEED, rulebook_seed(&game.mode).as_ref()], bump = rulebook.bump)]
+    pub rulebook: Option<Account<'info, Rulebook>>,
     #[account(mut)]
     pub player: Signer<'info>,
     pub system_program: Program<'info, System>,
@@ -46,53 +88,237 @@ pub struct StartGame<'info> {
     )]
     pub game: Account<'info, PirateGame>,
     pub authority: Signer<'info>,
+    /// The mode's rulebook, copied into the game; built-in rules apply when omitted
+    #[account(seeds = [RULEBOOK_SEED, rulebook_seed(&game.mode).as_ref()], bump = rulebook.bump)]
+    pub rulebook: Option<Account<'info, Rulebook>>,
+}
+
+/// Begin a started lobby once its committed slot has been hashed.
+/// Permissionless so any seat or a crank can run it.
+#[derive(Accounts)]
+pub struct RevealSeed<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.game_id.to_le_bytes().as_ref()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, PirateGame>,
+    /// CHECK: read raw for the seed; the sysvar is too large to deserialize
+    #[account(address = slot_hashes::ID)]
+    pub recent_slothashes: UncheckedAccount<'info>,
+    /// Beacon of the player who moves first, rung when the game begins
+    #[account(
+        mut,
+        seeds = [TURN_BEACON_SEED, beacon.player.as_ref()],
+        bump = beacon.bump
+    )]
+    pub beacon: Option<Account<'info, TurnBeacon>>,
 }
 
-pub fn create_game(ctx: Context<CreateGame>, game_id: u64, mode: GameMode) -> Result<()> {
+pub fn create_game(
+    ctx: Context<CreateGame>,
+    game_id: u64,
+    mode: GameMode,
+    options: GameOptions,
+) -> Result<()> {
     let game = &mut ctx.accounts.game;
     let clock = Clock::get()?;
 
+    validate_lobby_access(&options.access)?;
+
+    let params = params_or_default(ctx.accounts.config.as_deref());
+    require!(
+        options.loadout.is_within(&params),
+        GameError::InvalidLoadout
+    );
+    require!(
+        options.payout_schedule.is_valid(),
+        GameError::InvalidPayoutSchedule
+    );
+    require!(
+        !options.deterministic || !options.jackpot_eligible,
+        GameError::JackpotInDeterministicGame
+    );
+    require!(
+        params.allows_creator_fee(options.creator_fee_bps),
+        GameError::CreatorFeeTooHigh
+    );
+    match options.stake.as_ref() {
+        Some(stake) => require!(stake.entry_fee > 0, GameError::InvalidEntryFee),
+        None => require!(
+            params.allows_entry_fee(SOL_ENTRY_FEE_LAMPORTS),
+            GameError::EntryFeeTooHigh
+        ),
+    }
+    require!(
+        options.scoring.weights().is_valid(),
+        GameError::InvalidScoringWeights
+    );
+    match options.objective {
+        GameObjective::KingOfTheHill { target_points } => {
+            require!(target_points > 0, GameError::InvalidObjective)
+        }
+        GameObjective::TreasureRace { target_gold } => {
+            require!(target_gold > 0, GameError::InvalidObjective)
+        }
+        // Tutorials are only created by create_tutorial_game
+        GameObjective::Tutorial => return err!(GameError::InvalidObjective),
+        GameObjective::Conquest | GameObjective::CaptureTheFlagship => {}
+    }
+
+    let profile = &mut ctx.accounts.profile;
+    profile.ensure_initialized(ctx.accounts.authority.key(), ctx.bumps.profile);
+    require!(
+        profile
+            .create_window
+            .try_record(clock.unix_timestamp, params.max_creates_per_hour),
+        GameError::RateLimited
+    );
+
+    init_lobby(
+        game,
+        game_id,
+        ctx.accounts.authority.key(),
+        mode,
+        options,
+        &params,
+        clock.unix_timestamp,
+        ctx.bumps.game,
+    );
+
+    msg!("Game {} created", game_id);
+    check_invariants(game)?;
+    Ok(())
+}
+
+/// Fill in a freshly created lobby: waiting for players, nothing staked yet
+#[allow(clippy::too_many_arguments)]
+pub fn init_lobby(
+    game: &mut PirateGame,
+    game_id: u64,
+    authority: Pubkey,
+    mode: GameMode,
+    options: GameOptions,
+    params: &ConfigParams,
+    now: i64,
+    bump: u8,
+) {
     game.game_id = game_id;
-    game.authority = ctx.accounts.authority.key();
+    game.authority = authority;
     game.status = GameStatus::Waiting;
     game.mode = mode;
     game.player_count = 0;
     game.current_player_index = 0;
     game.turn_number = 0;
-    game.created_at = clock.unix_timestamp;
+    game.created_at = now;
     game.started_at = None;
     game.completed_at = None;
     game.winner = None;
     game.weather_type = crate::state::game::WeatherType::Calm;
     game.weather_duration = 2;
-    game.bump = ctx.bumps.game;
+    game.bump = bump;
+    game.total_pot = 0;
+    game.ledger = VaultLedger::default();
+    game.tips_total = 0;
+    game.token_pot = 0;
+    game.options = options;
+    game.seed = 0;
+    game.rng_audit = RngAudit::default();
+    game.rng = RngStream::default();
+    game.start_reveal_slot = None;
+    game.turn_order = Vec::new();
+    game.turn_slot = 0;
+    game.turn_started_at = 0;
+    game.scanned_this_turn = false;
+    game.parley_this_turn = false;
+    game.round_phase = RoundPhase::Commit;
+    game.pending_orders = Vec::new();
+    game.spawn_positions = Vec::new();
+    game.construction_queue = Vec::new();
+    game.contracts = Vec::new();
+    game.next_contract_id = 0;
+    game.overtime_players = Vec::new();
+    game.standings = Vec::new();
+    game.payout_claims = 0;
+    game.vesting = Vec::new();
+    game.final_state_hash = [0; 32];
+    game.payout_pot = 0;
+    game.payout_token_pot = 0;
+    game.creator_fee_due = 0;
+    game.rebate_pool = 0;
+    game.stake_raises = 0;
+    game.pending_raise = None;
+    game.pending_resignation = None;
+    game.tutorial_checkpoint = 0;
+    game.forked_from = None;
+    game.signer_log = Vec::new();
+    game.log_signer(authority);
+    game.resource_multipliers_bps = params.resource_multipliers_bps;
+    game.compact_events = params.compact_events;
+    game.resign_grace = params.resign_grace;
+    game.vesting_threshold_lamports = params.vesting_threshold_lamports;
+    game.pity_bonus_gold = if game.mode == GameMode::Casual {
+        params.pity_bonus_gold
+    } else {
+        0
+    };
+    game.adopt_rulebook(None);
+    game.scoring = game.options.scoring.weights();
     game.players = Vec::new();
     game.territory_map = Vec::new();
+}
 
-    msg!("Game {} created", game_id);
+pub fn join_game(ctx: Context<JoinGame>, invite_code: Option<String>) -> Result<()> {
+    let profile = &mut ctx.accounts.profile;
+    profile.ensure_initialized(ctx.accounts.player.key(), ctx.bumps.profile);
+
+    join_with_entry_fee(
+        &mut ctx.accounts.game,
+        &ctx.accounts.player,
+        &ctx.accounts.system_program,
+        profile,
+        ctx.accounts.agent.as_deref(),
+        ctx.accounts.config.as_deref(),
+        ctx.accounts.rulebook.as_deref(),
+        invite_code.as_deref(),
+    )?;
+
+    if let Some(agent) = ctx.accounts.agent.as_mut() {
+        agent.lock_bond(Clock::get()?.unix_timestamp);
+    }
     Ok(())
 }
 
-pub fn join_game(ctx: Context<JoinGame>) -> Result<()> {
-    let game = &mut ctx.accounts.game;
-    let player = &ctx.accounts.player;
-    let system_program = &ctx.accounts.system_program;
+/// Pay the SOL entry fee and take a seat, after the throttle and lobby checks
+#[allow(clippy::too_many_arguments)]
+pub fn join_with_entry_fee<'info>(
+    game: &mut Account<'info, PirateGame>,
+    player: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    profile: &mut PlayerProfile,
+    agent: Option<&AgentRegistry>,
+    config: Option<&GameConfig>,
+    rulebook: Option<&Rulebook>,
+    invite_code: Option<&str>,
+) -> Result<()> {
     let player_pubkey = player.key();
 
-    require!(
-        game.status == GameStatus::Waiting,
-        GameError::GameNotJoinable
-    );
+    record_join(profile, config)?;
+    check_lobby_access(game, &player_pubkey, profile, agent, invite_code)?;
+
+    require!(game.is_joinable(), GameError::GameNotJoinable);
     require!(game.player_count < MAX_PLAYERS, GameError::GameFull);
+    require!(game.options.stake.is_none(), GameError::StakeAssetMismatch);
 
     // Check if player already joined
     if game.players.iter().any(|p| p.pubkey == player_pubkey) {
         return Err(GameError::GameNotJoinable.into());
     }
+    game.check_join_funding(&player_pubkey, &player_pubkey)?;
 
-    // Transfer Entry Fee (0.1 SOL)
-    // TODO: Make this configurable or dynamic based on game stakes
-    let entry_fee = 100_000_000; // 0.1 SOL in lamports
+    // Transfer Entry Fee (0.1 SOL, doubled for each stake level an LFG match set)
+    let entry_fee = game.sol_stake_per_player();
+    record_wager(profile, config, entry_fee)?;
 
     let transfer_instruction = system_instruction::transfer(&player.key(), &game.key(), entry_fee);
 
@@ -105,64 +331,150 @@ pub fn join_game(ctx: Context<JoinGame>) -> Result<()> {
         ],
     )?;
 
-    // Add player
-    game.players.push(PlayerData {
-        pubkey: player_pubkey,
-        resources: Resources {
-            gold: 1000,
-            crew: 50,
-            cannons: 10,
-            supplies: 100,
-        },
-        ships: Vec::new(),
-        controlled_territories: Vec::new(),
-        total_score: 0,
-        is_active: true,
-        scan_charges: 3,
-        scanned_coordinates: Vec::new(),
-        // Ghost Fleet fields
-        is_ghost_fleet: false,
-        ghost_fleet_turns_remaining: 0,
-        total_ghosts_activated: 0,
-        speed_bonus_accumulated: 0,
-        average_decision_time_ms: 0,
-        total_moves: 0,
-    });
+    game.total_pot = game.total_pot.saturating_add(entry_fee);
+    game.ledger.deposit(entry_fee);
+    game.log_signer(player_pubkey);
+    collect_liveness_deposit(
+        game,
+        player.to_account_info(),
+        system_program.to_account_info(),
+    )?;
+
+    seat_player(game, player_pubkey, profile, agent, rulebook)?;
+
+    check_invariants(game)?;
+    Ok(())
+}
+
+/// Take a joining seat's refundable liveness deposit into the vault; see
+/// `state::liveness`. The seat itself records it once it is added.
+pub fn collect_liveness_deposit<'info>(
+    game: &mut Account<'info, PirateGame>,
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+) -> Result<()> {
+    let transfer_instruction =
+        system_instruction::transfer(payer.key, &game.key(), LIVENESS_DEPOSIT_LAMPORTS);
+    invoke(
+        &transfer_instruction,
+        &[payer, game.to_account_info(), system_program],
+    )?;
+    game.ledger.deposit(LIVENESS_DEPOSIT_LAMPORTS);
+    Ok(())
+}
+
+/// Count a join against the wallet's hourly throttle
+pub fn record_join(profile: &mut PlayerProfile, config: Option<&GameConfig>) -> Result<()> {
+    let params = params_or_default(config);
+    require!(
+        profile
+            .join_window
+            .try_record(Clock::get()?.unix_timestamp, params.max_joins_per_hour),
+        GameError::RateLimited
+    );
+    Ok(())
+}
+
+/// Check a SOL entry fee against the configured cap and count it against the
+/// wallet's rolling 24-hour wager cap
+pub fn record_wager(
+    profile: &mut PlayerProfile,
+    config: Option<&GameConfig>,
+    entry_fee: u64,
+) -> Result<()> {
+    let params = params_or_default(config);
+    require!(
+        params.allows_entry_fee(entry_fee),
+        GameError::EntryFeeTooHigh
+    );
+    require!(
+        profile.wager_window.try_record(
+            Clock::get()?.unix_timestamp,
+            entry_fee,
+            params.daily_wager_cap_lamports
+        ),
+        GameError::DailyWagerCapReached
+    );
+    Ok(())
+}
+
+/// Add a paid-up player to the lobby, starting the game once it is full
+pub fn seat_player(
+    game: &mut PirateGame,
+    player_pubkey: Pubkey,
+    profile: &PlayerProfile,
+    agent: Option<&AgentRegistry>,
+    rulebook: Option<&Rulebook>,
+) -> Result<()> {
+    let starting_resources = game.options.loadout.resources.clone();
+    let mut seat = initialize_player(player_pubkey, &starting_resources);
+    seat.strategy_hash = agent.and_then(AgentRegistry::committed_strategy);
+    seat.bonded_agent = agent.is_some_and(|a| a.bond > 0);
+    seat.liveness_deposit = LIVENESS_DEPOSIT_LAMPORTS;
+    seat.liveness_payer = player_pubkey;
+    grant_pity_bonus(game, &mut seat, profile);
+    game.players.push(seat);
 
     game.player_count += 1;
 
     emit!(PlayerJoined {
         player: player_pubkey,
         player_count: game.player_count,
+        flag_color: profile.flag_color,
+        avatar_seed: profile.avatar_seed,
+        title: profile.title,
     });
 
     // AUTO-START LOGIC
-    // If the lobby is full, automatically start the game
+    // If the lobby is full, start it; `reveal_seed` begins play
     if game.player_count >= MAX_PLAYERS {
         msg!("Auto-starting game {}...", game.game_id);
 
-        let clock = Clock::get()?;
-        let seed = clock.unix_timestamp as u64;
+        game.adopt_rulebook(rulebook);
+        game.commit_start(Clock::get()?.slot);
+    }
 
-        // Generate map
-        game.territory_map = generate_strategic_map(seed);
+    Ok(())
+}
 
-        // Deploy starting fleets
-        deploy_starting_fleets(game)?;
+/// Top up a joining seat's starting gold if the wallet is on a losing streak
+/// and the lobby offers a pity bonus; see `state::profile`
+pub fn grant_pity_bonus(game: &PirateGame, seat: &mut PlayerData, profile: &PlayerProfile) {
+    let gold = profile.pity_bonus(game.pity_bonus_gold);
+    if gold == 0 {
+        return;
+    }
+    seat.resources.gold = seat.resources.gold.saturating_add(gold);
+    emit!(PityBonusGranted {
+        game_id: game.game_id,
+        player: seat.pubkey,
+        gold,
+        losing_streak: profile.losing_streak,
+    });
+}
 
-        game.status = GameStatus::Active;
-        game.started_at = Some(clock.unix_timestamp);
-        game.turn_number = 1;
+/// Close the lobby and commit to the slot whose hash will seed the map and
+/// seat order; play begins with `reveal_seed` once that slot has passed
+pub fn start_game(ctx: Context<StartGame>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
 
-        emit!(GameStarted {
-            player_count: game.player_count,
-        });
-    }
+    require!(game.is_joinable(), GameError::GameAlreadyStarted);
+    require!(
+        game.player_count >= MIN_PLAYERS,
+        GameError::NotEnoughPlayers
+    );
+
+    game.adopt_rulebook(ctx.accounts.rulebook.as_deref());
+    game.commit_start(Clock::get()?.slot);
 
+    check_invariants(game)?;
     Ok(())
 }
 
-pub fn start_game(ctx: Context<StartGame>) -> Result<()> {
+/// Seed and begin a started lobby from the hash of the slot it committed
+/// to. If that hash has aged out of the SlotHashes window the lobby commits
+/// to a fresh slot instead, which nobody can know in advance either.
+pub fn reveal_seed(ctx: Context<RevealSeed>) -> Result<()> {
     let game = &mut ctx.accounts.game;
     let clock = Clock::get()?;
 
@@ -170,25 +482,93 @@ pub fn start_game(ctx: Context<StartGame>) -> Result<()> {
         game.status == GameStatus::Waiting,
         GameError::GameAlreadyStarted
     );
+    let target = game.start_reveal_slot.ok_or(GameError::StartNotCommitted)?;
+    require!(clock.slot > target, GameError::SeedNotReady);
+
+    let resolved = slot_hash_at(&ctx.accounts.recent_slothashes.try_borrow_data()?, target)?;
+    let Some((slot, hash)) = resolved else {
+        game.commit_start(clock.slot);
+        return Ok(());
+    };
+    let seed = seed_from_slot_hash(&hash, game.game_id);
+    begin_game(game, seed, clock.unix_timestamp, slot)?;
+
+    emit!(GameStarted {
+        player_count: game.player_count,
+        rulebook_version: game.rulebook_version,
+    });
+
+    ring_beacon(&mut ctx.accounts.beacon, ctx.remaining_accounts, game)?;
+    check_invariants(game)?;
+    Ok(())
+}
+
+// ============================================================================
+// LOBBY ACCESS
+// ============================================================================
+
+pub fn validate_lobby_access(access: &LobbyAccess) -> Result<()> {
     require!(
-        game.player_count >= MIN_PLAYERS,
-        GameError::NotEnoughPlayers
+        access.invite_list.len() <= MAX_PLAYERS as usize,
+        GameError::InviteListTooLong
     );
+    if let (Some(min), Some(max)) = (access.min_rating, access.max_rating) {
+        require!(min <= max, GameError::InvalidRatingBand);
+    }
+    Ok(())
+}
 
-    // Generate map
-    let seed = clock.unix_timestamp as u64;
-    game.territory_map = generate_strategic_map(seed);
+/// Enforce the lobby's rating band and invite rules for a joining wallet
+pub fn check_lobby_access(
+    game: &PirateGame,
+    player: &Pubkey,
+    profile: &PlayerProfile,
+    agent: Option<&AgentRegistry>,
+    invite_code: Option<&str>,
+) -> Result<()> {
+    let access = &game.options.access;
 
-    // Deploy starting fleets
-    deploy_starting_fleets(game)?;
+    if let Some(min) = access.min_rating {
+        require!(profile.rating >= min, GameError::RatingOutOfRange);
+    }
+    if let Some(max) = access.max_rating {
+        require!(profile.rating <= max, GameError::RatingOutOfRange);
+    }
 
-    game.status = GameStatus::Active;
-    game.started_at = Some(clock.unix_timestamp);
-    game.turn_number = 1;
+    if let Some(min_level) = access.min_level {
+        require!(profile.level >= min_level, GameError::LevelTooLow);
+    }
 
-    emit!(GameStarted {
-        player_count: game.player_count,
-    });
+    if let Some(min_reputation) = access.min_reputation {
+        require!(
+            profile.reputation >= min_reputation,
+            GameError::ReputationTooLow
+        );
+    }
+
+    if access.agents_only {
+        let agent = agent
+            .filter(|a| a.owner == *player)
+            .ok_or(GameError::AgentRequired)?;
+        if let Some(min_games) = access.min_agent_games {
+            require!(
+                agent.games_played >= min_games,
+                GameError::AgentTooInexperienced
+            );
+        }
+        if let Some(min_bond) = access.min_agent_bond {
+            require!(agent.bond >= min_bond, GameError::AgentBondTooLow);
+        }
+    }
+
+    if access.is_invite_only() {
+        let invited = access.invite_list.contains(player);
+        let code_matches = match (access.invite_code_hash, invite_code) {
+            (Some(expected), Some(code)) => hash(code.as_bytes()).to_bytes() == expected,
+            _ => false,
+        };
+        require!(invited || code_matches, GameError::NotInvited);
+    }
 
     Ok(())
 }
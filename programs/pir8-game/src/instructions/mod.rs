@@ -1,7 +1,57 @@
 pub mod admin;
+pub mod beacon;
+pub mod bond;
+pub mod contract;
+pub mod distributor;
+pub mod export;
+pub mod fork;
 pub mod gameplay;
+pub mod invite;
+pub mod leaderboard;
+pub mod ledger;
+pub mod lfg;
+pub mod liveness;
 pub mod matchmaking;
+pub mod naming;
+pub mod payout;
+pub mod profile;
+pub mod raise;
+pub mod revenue;
+pub mod scan_log;
+pub mod session;
+pub mod simultaneous;
+pub mod skin;
+pub mod stake;
+pub mod tip;
+pub mod tutorial;
+pub mod validate;
+pub mod vesting;
 
 pub use admin::*;
+pub use beacon::*;
+pub use bond::*;
+pub use contract::*;
+pub use distributor::*;
+pub use export::*;
+pub use fork::*;
 pub use gameplay::*;
+pub use invite::*;
+pub use leaderboard::*;
+pub use ledger::*;
+pub use lfg::*;
+pub use liveness::*;
 pub use matchmaking::*;
+pub use naming::*;
+pub use payout::*;
+pub use profile::*;
+pub use raise::*;
+pub use revenue::*;
+pub use scan_log::*;
+pub use session::*;
+pub use simultaneous::*;
+pub use skin::*;
+pub use stake::*;
+pub use tip::*;
+pub use tutorial::*;
+pub use validate::*;
+pub use vesting::*;
@@ -0,0 +1,60 @@
+use crate::constants::*;
+use crate::errors::GameError;
+use crate::events::LobbyNamed;
+use crate::state::game::PirateGame;
+use crate::state::naming::{
+    is_valid_lobby_name, lobby_name_hash, GameMetadata, NameRegistry, GAME_METADATA_SEED,
+    NAME_REGISTRY_SEED,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct NameLobby<'info> {
+    #[account(
+        seeds = [GAME_SEED, game.game_id.to_le_bytes().as_ref()],
+        bump = game.bump,
+        has_one = authority @ GameError::Unauthorized
+    )]
+    pub game: Account<'info, PirateGame>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = GameMetadata::SPACE,
+        seeds = [GAME_METADATA_SEED, game.game_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub metadata: Account<'info, GameMetadata>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = NameRegistry::SPACE,
+        seeds = [NAME_REGISTRY_SEED, lobby_name_hash(&name).as_ref()],
+        bump
+    )]
+    pub registry: Account<'info, NameRegistry>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Give the caller's lobby a vanity slug; see `state::naming`
+pub fn name_lobby(ctx: Context<NameLobby>, name: String) -> Result<()> {
+    require!(is_valid_lobby_name(&name), GameError::InvalidLobbyName);
+    let game_id = ctx.accounts.game.game_id;
+
+    let registry = &mut ctx.accounts.registry;
+    require!(registry.name.is_empty(), GameError::LobbyNameTaken);
+    let metadata = &mut ctx.accounts.metadata;
+    require!(metadata.name.is_empty(), GameError::LobbyAlreadyNamed);
+
+    registry.game_id = game_id;
+    registry.name = name.clone();
+    registry.bump = ctx.bumps.registry;
+    metadata.game_id = game_id;
+    metadata.name = name.clone();
+    metadata.bump = ctx.bumps.metadata;
+
+    emit!(LobbyNamed { game_id, name });
+    Ok(())
+}
@@ -0,0 +1,777 @@
+use crate::constants::*;
+use crate::errors::GameError;
+use crate::events::{
+    CreatorFeeClaimed, GameClosed, JackpotRollCommitted, JackpotRollLapsed, JackpotWon,
+    PayoutClaimed, PayoutsClaimed, PrizeVesting,
+};
+use crate::instructions::stake::transfer_stake;
+use crate::invariants::check_invariants;
+use crate::state::config::{GameConfig, CONFIG_SEED};
+use crate::state::game::{GameStatus, PirateGame};
+use crate::state::jackpot::{
+    jackpot_odds_bps, jackpot_roll, split_pot, Jackpot, JackpotCommit, PotSplit, JACKPOT_SEED,
+};
+use crate::state::revenue::is_fee_treasury;
+use crate::state::rng::{slot_hash_at, REVEAL_DELAY_SLOTS};
+use crate::state::vesting::{vested_share, VestingTranche, VESTING_DELAY_SECONDS};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::slot_hashes;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct ClaimPayout<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.game_id.to_le_bytes().as_ref()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, PirateGame>,
+    #[account(
+        init_if_needed,
+        payer = claimant,
+        space = Jackpot::SPACE,
+        seeds = [JACKPOT_SEED],
+        bump
+    )]
+    pub jackpot: Account<'info, Jackpot>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GameConfig>,
+    /// CHECK: platform treasury, the config authority or the revenue splitter
+    #[account(
+        mut,
+        constraint = is_fee_treasury(&config.authority, treasury.key) @ GameError::InvalidTreasury
+    )]
+    pub treasury: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+    /// CHECK: optional wallet or team treasury to receive the SOL payout instead
+    /// of the claimant; it only ever receives lamports
+    #[account(mut)]
+    pub destination: Option<UncheckedAccount<'info>>,
+    pub system_program: Program<'info, System>,
+    // Token-stake games only
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub claimant_token: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+/// Longest memo (e.g. a tournament tag) a payout claim may carry
+pub const MAX_PAYOUT_MEMO_LEN: usize = 32;
+
+/// Most games a single `claim_all_payouts` transaction will settle
+pub const MAX_BULK_CLAIM_GAMES: usize = 8;
+
+#[derive(Accounts)]
+pub struct ClaimAllPayouts<'info> {
+    #[account(
+        init_if_needed,
+        payer = claimant,
+        space = Jackpot::SPACE,
+        seeds = [JACKPOT_SEED],
+        bump
+    )]
+    pub jackpot: Account<'info, Jackpot>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GameConfig>,
+    /// CHECK: platform treasury, the config authority or the revenue splitter
+    #[account(
+        mut,
+        constraint = is_fee_treasury(&config.authority, treasury.key) @ GameError::InvalidTreasury
+    )]
+    pub treasury: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    // remaining_accounts: writable (game, game authority) pairs, one per completed game
+}
+
+/// Settle a committed jackpot roll once its slot has been hashed.
+/// Permissionless so a crank can run it; winnings only go where the claim
+/// sent the payout.
+#[derive(Accounts)]
+pub struct RevealJackpotRoll<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.game_id.to_le_bytes().as_ref()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, PirateGame>,
+    #[account(mut, seeds = [JACKPOT_SEED], bump = jackpot.bump)]
+    pub jackpot: Account<'info, Jackpot>,
+    /// CHECK: read raw for the jackpot roll; the sysvar is too large to deserialize
+    #[account(address = slot_hashes::ID)]
+    pub recent_slothashes: UncheckedAccount<'info>,
+    /// CHECK: checked against the roll's recipient; it only ever receives lamports
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+}
+
+/// Pay a finisher their place's share of the pot less the platform fee and
+/// feed the jackpot. First place in a jackpot-eligible game also commits a
+/// roll for the jackpot. Token stakes are paid out without a fee; the platform fee
+/// and jackpot are SOL-only. Tied players split the shares of the places
+/// they cover. Finishers also collect their fee-free move rebate. SOL goes to
+/// `destination` when one is given.
+pub fn claim_payout<'info>(
+    ctx: Context<'_, '_, '_, 'info, ClaimPayout<'info>>,
+    rank: u8,
+    memo: Option<String>,
+) -> Result<()> {
+    let memo = payout_memo(memo)?;
+
+    let claimant_key = ctx.accounts.claimant.key();
+    let recipient = payout_recipient(&ctx.accounts.claimant, ctx.accounts.destination.as_ref());
+    let game = &mut ctx.accounts.game;
+    let jackpot = &mut ctx.accounts.jackpot;
+    jackpot.bump = ctx.bumps.jackpot;
+
+    let (pot, token_payout, rebate) = take_share(game, &claimant_key, rank)?;
+    require!(
+        pot > 0 || token_payout > 0 || rebate > 0,
+        GameError::NothingToClaim
+    );
+
+    let (split, vested) = pay_pot(
+        game,
+        &claimant_key,
+        &recipient,
+        &ctx.accounts.treasury,
+        jackpot,
+        pot,
+    )?;
+    pay_rebate(game, &recipient, rebate)?;
+    if rank == 0 {
+        commit_jackpot_roll(game, jackpot, &claimant_key, recipient.key, pot)?;
+    }
+
+    // Token pot: the same share of the vault, fee-free
+    if token_payout > 0 {
+        let stake = game.options.stake.ok_or(GameError::StakeAssetMismatch)?;
+        let (Some(mint), Some(vault), Some(claimant_token), Some(token_program)) = (
+            ctx.accounts.mint.as_ref(),
+            ctx.accounts.vault.as_ref(),
+            ctx.accounts.claimant_token.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+        ) else {
+            return Err(GameError::StakeAssetMismatch.into());
+        };
+        require!(mint.key() == stake.mint, GameError::StakeAssetMismatch);
+        require!(
+            vault.key()
+                == get_associated_token_address_with_program_id(
+                    &game.key(),
+                    &stake.mint,
+                    token_program.key
+                ),
+            GameError::StakeAssetMismatch
+        );
+
+        let game_id_bytes = game.game_id.to_le_bytes();
+        let signer_seeds: &[&[u8]] = &[GAME_SEED, game_id_bytes.as_ref(), &[game.bump]];
+        transfer_stake(
+            &token_program.to_account_info(),
+            &vault.to_account_info(),
+            &mint.to_account_info(),
+            &claimant_token.to_account_info(),
+            &game.to_account_info(),
+            token_payout,
+            mint.decimals,
+            ctx.remaining_accounts,
+            &[signer_seeds],
+        )?;
+        game.token_pot -= token_payout;
+    }
+
+    emit!(PayoutClaimed {
+        game_id: game.game_id,
+        player: claimant_key,
+        rank,
+        payout: split.payout - vested,
+        vested,
+        platform_fee: split.platform_fee,
+        jackpot_contribution: split.jackpot_contribution,
+        token_payout,
+        rebate,
+        destination: recipient.key(),
+        memo,
+    });
+
+    check_invariants(game)?;
+    Ok(())
+}
+
+/// Claim the caller's payout from several completed SOL games at once. Each
+/// game is passed with its authority; a game whose payouts and profiles are
+/// all settled is closed and its rent returned to that authority. Token-stake
+/// games must be claimed one at a time with `claim_payout`.
+pub fn claim_all_payouts<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ClaimAllPayouts<'info>>,
+) -> Result<()> {
+    let claimant_key = ctx.accounts.claimant.key();
+    let games = ctx.remaining_accounts.chunks_exact(2);
+    let game_count = games.len();
+    require!(
+        game_count > 0 && games.remainder().is_empty(),
+        GameError::NothingToClaim
+    );
+    require!(game_count <= MAX_BULK_CLAIM_GAMES, GameError::TooManyGames);
+
+    let jackpot = &mut ctx.accounts.jackpot;
+    jackpot.bump = ctx.bumps.jackpot;
+
+    let mut total_payout = 0u64;
+    for pair in games {
+        let (game_info, authority_info) = (&pair[0], &pair[1]);
+        let mut game = Account::<PirateGame>::try_from(game_info)?;
+        require_keys_eq!(
+            authority_info.key(),
+            game.authority,
+            GameError::Unauthorized
+        );
+        require!(game.options.stake.is_none(), GameError::StakeAssetMismatch);
+
+        let rank = game
+            .standings
+            .iter()
+            .find(|s| s.player == claimant_key)
+            .map(|s| s.place)
+            .ok_or(GameError::PlayerNotInGame)?;
+        let (pot, _, rebate) = take_share(&mut game, &claimant_key, rank)?;
+        require!(pot > 0 || rebate > 0, GameError::NothingToClaim);
+
+        let recipient = ctx.accounts.claimant.to_account_info();
+        let (split, vested) = pay_pot(
+            &mut game,
+            &claimant_key,
+            &recipient,
+            &ctx.accounts.treasury,
+            jackpot,
+            pot,
+        )?;
+        pay_rebate(&mut game, &recipient, rebate)?;
+        if rank == 0 {
+            commit_jackpot_roll(&mut game, jackpot, &claimant_key, recipient.key, pot)?;
+        }
+        total_payout = total_payout.saturating_add(split.payout - vested + rebate);
+
+        emit!(PayoutClaimed {
+            game_id: game.game_id,
+            player: claimant_key,
+            rank,
+            payout: split.payout - vested,
+            vested,
+            platform_fee: split.platform_fee,
+            jackpot_contribution: split.jackpot_contribution,
+            token_payout: 0,
+            rebate,
+            destination: claimant_key,
+            memo: String::new(),
+        });
+
+        if game.is_fully_settled() {
+            emit!(GameClosed {
+                game_id: game.game_id,
+                rent_returned_to: game.authority,
+            });
+            game.close(authority_info.clone())?;
+        } else {
+            check_invariants(&game)?;
+            game.exit(&crate::ID)?;
+        }
+    }
+
+    emit!(PayoutsClaimed {
+        player: claimant_key,
+        games: game_count as u8,
+        total_payout,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimCreatorFee<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.game_id.to_le_bytes().as_ref()],
+        bump = game.bump,
+        has_one = authority @ GameError::Unauthorized
+    )]
+    pub game: Account<'info, PirateGame>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Pay the lobby's creator the fee set aside from the SOL pot at completion
+pub fn claim_creator_fee(ctx: Context<ClaimCreatorFee>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    require!(
+        game.status == GameStatus::Completed,
+        GameError::GameNotCompleted
+    );
+    let amount = game.creator_fee_due;
+    require!(amount > 0, GameError::NothingToClaim);
+
+    game.sub_lamports(amount)?;
+    ctx.accounts.authority.add_lamports(amount)?;
+    game.total_pot -= amount;
+    game.ledger.accrue_fee(amount);
+    game.creator_fee_due = 0;
+
+    emit!(CreatorFeeClaimed {
+        game_id: game.game_id,
+        creator: game.authority,
+        amount,
+    });
+
+    check_invariants(game)?;
+    Ok(())
+}
+
+/// A claim's memo, at most `MAX_PAYOUT_MEMO_LEN` bytes; empty when none is given
+fn payout_memo(memo: Option<String>) -> Result<String> {
+    let memo = memo.unwrap_or_default();
+    require!(memo.len() <= MAX_PAYOUT_MEMO_LEN, GameError::MemoTooLong);
+    Ok(memo)
+}
+
+/// Where a claim's SOL goes: the destination when one is given, otherwise
+/// the claimant
+fn payout_recipient<'info>(
+    claimant: &Signer<'info>,
+    destination: Option<&UncheckedAccount<'info>>,
+) -> AccountInfo<'info> {
+    match destination {
+        Some(destination) => destination.to_account_info(),
+        None => claimant.to_account_info(),
+    }
+}
+
+/// Validate the claimant's place, mark it claimed and work out their share
+/// of the SOL and token pots as of completion, and their move rebate
+fn take_share(game: &mut PirateGame, claimant: &Pubkey, rank: u8) -> Result<(u64, u64, u64)> {
+    require!(
+        game.status == GameStatus::Completed,
+        GameError::GameNotCompleted
+    );
+    // Unspent session budget still belongs in the pot being divided
+    require!(game.open_fee_sessions == 0, GameError::FeeSessionsOpen);
+    let seat = game
+        .standings
+        .iter()
+        .position(|s| s.player == *claimant)
+        .ok_or(GameError::PlayerNotInGame)?;
+    require!(game.standings[seat].place == rank, GameError::RankMismatch);
+    require!(
+        game.payout_claims & (1 << seat) == 0,
+        GameError::NothingToClaim
+    );
+    game.payout_claims |= 1 << seat;
+
+    let tied = game.standings.iter().filter(|s| s.place == rank).count() as u8;
+    let share_bps = game.options.payout_schedule.share_bps(rank, tied);
+    let pot = share_of(game.payout_pot, share_bps).min(game.total_pot);
+    let token_payout = share_of(game.payout_token_pot, share_bps).min(game.token_pot);
+    let rebate = game.move_rebate(claimant).min(game.total_pot - pot);
+    Ok((pot, token_payout, rebate))
+}
+
+/// Move a SOL share out of the game: recipient, treasury and jackpot. In a
+/// game whose pot is over its vesting threshold, half the payout stays behind
+/// as a tranche for `release_vested_prize`; returns the split and that amount.
+fn pay_pot<'info>(
+    game: &mut Account<'info, PirateGame>,
+    claimant: &Pubkey,
+    recipient: &AccountInfo<'info>,
+    treasury: &UncheckedAccount<'info>,
+    jackpot: &mut Account<'info, Jackpot>,
+    pot: u64,
+) -> Result<(PotSplit, u64)> {
+    let split = split_pot(pot);
+    let vested = vested_share(
+        split.payout,
+        game.payout_pot,
+        game.vesting_threshold_lamports,
+    );
+    if pot > 0 {
+        game.sub_lamports(pot - vested)?;
+        recipient.add_lamports(split.payout - vested)?;
+        treasury.add_lamports(split.platform_fee)?;
+        jackpot.add_lamports(split.jackpot_contribution)?;
+        game.total_pot -= pot - vested;
+        game.ledger.pay_out(split.payout - vested);
+        game.ledger
+            .accrue_fee(split.platform_fee + split.jackpot_contribution);
+    }
+    jackpot.balance = jackpot.balance.saturating_add(split.jackpot_contribution);
+
+    if vested > 0 {
+        let tranche = VestingTranche {
+            player: *claimant,
+            recipient: recipient.key(),
+            amount: vested,
+            unlocks_at: Clock::get()?.unix_timestamp + VESTING_DELAY_SECONDS,
+            frozen_until: 0,
+        };
+        emit!(PrizeVesting {
+            game_id: game.game_id,
+            player: *claimant,
+            amount: vested,
+            unlocks_at: tranche.unlocks_at,
+        });
+        game.vesting.push(tranche);
+    }
+    Ok((split, vested))
+}
+
+/// Pay a finisher's move rebate straight out of the game, with no platform
+/// fee or jackpot cut
+fn pay_rebate<'info>(
+    game: &mut Account<'info, PirateGame>,
+    recipient: &AccountInfo<'info>,
+    rebate: u64,
+) -> Result<()> {
+    if rebate > 0 {
+        game.sub_lamports(rebate)?;
+        recipient.add_lamports(rebate)?;
+        game.total_pot -= rebate;
+        game.ledger.pay_out(rebate);
+    }
+    Ok(())
+}
+
+/// First place in a jackpot-eligible game rolls for the jackpot, with odds
+/// set by the size of their share. The roll waits on a slot hash nobody knows
+/// yet; `reveal_jackpot_roll` settles it and pays `recipient`. Players tied
+/// for first each roll on their own share, one at a time: a co-winner's claim
+/// waits until the roll before it has been revealed.
+fn commit_jackpot_roll(
+    game: &mut PirateGame,
+    jackpot: &Jackpot,
+    claimant: &Pubkey,
+    recipient: &Pubkey,
+    pot: u64,
+) -> Result<()> {
+    let odds_bps = jackpot_odds_bps(pot);
+    if !game.options.rolls_for_jackpot() || odds_bps == 0 || jackpot.balance == 0 {
+        return Ok(());
+    }
+    require!(
+        game.pending_jackpot.is_none(),
+        GameError::JackpotRollPending
+    );
+
+    let reveal_slot = Clock::get()?.slot + REVEAL_DELAY_SLOTS;
+    game.pending_jackpot = Some(JackpotCommit {
+        winner: *claimant,
+        recipient: *recipient,
+        odds_bps,
+        reveal_slot,
+    });
+    emit!(JackpotRollCommitted {
+        game_id: game.game_id,
+        winner: *claimant,
+        odds_bps,
+        reveal_slot,
+    });
+    Ok(())
+}
+
+pub fn reveal_jackpot_roll(ctx: Context<RevealJackpotRoll>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let jackpot = &mut ctx.accounts.jackpot;
+    let commit = game.pending_jackpot.ok_or(GameError::NoJackpotRoll)?;
+    require_keys_eq!(
+        ctx.accounts.recipient.key(),
+        commit.recipient,
+        GameError::Unauthorized
+    );
+    require!(
+        Clock::get()?.slot > commit.reveal_slot,
+        GameError::JackpotRollNotReady
+    );
+    game.pending_jackpot = None;
+
+    let resolved = slot_hash_at(
+        &ctx.accounts.recent_slothashes.try_borrow_data()?,
+        commit.reveal_slot,
+    )?;
+    let Some((slot, hash)) = resolved else {
+        emit!(JackpotRollLapsed {
+            game_id: game.game_id,
+            winner: commit.winner,
+        });
+        check_invariants(game)?;
+        return Ok(());
+    };
+
+    game.rng_audit.jackpot_slot = slot;
+    let roll = jackpot_roll(&hash, game.game_id, game.seed, &commit.winner);
+    if roll < commit.odds_bps as u64 && jackpot.balance > 0 {
+        let amount = jackpot.balance;
+        jackpot.sub_lamports(amount)?;
+        ctx.accounts.recipient.add_lamports(amount)?;
+        jackpot.balance = 0;
+        jackpot.last_winner = Some(commit.winner);
+        jackpot.last_won_amount = amount;
+
+        emit!(JackpotWon {
+            game_id: game.game_id,
+            winner: commit.winner,
+            amount,
+            odds_bps: commit.odds_bps,
+        });
+    }
+
+    check_invariants(game)?;
+    Ok(())
+}
+
+/// `bps` basis points of an amount, rounded down
+fn share_of(amount: u64, bps: u32) -> u64 {
+    (amount as u128 * bps as u128 / BASIS_POINTS as u128) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::payout::PayoutSchedule;
+    use crate::test_utils::waiting_game;
+
+    /// A finished three-seat game: the first seat won, the other two tied
+    fn completed_game(total_pot: u64) -> PirateGame {
+        let mut game = waiting_game(3);
+        game.options.payout_schedule = PayoutSchedule([6_000, 3_000, 1_000, 0]);
+        game.total_pot = total_pot;
+        for player in game.players.iter_mut() {
+            player.pot_eligible = true;
+            player.profile_settled = true;
+        }
+        game.status = GameStatus::Completed;
+        let winner = game.players[0].pubkey;
+        game.record_standings(&[winner]);
+        game
+    }
+
+    #[test]
+    fn each_place_takes_its_share_and_the_dust_stays_in_the_pot() {
+        let mut game = completed_game(1_000_003);
+        let [first, second, third] = [0, 1, 2].map(|i| game.players[i].pubkey);
+        let places: Vec<u8> = game.standings.iter().map(|s| s.place).collect();
+        assert_eq!(places, [0, 1, 1]);
+
+        assert_eq!(
+            take_share(&mut game, &second, 0),
+            Err(GameError::RankMismatch.into())
+        );
+        assert_eq!(take_share(&mut game, &first, 0).unwrap(), (600_001, 0, 0));
+        // Tied for 2nd: each takes half of the 2nd and 3rd place shares
+        assert_eq!(take_share(&mut game, &second, 1).unwrap(), (200_000, 0, 0));
+        assert_eq!(take_share(&mut game, &third, 1).unwrap(), (200_000, 0, 0));
+        assert_eq!(
+            take_share(&mut game, &third, 1),
+            Err(GameError::NothingToClaim.into())
+        );
+        // Rounding never pays out more than the pot; the remainder is left over
+        assert_eq!(game.payout_pot - (600_001 + 200_000 + 200_000), 2);
+    }
+
+    #[test]
+    fn the_game_settles_once_every_nonzero_share_is_claimed() {
+        let mut game = completed_game(1_000_000);
+        let [first, second, third] = [0, 1, 2].map(|i| game.players[i].pubkey);
+
+        take_share(&mut game, &first, 0).unwrap();
+        take_share(&mut game, &second, 1).unwrap();
+        // claim_all_payouts closes the account only once this holds
+        assert!(!game.is_fully_settled());
+        take_share(&mut game, &third, 1).unwrap();
+        assert!(game.is_fully_settled());
+    }
+
+    /// A writable account, as the runtime would hand it to the program
+    fn account<'a>(
+        key: &'a Pubkey,
+        is_signer: bool,
+        lamports: &'a mut u64,
+        data: &'a mut [u8],
+        owner: &'a Pubkey,
+    ) -> AccountInfo<'a> {
+        AccountInfo::new(key, is_signer, true, lamports, data, owner, false, 0)
+    }
+
+    #[test]
+    fn memos_are_optional_and_bounded() {
+        assert_eq!(payout_memo(None).unwrap(), "");
+        let tag = "t".repeat(MAX_PAYOUT_MEMO_LEN);
+        assert_eq!(payout_memo(Some(tag.clone())).unwrap(), tag);
+        assert_eq!(
+            payout_memo(Some(tag + "!")),
+            Err(GameError::MemoTooLong.into())
+        );
+    }
+
+    #[test]
+    fn a_destination_receives_the_payout_and_rebate() {
+        let mut game = completed_game(1_000_000);
+        let first = game.players[0].pubkey;
+        game.players[0].total_moves = 10;
+        game.record_standings(&[first]);
+        let (pot, _, rebate) = take_share(&mut game, &first, 0).unwrap();
+        assert!(rebate > 0);
+
+        let mut game_data = Vec::new();
+        game.try_serialize(&mut game_data).unwrap();
+        let mut jackpot_data = Vec::new();
+        Jackpot {
+            balance: 0,
+            last_winner: None,
+            last_won_amount: 0,
+            bump: 255,
+        }
+        .try_serialize(&mut jackpot_data)
+        .unwrap();
+        let (game_key, destination_key, treasury_key, jackpot_key) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let (mut game_lamports, mut jackpot_lamports) = (game.total_pot, 0);
+        let (mut claimant_lamports, mut destination_lamports, mut treasury_lamports) = (0, 0, 0);
+        let (mut claimant_data, mut destination_data, mut treasury_data) = ([], [], []);
+        let (program, system) = (crate::ID, anchor_lang::system_program::ID);
+
+        let game_info = account(
+            &game_key,
+            false,
+            &mut game_lamports,
+            &mut game_data,
+            &program,
+        );
+        let jackpot_info = account(
+            &jackpot_key,
+            false,
+            &mut jackpot_lamports,
+            &mut jackpot_data,
+            &program,
+        );
+        let claimant_info = account(
+            &first,
+            true,
+            &mut claimant_lamports,
+            &mut claimant_data,
+            &system,
+        );
+        let destination_info = account(
+            &destination_key,
+            false,
+            &mut destination_lamports,
+            &mut destination_data,
+            &system,
+        );
+        let treasury_info = account(
+            &treasury_key,
+            false,
+            &mut treasury_lamports,
+            &mut treasury_data,
+            &system,
+        );
+        let mut game_account = Account::<PirateGame>::try_from(&game_info).unwrap();
+        let mut jackpot = Account::<Jackpot>::try_from(&jackpot_info).unwrap();
+        let claimant = Signer::try_from(&claimant_info).unwrap();
+        let destination = UncheckedAccount::try_from(&destination_info);
+        let treasury = UncheckedAccount::try_from(&treasury_info);
+
+        assert_eq!(payout_recipient(&claimant, None).key(), first);
+        let recipient = payout_recipient(&claimant, Some(&destination));
+        assert_eq!(recipient.key(), destination_key);
+
+        let (split, vested) = pay_pot(
+            &mut game_account,
+            &first,
+            &recipient,
+            &treasury,
+            &mut jackpot,
+            pot,
+        )
+        .unwrap();
+        pay_rebate(&mut game_account, &recipient, rebate).unwrap();
+
+        assert_eq!(vested, 0);
+        assert_eq!(destination_info.lamports(), split.payout + rebate);
+        assert_eq!(claimant_info.lamports(), 0);
+        assert_eq!(treasury_info.lamports(), split.platform_fee);
+        assert_eq!(jackpot_info.lamports(), split.jackpot_contribution);
+        assert_eq!(game_info.lamports(), game_account.total_pot);
+    }
+
+    #[test]
+    fn token_pots_divide_by_the_same_schedule() {
+        let mut game = completed_game(0);
+        game.token_pot = 1_000;
+        let first = game.players[0].pubkey;
+        game.record_standings(&[first]);
+        let [second, third] = [1, 2].map(|i| game.players[i].pubkey);
+
+        // No SOL changes hands and no fee is taken from the tokens
+        assert_eq!(take_share(&mut game, &first, 0).unwrap(), (0, 600, 0));
+        assert_eq!(take_share(&mut game, &second, 1).unwrap(), (0, 200, 0));
+        assert_eq!(take_share(&mut game, &third, 1).unwrap(), (0, 200, 0));
+    }
+
+    #[test]
+    fn tied_winners_cannot_overwrite_each_others_jackpot_roll() {
+        let mut game = waiting_game(2);
+        game.options.jackpot_eligible = true;
+        game.total_pot = 2_000_000_000;
+        for player in game.players.iter_mut() {
+            player.pot_eligible = true;
+        }
+        game.status = GameStatus::Completed;
+        let [first, second] = [0, 1].map(|i| game.players[i].pubkey);
+        game.record_standings(&[first, second]);
+        let (pot, _, _) = take_share(&mut game, &second, 0).unwrap();
+        let jackpot = Jackpot {
+            balance: 1_000,
+            last_winner: None,
+            last_won_amount: 0,
+            bump: 255,
+        };
+
+        // The other winner claimed first and is waiting on their reveal
+        let pending = JackpotCommit {
+            winner: first,
+            recipient: first,
+            odds_bps: jackpot_odds_bps(pot),
+            reveal_slot: 10,
+        };
+        game.pending_jackpot = Some(pending);
+        assert_eq!(
+            commit_jackpot_roll(&mut game, &jackpot, &second, &second, pot),
+            Err(GameError::JackpotRollPending.into())
+        );
+        assert_eq!(game.pending_jackpot, Some(pending));
+    }
+
+    #[test]
+    fn shares_wait_for_completion_and_open_fee_sessions() {
+        let mut game = completed_game(1_000_000);
+        let first = game.players[0].pubkey;
+
+        game.open_fee_sessions = 1;
+        assert_eq!(
+            take_share(&mut game, &first, 0),
+            Err(GameError::FeeSessionsOpen.into())
+        );
+        game.open_fee_sessions = 0;
+        game.status = GameStatus::Active;
+        assert_eq!(
+            take_share(&mut game, &first, 0),
+            Err(GameError::GameNotCompleted.into())
+        );
+    }
+}
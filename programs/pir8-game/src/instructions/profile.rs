@@ -0,0 +1,132 @@
+use crate::constants::*;
+use crate::errors::GameError;
+use crate::events::ProfileProgressed;
+use crate::invariants::check_invariants;
+use crate::state::game::{GameObjective, GameStatus, PirateGame};
+use crate::state::player::AgentRegistry;
+use crate::state::profile::{
+    reputation_change, PlayerProfile, COSMETIC_FLAG_COLORS, FLAG_COLOR_COUNT, PROFILE_SEED,
+    XP_GAME_COMPLETED, XP_GAME_WON,
+};
+use anchor_lang::prelude::*;
+
+/// Move a player's banked XP and result from a completed game into their profile.
+/// Permissionless so cranks can settle every seat once the game ends.
+#[derive(Accounts)]
+pub struct SettleProfile<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.game_id.to_le_bytes().as_ref()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, PirateGame>,
+    #[account(
+        mut,
+        seeds = [PROFILE_SEED, profile.player.as_ref()],
+        bump = profile.bump
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+    /// The player's agent, if any, whose record is credited with the game too
+    #[account(
+        mut,
+        seeds = [b"agent", profile.player.as_ref()],
+        bump
+    )]
+    pub agent: Option<Account<'info, AgentRegistry>>,
+}
+
+/// Choose the flag, avatar and title shown alongside this wallet in games
+#[derive(Accounts)]
+pub struct CustomizeProfile<'info> {
+    #[account(
+        mut,
+        seeds = [PROFILE_SEED, player.key().as_ref()],
+        bump = profile.bump,
+        has_one = player
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+    pub player: Signer<'info>,
+}
+
+pub fn settle_profile(ctx: Context<SettleProfile>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let profile = &mut ctx.accounts.profile;
+
+    require!(
+        game.status == GameStatus::Completed,
+        GameError::GameNotCompleted
+    );
+    // Tutorials are practice and never count towards a profile
+    require!(
+        game.options.objective != GameObjective::Tutorial,
+        GameError::WrongObjective
+    );
+    require!(game.forked_from.is_none(), GameError::PracticeGame);
+
+    let game_id = game.game_id;
+    let won = game.is_winner(&profile.player);
+    let player = game
+        .players
+        .iter_mut()
+        .find(|p| p.pubkey == profile.player)
+        .ok_or(GameError::PlayerNotInGame)?;
+    require!(!player.profile_settled, GameError::ProfileAlreadySettled);
+    player.profile_settled = true;
+
+    let mut xp_gained = player.xp_earned as u64 + XP_GAME_COMPLETED as u64;
+    if won {
+        xp_gained += XP_GAME_WON as u64;
+    }
+
+    profile.games_played = profile.games_played.saturating_add(1);
+    if won {
+        profile.wins = profile.wins.saturating_add(1);
+    }
+    profile.record_result(won);
+    let leveled_up = profile.add_xp(xp_gained);
+    profile.refresh_titles();
+    let (gain, loss) = reputation_change(player.departure, player.departed_turn);
+    profile.adjust_reputation(gain, loss);
+
+    if let Some(agent) = ctx.accounts.agent.as_mut() {
+        agent.games_played = agent.games_played.saturating_add(1);
+        if won {
+            agent.wins = agent.wins.saturating_add(1);
+        }
+        agent.last_active = Clock::get()?.unix_timestamp;
+    }
+
+    emit!(ProfileProgressed {
+        game_id,
+        player: profile.player,
+        xp_gained,
+        total_xp: profile.xp,
+        level: profile.level,
+        leveled_up,
+        reputation: profile.reputation,
+    });
+
+    check_invariants(game)?;
+    Ok(())
+}
+
+pub fn customize_profile(
+    ctx: Context<CustomizeProfile>,
+    flag_color: u8,
+    avatar_seed: u64,
+    title: u8,
+) -> Result<()> {
+    let profile = &mut ctx.accounts.profile;
+
+    require!(flag_color < FLAG_COLOR_COUNT, GameError::CosmeticLocked);
+    require!(
+        flag_color == 0 || profile.cosmetic_flags & COSMETIC_FLAG_COLORS != 0,
+        GameError::CosmeticLocked
+    );
+    require!(profile.has_title(title), GameError::CosmeticLocked);
+
+    profile.flag_color = flag_color;
+    profile.avatar_seed = avatar_seed;
+    profile.title = title;
+    Ok(())
+}
@@ -0,0 +1,260 @@
+use crate::constants::*;
+use crate::errors::GameError;
+use crate::events::{RaiseAccepted, RaiseCancelled, RaiseProposed, StakesRaised};
+use crate::invariants::check_invariants;
+use crate::state::game::{GameStatus, PirateGame};
+use crate::state::player::PlayerRole;
+use crate::state::raise::{StakeRaise, MAX_STAKE_RAISES};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{program::invoke, system_instruction};
+
+#[derive(Accounts)]
+pub struct RaiseStakes<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.game_id.to_le_bytes().as_ref()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, PirateGame>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Propose doubling the stake, paying the raise into escrow straight away
+pub fn propose_raise(mut ctx: Context<RaiseStakes>) -> Result<()> {
+    let proposer = ctx.accounts.player.key();
+    let game = &ctx.accounts.game;
+
+    require!(game.status == GameStatus::Active, GameError::GameNotActive);
+    require!(game.options.stake.is_none(), GameError::StakeAssetMismatch);
+    require!(game.forked_from.is_none(), GameError::PracticeGame);
+    require!(game.pending_raise.is_none(), GameError::RaisePending);
+    require!(
+        game.stake_raises < MAX_STAKE_RAISES,
+        GameError::StakeRaiseLimit
+    );
+    require_captain(game, &proposer)?;
+
+    let raise = StakeRaise {
+        proposer,
+        amount: game.sol_stake_per_player(),
+        proposed_turn: game.turn_number,
+        backers: vec![proposer],
+    };
+    pay_into_escrow(&mut ctx, raise.amount)?;
+
+    let game = &mut ctx.accounts.game;
+    emit!(RaiseProposed {
+        game_id: game.game_id,
+        proposer,
+        amount: raise.amount,
+        expires_turn: raise.expires_turn(),
+    });
+    game.pending_raise = Some(raise);
+
+    settle_if_unanimous(game);
+    check_invariants(game)?;
+    Ok(())
+}
+
+/// Match a pending raise. The last active captain to accept puts the whole
+/// escrow into the pot.
+pub fn accept_raise(mut ctx: Context<RaiseStakes>) -> Result<()> {
+    let player = ctx.accounts.player.key();
+    let game = &ctx.accounts.game;
+
+    require!(game.status == GameStatus::Active, GameError::GameNotActive);
+    require_captain(game, &player)?;
+    let raise = game
+        .pending_raise
+        .as_ref()
+        .ok_or(GameError::NoRaisePending)?;
+    require!(
+        game.turn_number <= raise.expires_turn(),
+        GameError::RaiseExpired
+    );
+    require!(
+        !raise.backers.contains(&player),
+        GameError::RaiseAlreadyAccepted
+    );
+    let amount = raise.amount;
+    pay_into_escrow(&mut ctx, amount)?;
+
+    let game = &mut ctx.accounts.game;
+    if let Some(raise) = game.pending_raise.as_mut() {
+        raise.backers.push(player);
+    }
+    emit!(RaiseAccepted {
+        game_id: game.game_id,
+        player,
+    });
+
+    settle_if_unanimous(game);
+    check_invariants(game)?;
+    Ok(())
+}
+
+/// Turn down a pending raise and refund every backer, who must be passed as
+/// writable remaining accounts in backing order. A captain who has not
+/// accepted may decline while the window is open; once it has lapsed, or
+/// the game is over, any player may cancel it.
+pub fn decline_raise<'info>(ctx: Context<'_, '_, '_, 'info, RaiseStakes<'info>>) -> Result<()> {
+    let player = ctx.accounts.player.key();
+    let game = &mut ctx.accounts.game;
+
+    require!(
+        game.players.iter().any(|p| p.pubkey == player),
+        GameError::PlayerNotInGame
+    );
+    let raise = game.pending_raise.take().ok_or(GameError::NoRaisePending)?;
+    let open = game.status == GameStatus::Active && game.turn_number <= raise.expires_turn();
+    require!(
+        !open || !raise.backers.contains(&player),
+        GameError::RaiseAlreadyAccepted
+    );
+
+    require!(
+        ctx.remaining_accounts.len() == raise.backers.len()
+            && ctx
+                .remaining_accounts
+                .iter()
+                .zip(raise.backers.iter())
+                .all(|(account, backer)| account.key == backer && account.is_writable),
+        GameError::RefundAccountMismatch
+    );
+    for backer in ctx.remaining_accounts.iter() {
+        game.sub_lamports(raise.amount)?;
+        backer.add_lamports(raise.amount)?;
+        game.ledger.pay_out(raise.amount);
+    }
+
+    emit!(RaiseCancelled {
+        game_id: game.game_id,
+        cancelled_by: player,
+        refunded: raise.escrow(),
+    });
+
+    check_invariants(game)?;
+    Ok(())
+}
+
+fn require_captain(game: &PirateGame, player: &Pubkey) -> Result<()> {
+    let seat = game.get_player(player).ok_or(GameError::PlayerNotInGame)?;
+    require!(
+        seat.role == PlayerRole::Captain,
+        GameError::SpectatorCannotAct
+    );
+    Ok(())
+}
+
+fn pay_into_escrow(ctx: &mut Context<RaiseStakes>, amount: u64) -> Result<()> {
+    let player = &ctx.accounts.player;
+    let game = &ctx.accounts.game;
+    invoke(
+        &system_instruction::transfer(&player.key(), &game.key(), amount),
+        &[
+            player.to_account_info(),
+            game.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+    )?;
+    ctx.accounts.game.ledger.deposit(amount);
+    Ok(())
+}
+
+/// Move the escrow into the pot once every active captain has paid in
+fn settle_if_unanimous(game: &mut PirateGame) {
+    let Some(raise) = game.pending_raise.as_ref() else {
+        return;
+    };
+    let unanimous = game
+        .players
+        .iter()
+        .filter(|p| p.is_active)
+        .all(|p| raise.backers.contains(&p.pubkey));
+    if !unanimous {
+        return;
+    }
+
+    game.total_pot = game.total_pot.saturating_add(raise.escrow());
+    game.stake_raises += 1;
+    game.pending_raise = None;
+
+    emit!(StakesRaised {
+        game_id: game.game_id,
+        stake_per_player: game.sol_stake_per_player(),
+        total_pot: game.total_pot,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::GameBuilder;
+
+    /// An active three-captain game with a raise proposed by the first seat
+    fn raised_game() -> PirateGame {
+        let mut game = GameBuilder::new(3).build();
+        game.total_pot = 3 * SOL_ENTRY_FEE_LAMPORTS;
+        game.pending_raise = Some(StakeRaise {
+            proposer: game.players[0].pubkey,
+            amount: game.sol_stake_per_player(),
+            proposed_turn: game.turn_number,
+            backers: vec![game.players[0].pubkey],
+        });
+        game
+    }
+
+    fn back_raise(game: &mut PirateGame, seat: usize) {
+        let backer = game.players[seat].pubkey;
+        game.pending_raise.as_mut().unwrap().backers.push(backer);
+        settle_if_unanimous(game);
+    }
+
+    #[test]
+    fn a_raise_joins_the_pot_only_once_every_captain_has_paid() {
+        let mut game = raised_game();
+        settle_if_unanimous(&mut game);
+        back_raise(&mut game, 1);
+        // Escrowed, not yet pot
+        assert_eq!(game.raise_escrow(), 2 * SOL_ENTRY_FEE_LAMPORTS);
+        assert_eq!(game.total_pot, 3 * SOL_ENTRY_FEE_LAMPORTS);
+
+        back_raise(&mut game, 2);
+        assert!(game.pending_raise.is_none());
+        assert_eq!(game.raise_escrow(), 0);
+        assert_eq!(game.total_pot, 6 * SOL_ENTRY_FEE_LAMPORTS);
+        assert_eq!(game.stake_raises, 1);
+        // The next raise doubles the doubled stake
+        assert_eq!(game.sol_stake_per_player(), 2 * SOL_ENTRY_FEE_LAMPORTS);
+    }
+
+    #[test]
+    fn captains_who_have_left_do_not_hold_up_a_raise() {
+        let mut game = raised_game();
+        game.players[2].is_active = false;
+        back_raise(&mut game, 1);
+
+        assert!(game.pending_raise.is_none());
+        assert_eq!(game.total_pot, 5 * SOL_ENTRY_FEE_LAMPORTS);
+    }
+
+    #[test]
+    fn only_active_captains_may_back_a_raise() {
+        let mut game = raised_game();
+        let [captain, departed] = [1, 2].map(|i| game.players[i].pubkey);
+        game.players[2].is_active = false;
+
+        assert!(require_captain(&game, &captain).is_ok());
+        assert_eq!(
+            require_captain(&game, &departed),
+            Err(GameError::PlayerNotInGame.into())
+        );
+        game.players[1].role = PlayerRole::Spectator;
+        assert_eq!(
+            require_captain(&game, &captain),
+            Err(GameError::SpectatorCannotAct.into())
+        );
+    }
+}
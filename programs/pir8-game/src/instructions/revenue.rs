@@ -0,0 +1,123 @@
+use crate::errors::GameError;
+use crate::events::{FeesDistributed, RevenueSplitUpdated};
+use crate::state::config::{GameConfig, CONFIG_SEED};
+use crate::state::jackpot::{Jackpot, JACKPOT_SEED};
+use crate::state::revenue::{RevenueSplit, RevenueSplitter, REVENUE_SPLITTER_SEED};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct InitializeRevenueSplitter<'info> {
+    #[account(
+        init,
+        seeds = [REVENUE_SPLITTER_SEED],
+        bump,
+        payer = authority,
+        space = RevenueSplitter::SPACE
+    )]
+    pub revenue_splitter: Account<'info, RevenueSplitter>,
+    /// The splitter belongs to whoever holds the balance config
+    #[account(seeds = [CONFIG_SEED], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, GameConfig>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRevenueSplitter<'info> {
+    #[account(
+        mut,
+        seeds = [REVENUE_SPLITTER_SEED],
+        bump = revenue_splitter.bump,
+        has_one = authority
+    )]
+    pub revenue_splitter: Account<'info, RevenueSplitter>,
+    pub authority: Signer<'info>,
+}
+
+/// Sweep the splitter's accumulated fees. Permissionless so a crank can run
+/// it on any schedule; the recipients are pinned to the configured split.
+#[derive(Accounts)]
+pub struct DistributeFees<'info> {
+    #[account(
+        mut,
+        seeds = [REVENUE_SPLITTER_SEED],
+        bump = revenue_splitter.bump
+    )]
+    pub revenue_splitter: Account<'info, RevenueSplitter>,
+    /// CHECK: dev wallet, pinned to the split; it only ever receives lamports
+    #[account(mut, address = revenue_splitter.split.dev_wallet)]
+    pub dev_wallet: UncheckedAccount<'info>,
+    /// CHECK: seasonal prize pool, pinned to the split; it only ever receives lamports
+    #[account(mut, address = revenue_splitter.split.prize_pool)]
+    pub prize_pool: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = Jackpot::SPACE,
+        seeds = [JACKPOT_SEED],
+        bump
+    )]
+    pub jackpot: Account<'info, Jackpot>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_revenue_splitter(
+    ctx: Context<InitializeRevenueSplitter>,
+    split: RevenueSplit,
+) -> Result<()> {
+    require!(split.is_valid(), GameError::InvalidRevenueSplit);
+
+    let revenue_splitter = &mut ctx.accounts.revenue_splitter;
+    revenue_splitter.authority = ctx.accounts.authority.key();
+    revenue_splitter.split = split.clone();
+    revenue_splitter.total_distributed = 0;
+    revenue_splitter.bump = ctx.bumps.revenue_splitter;
+
+    emit!(RevenueSplitUpdated { split });
+    Ok(())
+}
+
+/// Change the recipients or shares. Fees already collected are swept under
+/// the new split.
+pub fn update_revenue_splitter(
+    ctx: Context<UpdateRevenueSplitter>,
+    split: RevenueSplit,
+) -> Result<()> {
+    require!(split.is_valid(), GameError::InvalidRevenueSplit);
+
+    ctx.accounts.revenue_splitter.split = split.clone();
+
+    emit!(RevenueSplitUpdated { split });
+    Ok(())
+}
+
+pub fn distribute_fees(ctx: Context<DistributeFees>) -> Result<()> {
+    let revenue_splitter = &mut ctx.accounts.revenue_splitter;
+    let jackpot = &mut ctx.accounts.jackpot;
+    jackpot.bump = ctx.bumps.jackpot;
+
+    let info = revenue_splitter.to_account_info();
+    let rent_exempt = Rent::get()?.minimum_balance(info.data_len());
+    let amount = info.lamports().saturating_sub(rent_exempt);
+    require!(amount > 0, GameError::NoFeesToDistribute);
+
+    let split = revenue_splitter.split.split(amount);
+    revenue_splitter.sub_lamports(amount)?;
+    ctx.accounts.dev_wallet.add_lamports(split.dev)?;
+    ctx.accounts.prize_pool.add_lamports(split.prize_pool)?;
+    jackpot.add_lamports(split.jackpot)?;
+    jackpot.balance = jackpot.balance.saturating_add(split.jackpot);
+    revenue_splitter.total_distributed = revenue_splitter.total_distributed.saturating_add(amount);
+
+    emit!(FeesDistributed {
+        amount,
+        dev: split.dev,
+        prize_pool: split.prize_pool,
+        jackpot: split.jackpot,
+        total_distributed: revenue_splitter.total_distributed,
+    });
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use crate::constants::*;
+use crate::errors::GameError;
+use crate::state::game::PirateGame;
+use crate::state::scan_log::{ScanLog, SCAN_LOG_SEED};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct OpenScanLog<'info> {
+    #[account(
+        seeds = [GAME_SEED, game.game_id.to_le_bytes().as_ref()],
+        bump = game.bump,
+        constraint = game.has_seat(player.key) @ GameError::PlayerNotInGame
+    )]
+    pub game: Account<'info, PirateGame>,
+    #[account(
+        init,
+        payer = player,
+        space = ScanLog::SPACE,
+        seeds = [SCAN_LOG_SEED, game.game_id.to_le_bytes().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub scan_log: Account<'info, ScanLog>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseScanLog<'info> {
+    #[account(
+        mut,
+        seeds = [SCAN_LOG_SEED, scan_log.game_id.to_le_bytes().as_ref(), player.key().as_ref()],
+        bump = scan_log.bump,
+        has_one = player,
+        close = player
+    )]
+    pub scan_log: Account<'info, ScanLog>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+}
+
+/// Open the caller's scan log for a game they are seated in. Any scan the
+/// seat has already made is picked up by the next turn sent with the log.
+pub fn open_scan_log(ctx: Context<OpenScanLog>) -> Result<()> {
+    let scan_log = &mut ctx.accounts.scan_log;
+    scan_log.game_id = ctx.accounts.game.game_id;
+    scan_log.player = ctx.accounts.player.key();
+    scan_log.bump = ctx.bumps.scan_log;
+    Ok(())
+}
+
+/// Close the caller's scan log and return its rent
+pub fn close_scan_log(_ctx: Context<CloseScanLog>) -> Result<()> {
+    Ok(())
+}
@@ -0,0 +1,212 @@
+use crate::constants::GAME_SEED;
+use crate::errors::GameError;
+use crate::events::{FeeSessionClosed, FeeSessionOpened};
+use crate::instructions::gameplay::{apply_batch_actions, require_player_turn};
+use crate::invariants::check_invariants;
+use crate::state::beacon::{ring_beacon, TurnBeacon, TURN_BEACON_SEED};
+use crate::state::diff::{emit_state_diff, StateSnapshot};
+use crate::state::game::{GameAction, GameStatus, PirateGame, VictoryType};
+use crate::state::player::AgentRegistry;
+use crate::state::session::{
+    refund_fee_budget, take_fee_budget, FeeSession, FEE_SESSION_SEED, SPONSORED_TX_FEE_LAMPORTS,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct OpenFeeSession<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.game_id.to_le_bytes().as_ref()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, PirateGame>,
+    #[account(
+        init,
+        payer = owner,
+        space = FeeSession::SPACE,
+        seeds = [FEE_SESSION_SEED, game.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub session: Account<'info, FeeSession>,
+    /// The owner's registry names the delegate key the budget is bound to
+    #[account(
+        seeds = [b"agent", owner.key().as_ref()],
+        bump,
+        has_one = owner
+    )]
+    pub agent: Account<'info, AgentRegistry>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Submit a turn signed by the delegate, which is reimbursed for the fee
+#[derive(Accounts)]
+pub struct SponsoredMove<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.game_id.to_le_bytes().as_ref()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, PirateGame>,
+    #[account(
+        mut,
+        seeds = [FEE_SESSION_SEED, game.key().as_ref(), owner.key().as_ref()],
+        bump = session.bump,
+        has_one = game,
+        has_one = owner
+    )]
+    pub session: Account<'info, FeeSession>,
+    #[account(
+        seeds = [b"agent", owner.key().as_ref()],
+        bump,
+        has_one = owner
+    )]
+    pub agent: Account<'info, AgentRegistry>,
+    /// Delegate key signing and paying for the transaction
+    #[account(mut)]
+    pub session_key: Signer<'info>,
+    /// CHECK: owner is validated via has_one constraints on the session and agent accounts
+    #[account(
+        constraint = game.has_seat(owner.key) @ GameError::PlayerNotInGame,
+        constraint = game.is_current_player(owner.key) @ GameError::NotPlayerTurn
+    )]
+    pub owner: UncheckedAccount<'info>,
+    /// Beacon of the player to move next, rung when the turn changes
+    #[account(
+        mut,
+        seeds = [TURN_BEACON_SEED, beacon.player.as_ref()],
+        bump = beacon.bump
+    )]
+    pub beacon: Option<Account<'info, TurnBeacon>>,
+}
+
+/// Owners may close their session at any time; once the game is over anyone
+/// may, so no session can hold budget back from the payouts
+#[derive(Accounts)]
+pub struct CloseFeeSession<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.game_id.to_le_bytes().as_ref()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, PirateGame>,
+    #[account(
+        mut,
+        close = owner,
+        seeds = [FEE_SESSION_SEED, game.key().as_ref(), owner.key().as_ref()],
+        bump = session.bump,
+        has_one = game,
+        has_one = owner
+    )]
+    pub session: Account<'info, FeeSession>,
+    /// CHECK: validated via has_one on the session; receives the rent
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+    pub closer: Signer<'info>,
+}
+
+/// Move a small fee budget from the pot into a session for the owner's
+/// delegate. Each seat may do this once per game.
+pub fn open_fee_session(ctx: Context<OpenFeeSession>) -> Result<()> {
+    let owner_key = ctx.accounts.owner.key();
+    let delegate = ctx
+        .accounts
+        .agent
+        .delegate
+        .ok_or(GameError::UnauthorizedDelegate)?;
+    let game = &mut ctx.accounts.game;
+    let budget = take_fee_budget(game, &owner_key)?;
+
+    // Both accounts are program-owned, so lamports move directly
+    game.sub_lamports(budget)?;
+    ctx.accounts.session.add_lamports(budget)?;
+    game.log_signer(owner_key);
+
+    let session = &mut ctx.accounts.session;
+    session.game = game.key();
+    session.owner = owner_key;
+    session.delegate = delegate;
+    session.budget_lamports = budget;
+    session.spent_lamports = 0;
+    session.bump = ctx.bumps.session;
+
+    emit!(FeeSessionOpened {
+        game_id: game.game_id,
+        owner: owner_key,
+        delegate,
+        budget_lamports: budget,
+    });
+
+    check_invariants(game)?;
+    Ok(())
+}
+
+/// Play the owner's turn as a batch signed by the delegate. The session
+/// reimburses one signature fee while budget remains; after that the
+/// delegate simply pays its own fees.
+pub fn sponsored_batch_actions(
+    ctx: Context<SponsoredMove>,
+    actions: Vec<GameAction>,
+    nonce: u64,
+) -> Result<()> {
+    let session_key = ctx.accounts.session_key.key();
+    let owner_key = ctx.accounts.owner.key();
+
+    require!(
+        ctx.accounts.agent.delegate == Some(session_key)
+            && ctx.accounts.session.delegate == session_key,
+        GameError::UnauthorizedDelegate
+    );
+
+    let game = &mut ctx.accounts.game;
+    let snapshot = StateSnapshot::capture(game);
+
+    require_player_turn(game, &owner_key, nonce)?;
+    game.log_signer(session_key);
+    apply_batch_actions(game, owner_key, actions)?;
+    if !game.complete_if_last_standing(Clock::get()?.unix_timestamp, VictoryType::FlagshipCaptured)
+    {
+        game.advance_turn();
+    }
+
+    let session = &mut ctx.accounts.session;
+    let reimbursement = SPONSORED_TX_FEE_LAMPORTS.min(session.remaining_lamports());
+    if reimbursement > 0 {
+        session.sub_lamports(reimbursement)?;
+        ctx.accounts.session_key.add_lamports(reimbursement)?;
+        session.spent_lamports += reimbursement;
+    }
+
+    ring_beacon(&mut ctx.accounts.beacon, ctx.remaining_accounts, game)?;
+    emit_state_diff(&snapshot, game);
+    check_invariants(game)?;
+    Ok(())
+}
+
+/// Close a session, returning unspent budget to the pot and rent to the owner
+pub fn close_fee_session(ctx: Context<CloseFeeSession>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let session = &mut ctx.accounts.session;
+    require!(
+        ctx.accounts.closer.key() == session.owner || game.status == GameStatus::Completed,
+        GameError::Unauthorized
+    );
+    let unspent = session.remaining_lamports();
+
+    if unspent > 0 {
+        session.sub_lamports(unspent)?;
+        game.add_lamports(unspent)?;
+    }
+    refund_fee_budget(game, unspent);
+
+    emit!(FeeSessionClosed {
+        game_id: game.game_id,
+        owner: session.owner,
+        spent_lamports: session.spent_lamports,
+        refunded_to_pot: unspent,
+    });
+
+    check_invariants(game)?;
+    Ok(())
+}
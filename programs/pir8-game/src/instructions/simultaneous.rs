@@ -0,0 +1,216 @@
+use crate::constants::MAX_SHIPS_PER_PLAYER;
+use crate::errors::GameError;
+use crate::events::{CommitMissed, OrdersCommitted, OrdersRevealed, RevealMissed};
+use crate::instructions::bond::strike_agents;
+use crate::instructions::gameplay::MakeMove;
+use crate::invariants::check_invariants;
+use crate::state::beacon::ring_beacon;
+use crate::state::diff::{emit_state_diff, StateSnapshot};
+use crate::state::game::{GameStatus, PirateGame, VictoryType};
+use crate::state::player::{consume_action_nonce, PlayerRole};
+use crate::state::simultaneous::{
+    commit_window_closed, hash_orders, open_reveals_if_committed, resolve_simultaneous_round,
+    reveal_window_closed, uncommitted_captains, OrderCommitment, RoundPhase, ShipOrder,
+};
+use anchor_lang::prelude::*;
+
+/// Commit a hash of this round's orders (simultaneous games only)
+pub fn commit_orders(ctx: Context<MakeMove>, commitment: [u8; 32], nonce: u64) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let player_pubkey = ctx.accounts.player.key();
+
+    let player_index = require_simultaneous_player(game, &player_pubkey)?;
+    consume_action_nonce(&mut game.players[player_index as usize], nonce)?;
+    require!(
+        game.round_phase == RoundPhase::Commit,
+        GameError::WrongRoundPhase
+    );
+    require!(
+        !game
+            .pending_orders
+            .iter()
+            .any(|c| c.player_index == player_index),
+        GameError::OrdersAlreadyCommitted
+    );
+
+    game.pending_orders.push(OrderCommitment {
+        player_index,
+        commitment,
+        revealed: false,
+        orders: Vec::new(),
+        salt: [0; 32],
+    });
+
+    emit!(OrdersCommitted {
+        game_id: game.game_id,
+        player: player_pubkey,
+        turn_number: game.turn_number,
+    });
+
+    // Everyone is locked in - open the reveal window
+    open_reveals_if_committed(game, Clock::get()?.unix_timestamp);
+
+    ring_beacon(&mut ctx.accounts.beacon, ctx.remaining_accounts, game)?;
+    check_invariants(game)?;
+    Ok(())
+}
+
+/// Reveal committed orders; the last reveal resolves the round
+pub fn reveal_orders(
+    ctx: Context<MakeMove>,
+    orders: Vec<ShipOrder>,
+    salt: [u8; 32],
+    nonce: u64,
+) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let snapshot = StateSnapshot::capture(game);
+    let player_pubkey = ctx.accounts.player.key();
+
+    let player_index = require_simultaneous_player(game, &player_pubkey)?;
+    consume_action_nonce(&mut game.players[player_index as usize], nonce)?;
+    require!(
+        game.round_phase == RoundPhase::Reveal,
+        GameError::WrongRoundPhase
+    );
+    require!(
+        orders.len() <= MAX_SHIPS_PER_PLAYER,
+        GameError::FleetSizeLimit
+    );
+
+    let hash = hash_orders(&orders, &salt)?;
+    let game_id = game.game_id;
+    let turn_number = game.turn_number;
+    let commitment = game
+        .pending_orders
+        .iter_mut()
+        .find(|c| c.player_index == player_index)
+        .ok_or(GameError::OrdersNotCommitted)?;
+    require!(!commitment.revealed, GameError::OrdersAlreadyRevealed);
+    require!(commitment.commitment == hash, GameError::CommitmentMismatch);
+
+    commitment.revealed = true;
+    commitment.orders = orders;
+    commitment.salt = salt;
+
+    emit!(OrdersRevealed {
+        game_id,
+        player: player_pubkey,
+        turn_number,
+        order_count: commitment.orders.len() as u8,
+    });
+
+    if game.pending_orders.iter().all(|c| c.revealed) {
+        let now = Clock::get()?.unix_timestamp;
+        resolve_simultaneous_round(game)?;
+        game.turn_started_at = now;
+        game.complete_if_last_standing(now, VictoryType::FlagshipCaptured);
+    }
+
+    ring_beacon(&mut ctx.accounts.beacon, ctx.remaining_accounts, game)?;
+    emit_state_diff(&snapshot, game);
+    check_invariants(game)?;
+    Ok(())
+}
+
+/// Resolve the round once the reveal window has closed. Any captain still in
+/// the game may call it; everyone who hasn't revealed passes the round, and
+/// bonded agents among them take a strike, their registries passed as
+/// remaining accounts.
+pub fn close_reveals<'info>(ctx: Context<'_, '_, 'info, 'info, MakeMove<'info>>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let snapshot = StateSnapshot::capture(game);
+    let now = Clock::get()?.unix_timestamp;
+
+    require_simultaneous_player(game, &ctx.accounts.player.key())?;
+    require!(
+        game.round_phase == RoundPhase::Reveal,
+        GameError::WrongRoundPhase
+    );
+    require!(reveal_window_closed(game, now), GameError::RevealWindowOpen);
+
+    let mut missed = Vec::new();
+    for commitment in game.pending_orders.iter().filter(|c| !c.revealed) {
+        let player = game.players[commitment.player_index as usize].pubkey;
+        emit!(RevealMissed {
+            game_id: game.game_id,
+            player,
+            turn_number: game.turn_number,
+        });
+        missed.push(player);
+    }
+    for seat in game
+        .players
+        .iter_mut()
+        .filter(|p| missed.contains(&p.pubkey))
+    {
+        seat.timeouts = seat.timeouts.saturating_add(1);
+    }
+    strike_agents(game, &missed, ctx.remaining_accounts)?;
+
+    resolve_simultaneous_round(game)?;
+    game.turn_started_at = now;
+    game.complete_if_last_standing(now, VictoryType::FlagshipCaptured);
+
+    ring_beacon(&mut ctx.accounts.beacon, ctx.remaining_accounts, game)?;
+    emit_state_diff(&snapshot, game);
+    check_invariants(game)?;
+    Ok(())
+}
+
+/// Open the reveals once the commit window has closed. Any captain still in
+/// the game may call it; everyone who hasn't committed sits the round out,
+/// and bonded agents among them take a strike, their registries passed as
+/// remaining accounts. A round nobody committed to resolves straight away.
+pub fn close_commits<'info>(ctx: Context<'_, '_, 'info, 'info, MakeMove<'info>>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let snapshot = StateSnapshot::capture(game);
+    let now = Clock::get()?.unix_timestamp;
+
+    require_simultaneous_player(game, &ctx.accounts.player.key())?;
+    require!(
+        game.round_phase == RoundPhase::Commit,
+        GameError::WrongRoundPhase
+    );
+    require!(commit_window_closed(game, now), GameError::CommitWindowOpen);
+
+    let mut missed = Vec::new();
+    for index in uncommitted_captains(game) {
+        let player = game.players[index].pubkey;
+        emit!(CommitMissed {
+            game_id: game.game_id,
+            player,
+            turn_number: game.turn_number,
+        });
+        let seat = &mut game.players[index];
+        seat.timeouts = seat.timeouts.saturating_add(1);
+        missed.push(player);
+    }
+    strike_agents(game, &missed, ctx.remaining_accounts)?;
+
+    game.round_phase = RoundPhase::Reveal;
+    game.turn_started_at = now;
+    if game.pending_orders.is_empty() {
+        resolve_simultaneous_round(game)?;
+        game.complete_if_last_standing(now, VictoryType::FlagshipCaptured);
+    }
+
+    ring_beacon(&mut ctx.accounts.beacon, ctx.remaining_accounts, game)?;
+    emit_state_diff(&snapshot, game);
+    check_invariants(game)?;
+    Ok(())
+}
+
+fn require_simultaneous_player(game: &PirateGame, player: &Pubkey) -> Result<u8> {
+    require!(game.status == GameStatus::Active, GameError::GameNotActive);
+    require!(game.options.simultaneous_turns, GameError::WrongTurnMode);
+    let index = game
+        .players
+        .iter()
+        .position(|p| p.pubkey == *player && p.is_active)
+        .ok_or(GameError::NotPlayerTurn)?;
+    require!(
+        game.players[index].role == PlayerRole::Captain,
+        GameError::SpectatorCannotAct
+    );
+    Ok(index as u8)
+}
@@ -0,0 +1,105 @@
+use crate::constants::*;
+use crate::errors::GameError;
+use crate::events::ShipSkinSet;
+use crate::invariants::check_invariants;
+use crate::state::config::{GameConfig, CONFIG_SEED};
+use crate::state::game::{GameStatus, PirateGame};
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{BaseStateWithExtensions, StateWithExtensions},
+};
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use spl_token_group_interface::state::TokenGroupMember;
+
+// ============================================================================
+// SHIP SKINS
+// ============================================================================
+//
+// Purely cosmetic: a captain holding an NFT from one of the config's approved
+// collections can dress one of their ships in it. Collections are Token-2022
+// groups, and membership is read from the `TokenGroupMember` extension on the
+// NFT mint itself - only the group's update authority can initialize that, so
+// it cannot be forged by the holder. The skin rides along in `ShipMoved` so
+// frontends can render it; nothing in play reads it.
+
+#[derive(Accounts)]
+#[instruction(ship_id: String, nft_mint: Pubkey)]
+pub struct SetShipSkin<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.game_id.to_le_bytes().as_ref()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, PirateGame>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, GameConfig>,
+    #[account(address = nft_mint, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        token::mint = mint,
+        token::authority = player,
+        token::token_program = token_program
+    )]
+    pub player_token: InterfaceAccount<'info, TokenAccount>,
+    pub player: Signer<'info>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+/// Collection an NFT mint belongs to, from its own group member extension
+pub fn skin_collection(mint: &AccountInfo) -> Result<Pubkey> {
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    let member = state
+        .get_extension::<TokenGroupMember>()
+        .map_err(|_| GameError::SkinCollectionNotApproved)?;
+    require!(
+        member.mint == *mint.key,
+        GameError::SkinCollectionNotApproved
+    );
+    Ok(member.group)
+}
+
+pub fn set_ship_skin(ctx: Context<SetShipSkin>, ship_id: String, nft_mint: Pubkey) -> Result<()> {
+    let player = ctx.accounts.player.key();
+    let mint = &ctx.accounts.mint;
+
+    // A one-of-one the signer actually holds
+    require!(
+        mint.decimals == 0 && mint.supply == 1 && ctx.accounts.player_token.amount == 1,
+        GameError::SkinNotOwned
+    );
+    let collection = skin_collection(&mint.to_account_info())?;
+    require!(
+        ctx.accounts
+            .config
+            .params
+            .skin_collections
+            .contains(&collection),
+        GameError::SkinCollectionNotApproved
+    );
+
+    let game = &mut ctx.accounts.game;
+    require!(game.status == GameStatus::Active, GameError::GameNotActive);
+    let game_id = game.game_id;
+    let ship = game
+        .get_player_mut(&player)
+        .ok_or(GameError::PlayerNotInGame)?
+        .ships
+        .iter_mut()
+        .find(|s| s.id == ship_id)
+        .ok_or(GameError::ShipNotFound)?;
+    ship.skin_mint = Some(nft_mint);
+
+    emit!(ShipSkinSet {
+        game_id,
+        player,
+        ship_id,
+        nft_mint,
+        collection,
+    });
+
+    check_invariants(game)?;
+    Ok(())
+}
@@ -0,0 +1,286 @@
+use crate::constants::*;
+use crate::errors::GameError;
+use crate::instructions::matchmaking::{
+    check_lobby_access, collect_liveness_deposit, record_join, seat_player,
+};
+use crate::invariants::check_invariants;
+use crate::state::config::{GameConfig, CONFIG_SEED};
+use crate::state::game::PirateGame;
+use crate::state::player::AgentRegistry;
+use crate::state::profile::{PlayerProfile, PROFILE_SEED};
+use crate::state::rulebook::{rulebook_seed, Rulebook, RULEBOOK_SEED};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+// ============================================================================
+// TOKEN STAKES
+// ============================================================================
+//
+// Games created with a `TokenStake` take their entry fee in an SPL Token or
+// Token-2022 mint instead of SOL. The vault is the game PDA's associated
+// token account. Transfer fees are tolerated by crediting only what the
+// vault actually received, and transfer hooks by forwarding the hook's extra
+// accounts (resolved client-side) as remaining accounts.
+
+/// Mint extensions that make a stake unsafe to hold: non-transferable tokens
+/// could never be paid out, and a permanent delegate could drain the vault
+const REJECTED_MINT_EXTENSIONS: [ExtensionType; 2] = [
+    ExtensionType::NonTransferable,
+    ExtensionType::PermanentDelegate,
+];
+
+#[derive(Accounts)]
+pub struct JoinGameWithToken<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.game_id.to_le_bytes().as_ref()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, PirateGame>,
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = PlayerProfile::SPACE,
+        seeds = [PROFILE_SEED, player.key().as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+    /// Required by agents_only lobbies; a bonded agent's bond is locked on joining
+    #[account(mut, seeds = [b"agent", player.key().as_ref()], bump)]
+    pub agent: Option<Account<'info, AgentRegistry>>,
+    /// Join throttle; defaults apply when omitted
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Option<Account<'info, GameConfig>>,
+    /// The mode's rulebook, copied into the game if this join fills the lobby;
+    /// built-in rules apply when omitted
+    #[account(seeds = [RULEBOOK_SEED, rulebook_seed(&game.mode).as_ref()], bump = rulebook.bump)]
+    pub rulebook: Option<Account<'info, Rulebook>>,
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = player,
+        token::token_program = token_program
+    )]
+    pub player_token: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = player,
+        associated_token::mint = mint,
+        associated_token::authority = game,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Reject Token-2022 mints carrying extensions hostile to a shared pot
+pub fn validate_stake_mint(mint: &AccountInfo) -> Result<()> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(()); // Legacy SPL Token mints have no extensions
+    }
+
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    let extensions = state.get_extension_types()?;
+    require!(
+        !extensions
+            .iter()
+            .any(|e| REJECTED_MINT_EXTENSIONS.contains(e)),
+        GameError::UnsupportedStakeMint
+    );
+    Ok(())
+}
+
+/// `transfer_checked` built by hand so a transfer hook's extra accounts
+/// (passed through from the caller's remaining accounts) can ride along
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_stake<'info>(
+    token_program: &AccountInfo<'info>,
+    from: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    amount: u64,
+    decimals: u8,
+    hook_accounts: &[AccountInfo<'info>],
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mut transfer = spl_token_2022::instruction::transfer_checked(
+        token_program.key,
+        from.key,
+        mint.key,
+        to.key,
+        authority.key,
+        &[],
+        amount,
+        decimals,
+    )?;
+    let mut transfer_accounts = vec![from.clone(), mint.clone(), to.clone(), authority.clone()];
+    for extra in hook_accounts.iter() {
+        transfer.accounts.push(if extra.is_writable {
+            AccountMeta::new(*extra.key, extra.is_signer)
+        } else {
+            AccountMeta::new_readonly(*extra.key, extra.is_signer)
+        });
+        transfer_accounts.push(extra.clone());
+    }
+
+    invoke_signed(&transfer, &transfer_accounts, signer_seeds)?;
+    Ok(())
+}
+
+pub fn join_game_with_token<'info>(
+    ctx: Context<'_, '_, '_, 'info, JoinGameWithToken<'info>>,
+    invite_code: Option<String>,
+) -> Result<()> {
+    let player_pubkey = ctx.accounts.player.key();
+    let game = &mut ctx.accounts.game;
+
+    let profile = &mut ctx.accounts.profile;
+    profile.ensure_initialized(player_pubkey, ctx.bumps.profile);
+    record_join(profile, ctx.accounts.config.as_deref())?;
+    check_lobby_access(
+        game,
+        &player_pubkey,
+        profile,
+        ctx.accounts.agent.as_deref(),
+        invite_code.as_deref(),
+    )?;
+
+    require!(game.is_joinable(), GameError::GameNotJoinable);
+    require!(game.player_count < MAX_PLAYERS, GameError::GameFull);
+    let stake = game.options.stake.ok_or(GameError::StakeAssetMismatch)?;
+    require!(
+        ctx.accounts.mint.key() == stake.mint,
+        GameError::StakeAssetMismatch
+    );
+    if game.players.iter().any(|p| p.pubkey == player_pubkey) {
+        return Err(GameError::GameNotJoinable.into());
+    }
+    game.check_join_funding(&player_pubkey, &player_pubkey)?;
+
+    validate_stake_mint(&ctx.accounts.mint.to_account_info())?;
+
+    let vault_before = ctx.accounts.vault.amount;
+    transfer_stake(
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.player_token.to_account_info(),
+        &ctx.accounts.mint.to_account_info(),
+        &ctx.accounts.vault.to_account_info(),
+        &ctx.accounts.player.to_account_info(),
+        stake.entry_fee,
+        ctx.accounts.mint.decimals,
+        ctx.remaining_accounts,
+        &[],
+    )?;
+    ctx.accounts.vault.reload()?;
+
+    // Transfer fees are withheld in the vault, so credit only the net amount
+    let credited = ctx.accounts.vault.amount.saturating_sub(vault_before);
+    game.token_pot = game.token_pot.saturating_add(credited);
+    game.log_signer(player_pubkey);
+    collect_liveness_deposit(
+        game,
+        ctx.accounts.player.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+    )?;
+    msg!(
+        "Player {} staked {} (credited {}) of mint {}",
+        player_pubkey,
+        stake.entry_fee,
+        credited,
+        stake.mint
+    );
+
+    seat_player(
+        game,
+        player_pubkey,
+        profile,
+        ctx.accounts.agent.as_deref(),
+        ctx.accounts.rulebook.as_deref(),
+    )?;
+    if let Some(agent) = ctx.accounts.agent.as_mut() {
+        agent.lock_bond(Clock::get()?.unix_timestamp);
+    }
+
+    check_invariants(game)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_spl::token_2022::spl_token_2022::extension::{
+        mint_close_authority::MintCloseAuthority, non_transferable::NonTransferable,
+        permanent_delegate::PermanentDelegate, BaseStateWithExtensionsMut, StateWithExtensionsMut,
+    };
+    use anchor_spl::token_2022::spl_token_2022::state::Mint as MintState;
+
+    /// Raw data of an initialized Token-2022 mint carrying `extensions`
+    fn mint_data(extensions: &[ExtensionType]) -> Vec<u8> {
+        let len = ExtensionType::try_calculate_account_len::<MintState>(extensions).unwrap();
+        let mut data = vec![0; len];
+        let mut state =
+            StateWithExtensionsMut::<MintState>::unpack_uninitialized(&mut data).unwrap();
+        for extension in extensions {
+            match extension {
+                ExtensionType::MintCloseAuthority => {
+                    state.init_extension::<MintCloseAuthority>(true).unwrap();
+                }
+                ExtensionType::NonTransferable => {
+                    state.init_extension::<NonTransferable>(true).unwrap();
+                }
+                ExtensionType::PermanentDelegate => {
+                    state.init_extension::<PermanentDelegate>(true).unwrap();
+                }
+                _ => unreachable!("no test mints this extension"),
+            }
+        }
+        state.base.decimals = 6;
+        state.base.is_initialized = true;
+        state.pack_base();
+        state.init_account_type().unwrap();
+        data
+    }
+
+    fn check_mint(owner: &Pubkey, data: &mut [u8]) -> Result<()> {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mint = AccountInfo::new(&key, false, false, &mut lamports, data, owner, false, 0);
+        validate_stake_mint(&mint)
+    }
+
+    #[test]
+    fn stakes_accept_plain_and_harmless_mints() {
+        let mut legacy = mint_data(&[]);
+        assert!(check_mint(&anchor_spl::token::ID, &mut legacy).is_ok());
+
+        let mut plain = mint_data(&[]);
+        assert!(check_mint(&spl_token_2022::ID, &mut plain).is_ok());
+        let mut closable = mint_data(&[ExtensionType::MintCloseAuthority]);
+        assert!(check_mint(&spl_token_2022::ID, &mut closable).is_ok());
+    }
+
+    #[test]
+    fn stakes_reject_mints_that_could_trap_or_drain_the_vault() {
+        for extension in REJECTED_MINT_EXTENSIONS {
+            let mut data = mint_data(&[ExtensionType::MintCloseAuthority, extension]);
+            assert_eq!(
+                check_mint(&spl_token_2022::ID, &mut data),
+                Err(GameError::UnsupportedStakeMint.into())
+            );
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use crate::constants::*;
+use crate::events::PotTipped;
+use crate::invariants::check_invariants;
+use crate::state::game::PirateGame;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{program::invoke, system_instruction};
+
+#[derive(Accounts)]
+pub struct TipPot<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.game_id.to_le_bytes().as_ref()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, PirateGame>,
+    #[account(mut)]
+    pub tipper: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Add `amount` lamports to an active game's pot from outside the match.
+/// Tips are paid out with the rest of the pot, so they grow every share.
+pub fn tip_pot(ctx: Context<TipPot>, amount: u64) -> Result<()> {
+    let tipper = ctx.accounts.tipper.key();
+    ctx.accounts.game.add_tip(&tipper, amount)?;
+
+    let game = &ctx.accounts.game;
+    invoke(
+        &system_instruction::transfer(&tipper, &game.key(), amount),
+        &[
+            ctx.accounts.tipper.to_account_info(),
+            game.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+    )?;
+
+    emit!(PotTipped {
+        game_id: game.game_id,
+        tipper,
+        amount,
+        tips_total: game.tips_total,
+        total_pot: game.total_pot,
+    });
+
+    check_invariants(game)?;
+    Ok(())
+}
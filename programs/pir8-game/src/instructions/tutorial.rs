@@ -0,0 +1,41 @@
+use crate::constants::*;
+use crate::events::GameStarted;
+use crate::invariants::check_invariants;
+use crate::state::game::PirateGame;
+use crate::state::tutorial::tutorial_game;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct CreateTutorialGame<'info> {
+    #[account(
+        init,
+        seeds = [GAME_SEED, game_id.to_le_bytes().as_ref()],
+        bump,
+        payer = player,
+        space = PirateGame::SPACE
+    )]
+    pub game: Account<'info, PirateGame>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Start a single-player tutorial on the fixed map; see `state::tutorial`.
+/// No entry fee, no lobby and no seed - play begins immediately.
+pub fn create_tutorial_game(ctx: Context<CreateTutorialGame>, game_id: u64) -> Result<()> {
+    let learner = ctx.accounts.player.key();
+    let now = Clock::get()?.unix_timestamp;
+
+    let game = &mut ctx.accounts.game;
+    game.set_inner(tutorial_game(game_id, learner, ctx.bumps.game, now));
+
+    msg!("Tutorial {} started for {}", game_id, learner);
+    emit!(GameStarted {
+        player_count: game.player_count,
+        rulebook_version: game.rulebook_version,
+    });
+
+    check_invariants(game)?;
+    Ok(())
+}
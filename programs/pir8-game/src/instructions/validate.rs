@@ -0,0 +1,71 @@
+use crate::constants::*;
+use crate::instructions::gameplay::{apply_batch_actions, require_player_turn};
+use crate::state::game::{GameAction, PirateGame};
+use anchor_lang::prelude::*;
+
+/// Read-only dry run of an action; meant to be simulated
+#[derive(Accounts)]
+pub struct ValidateAction<'info> {
+    #[account(
+        seeds = [GAME_SEED, game.game_id.to_le_bytes().as_ref()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, PirateGame>,
+}
+
+/// Verdict on an action. `error_code` is the code the real instruction would
+/// fail with (as Anchor reports it, so custom errors start at 6000), 0 if valid.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct ActionValidation {
+    pub valid: bool,
+    pub error_code: u32,
+    pub error_name: String,
+}
+
+impl From<Error> for ActionValidation {
+    fn from(err: Error) -> Self {
+        let (error_code, error_name) = match err {
+            Error::AnchorError(e) => (e.error_code_number, e.error_name),
+            Error::ProgramError(e) => (
+                u64::from(e.program_error.clone()) as u32,
+                e.program_error.to_string(),
+            ),
+        };
+        Self {
+            valid: false,
+            error_code,
+            error_name,
+        }
+    }
+}
+
+/// Run `action` for `player` through the same turn and rule checks as
+/// `batch_actions`, against a scratch copy of the game, and report the
+/// verdict as return data, assuming the player's expected action nonce. The
+/// game account is never written. Events the dry run logs describe the
+/// hypothetical outcome only.
+pub fn validate_action(
+    ctx: Context<ValidateAction>,
+    player: Pubkey,
+    action: GameAction,
+) -> Result<ActionValidation> {
+    let mut scratch = PirateGame::clone(&ctx.accounts.game);
+    let nonce = scratch
+        .players
+        .iter()
+        .find(|p| p.pubkey == player)
+        .map(|p| p.action_nonce)
+        .unwrap_or_default();
+
+    let outcome = require_player_turn(&mut scratch, &player, nonce)
+        .and_then(|_| apply_batch_actions(&mut scratch, player, vec![action]));
+
+    Ok(match outcome {
+        Ok(()) => ActionValidation {
+            valid: true,
+            error_code: 0,
+            error_name: String::new(),
+        },
+        Err(err) => err.into(),
+    })
+}
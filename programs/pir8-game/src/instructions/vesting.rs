@@ -0,0 +1,101 @@
+use crate::constants::*;
+use crate::errors::GameError;
+use crate::events::{VestedPrizeReleased, VestingFrozen};
+use crate::invariants::check_invariants;
+use crate::state::config::{GameConfig, CONFIG_SEED};
+use crate::state::game::PirateGame;
+use anchor_lang::prelude::*;
+
+/// Pay out a winner's second tranche once it has vested. Permissionless so a
+/// crank can release it; the lamports only go where the claim sent the first.
+#[derive(Accounts)]
+pub struct ReleaseVestedPrize<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.game_id.to_le_bytes().as_ref()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, PirateGame>,
+    /// CHECK: checked against the tranche's recipient; it only ever receives lamports
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FreezeVesting<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.game_id.to_le_bytes().as_ref()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, PirateGame>,
+    /// Disputes belong to whoever holds the balance config
+    #[account(seeds = [CONFIG_SEED], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, GameConfig>,
+    pub authority: Signer<'info>,
+}
+
+pub fn release_vested_prize(ctx: Context<ReleaseVestedPrize>, player: Pubkey) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let index = game
+        .vesting
+        .iter()
+        .position(|t| t.player == player)
+        .ok_or(GameError::NoVestingPrize)?;
+    let tranche = &game.vesting[index];
+    let now = Clock::get()?.unix_timestamp;
+    require!(!tranche.is_frozen(now), GameError::PrizeFrozen);
+    require!(tranche.is_releasable(now), GameError::PrizeStillVesting);
+    require_keys_eq!(
+        ctx.accounts.recipient.key(),
+        tranche.recipient,
+        GameError::Unauthorized
+    );
+
+    let tranche = game.vesting.remove(index);
+    game.sub_lamports(tranche.amount)?;
+    ctx.accounts.recipient.add_lamports(tranche.amount)?;
+    game.total_pot -= tranche.amount;
+    game.ledger.pay_out(tranche.amount);
+
+    emit!(VestedPrizeReleased {
+        game_id: game.game_id,
+        player,
+        recipient: tranche.recipient,
+        amount: tranche.amount,
+    });
+
+    check_invariants(game)?;
+    Ok(())
+}
+
+/// Freeze or thaw a winner's unvested tranche while a dispute is looked
+/// into. Only the held half can be frozen; the vesting clock keeps running,
+/// and a freeze lapses at the tranche's deadline if it is never thawed.
+pub fn freeze_vesting(ctx: Context<FreezeVesting>, player: Pubkey, frozen: bool) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let now = Clock::get()?.unix_timestamp;
+    let tranche = game
+        .vesting
+        .iter_mut()
+        .find(|t| t.player == player)
+        .ok_or(GameError::NoVestingPrize)?;
+    tranche.frozen_until = if frozen {
+        require!(
+            now < tranche.freeze_deadline(),
+            GameError::FreezeWindowClosed
+        );
+        tranche.freeze_deadline()
+    } else {
+        0
+    };
+    let frozen_until = tranche.frozen_until;
+
+    emit!(VestingFrozen {
+        game_id: game.game_id,
+        player,
+        frozen,
+        frozen_until,
+    });
+    Ok(())
+}
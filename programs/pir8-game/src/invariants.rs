@@ -0,0 +1,98 @@
+// ============================================================================
+// DEBUG INVARIANTS
+// ============================================================================
+// Cross-cutting state checks run at the end of every instruction when the
+// `debug-invariants` feature is enabled (integration tests, devnet builds).
+// Without the feature `check_invariants` compiles to nothing.
+
+use crate::state::game::PirateGame;
+use anchor_lang::prelude::*;
+
+#[cfg(feature = "debug-invariants")]
+use crate::{
+    constants::MAP_SIZE, errors::GameError, state::cargo::CARGO_HOLD_GOLD,
+    state::map::index_to_coordinate,
+};
+
+/// Upper bound no legitimate resource balance should ever reach
+pub const RESOURCE_SANITY_CAP: u32 = 1_000_000;
+
+#[cfg(feature = "debug-invariants")]
+pub fn check_invariants(game: &Account<PirateGame>) -> Result<()> {
+    // Pot: everything above rent exemption is staked entry fees, plus any
+    // stake raise and liveness deposits still held in escrow
+    let info = game.to_account_info();
+    let rent_exempt = Rent::get()?.minimum_balance(info.data_len());
+    require!(
+        info.lamports()
+            == rent_exempt
+                .saturating_add(game.total_pot)
+                .saturating_add(game.held_in_escrow()),
+        GameError::InvariantPotMismatch
+    );
+    // ...and the vault ledger accounts for every lamport of it
+    require!(
+        game.ledger.balance() == Some(game.total_pot.saturating_add(game.held_in_escrow())),
+        GameError::InvariantPotMismatch
+    );
+
+    // Territory: every owned cell is listed by its owner and vice versa
+    for (index, cell) in game.territory_map.iter().enumerate() {
+        if let Some(owner) = cell.owner {
+            let (x, y) = index_to_coordinate(index);
+            let coord = format!("{},{}", x, y);
+            let listed = game
+                .players
+                .iter()
+                .any(|p| p.pubkey == owner && p.controlled_territories.contains(&coord));
+            require!(listed, GameError::InvariantTerritoryMismatch);
+        }
+    }
+    for player in game.players.iter() {
+        for coord in player.controlled_territories.iter() {
+            let mut parts = coord.split(',').map(|v| v.parse::<usize>());
+            let owned = match (parts.next(), parts.next()) {
+                (Some(Ok(x)), Some(Ok(y))) if x < MAP_SIZE && y < MAP_SIZE => game
+                    .territory_map
+                    .get(x * MAP_SIZE + y)
+                    .map(|cell| cell.owner == Some(player.pubkey))
+                    .unwrap_or(false),
+                _ => false,
+            };
+            require!(owned, GameError::InvariantTerritoryMismatch);
+        }
+    }
+
+    // Ships: one ship per cell, health and cargo within bounds
+    let ships: Vec<_> = game.players.iter().flat_map(|p| p.ships.iter()).collect();
+    for (i, ship) in ships.iter().enumerate() {
+        require!(
+            ship.health <= ship.max_health && ship.cargo_gold <= CARGO_HOLD_GOLD,
+            GameError::InvariantResourceCap
+        );
+        let overlaps = ships[i + 1..]
+            .iter()
+            .any(|o| o.position_x == ship.position_x && o.position_y == ship.position_y);
+        require!(!overlaps, GameError::InvariantShipOverlap);
+    }
+
+    // Resources never exceed the sanity cap
+    for player in game.players.iter() {
+        let r = &player.resources;
+        require!(
+            r.gold <= RESOURCE_SANITY_CAP
+                && r.crew <= RESOURCE_SANITY_CAP
+                && r.cannons <= RESOURCE_SANITY_CAP
+                && r.supplies <= RESOURCE_SANITY_CAP,
+            GameError::InvariantResourceCap
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "debug-invariants"))]
+#[inline(always)]
+pub fn check_invariants(_game: &Account<PirateGame>) -> Result<()> {
+    Ok(())
+}
@@ -4,11 +4,23 @@ pub mod constants;
 pub mod errors;
 pub mod events;
 pub mod instructions;
+pub mod invariants;
+pub mod rules;
 pub mod state;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
 
+#[cfg(all(test, not(target_os = "solana")))]
+mod sim;
+
+use events::{StateExported, VaultReconciled};
 use instructions::*;
-use state::game::GameMode;
+use state::config::ConfigParams;
+use state::game::{GameAction, GameMode, GameOptions};
 use state::player::ShipType;
+use state::revenue::RevenueSplit;
+use state::rulebook::RulebookParams;
+use state::simultaneous::ShipOrder;
 
 declare_id!("EeHyY2FQ3A4GLieZbGbmZtz1iLKzLytXkRcXyzGfmePt");
 
@@ -30,6 +42,16 @@ pub mod pir8_game {
         instructions::register_agent(ctx, name, version, twitter, website)
     }
 
+    pub fn update_agent_metadata(
+        ctx: Context<UpdateAgentMetadata>,
+        name: String,
+        version: String,
+        twitter: Option<String>,
+        website: Option<String>,
+    ) -> Result<()> {
+        instructions::update_agent_metadata(ctx, name, version, twitter, website)
+    }
+
     pub fn delegate_agent_control(
         ctx: Context<DelegateAgentControl>,
         delegate: Option<Pubkey>,
@@ -37,28 +59,109 @@ pub mod pir8_game {
         instructions::delegate_agent_control(ctx, delegate)
     }
 
+    pub fn update_strategy_hash(
+        ctx: Context<UpdateStrategyHash>,
+        strategy_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::update_strategy_hash(ctx, strategy_hash)
+    }
+
+    pub fn post_bond(ctx: Context<ManageBond>, amount: u64) -> Result<()> {
+        instructions::post_bond(ctx, amount)
+    }
+
+    pub fn withdraw_bond(ctx: Context<ManageBond>, amount: u64) -> Result<()> {
+        instructions::withdraw_bond(ctx, amount)
+    }
+
     // ============================================================================
     // GAME LIFECYCLE (MATCHMAKING)
     // ============================================================================
 
-    pub fn create_game(ctx: Context<CreateGame>, game_id: u64, mode: GameMode) -> Result<()> {
-        instructions::create_game(ctx, game_id, mode)
+    pub fn create_game(
+        ctx: Context<CreateGame>,
+        game_id: u64,
+        mode: GameMode,
+        options: GameOptions,
+    ) -> Result<()> {
+        instructions::create_game(ctx, game_id, mode, options)
+    }
+
+    pub fn join_game(ctx: Context<JoinGame>, invite_code: Option<String>) -> Result<()> {
+        instructions::join_game(ctx, invite_code)
     }
 
-    pub fn join_game(ctx: Context<JoinGame>) -> Result<()> {
-        instructions::join_game(ctx)
+    pub fn join_game_with_token<'info>(
+        ctx: Context<'_, '_, '_, 'info, JoinGameWithToken<'info>>,
+        invite_code: Option<String>,
+    ) -> Result<()> {
+        instructions::join_game_with_token(ctx, invite_code)
     }
 
     pub fn start_game(ctx: Context<StartGame>) -> Result<()> {
         instructions::start_game(ctx)
     }
 
+    pub fn reveal_seed(ctx: Context<RevealSeed>) -> Result<()> {
+        instructions::reveal_seed(ctx)
+    }
+
+    pub fn create_tutorial_game(ctx: Context<CreateTutorialGame>, game_id: u64) -> Result<()> {
+        instructions::create_tutorial_game(ctx, game_id)
+    }
+
+    pub fn fork_game(ctx: Context<ForkGame>, game_id: u64) -> Result<()> {
+        instructions::fork_game(ctx, game_id)
+    }
+
+    pub fn send_invite(ctx: Context<SendInvite>, target: Pubkey) -> Result<()> {
+        instructions::send_invite(ctx, target)
+    }
+
+    pub fn accept_invite(ctx: Context<AcceptInvite>, invite_code: Option<String>) -> Result<()> {
+        instructions::accept_invite(ctx, invite_code)
+    }
+
+    pub fn decline_invite(ctx: Context<DeclineInvite>) -> Result<()> {
+        instructions::decline_invite(ctx)
+    }
+
+    pub fn post_lfg(
+        ctx: Context<PostLfg>,
+        min_rating: u32,
+        max_rating: u32,
+        mode: GameMode,
+        stake_level: u8,
+        expires_in: i64,
+    ) -> Result<()> {
+        instructions::post_lfg(ctx, min_rating, max_rating, mode, stake_level, expires_in)
+    }
+
+    pub fn cancel_lfg(ctx: Context<CancelLfg>) -> Result<()> {
+        instructions::cancel_lfg(ctx)
+    }
+
+    pub fn match_from_lfg(
+        ctx: Context<MatchFromLfg>,
+        game_id: u64,
+        opponent: Pubkey,
+    ) -> Result<()> {
+        instructions::match_from_lfg(ctx, game_id, opponent)
+    }
+
+    pub fn name_lobby(ctx: Context<NameLobby>, name: String) -> Result<()> {
+        instructions::name_lobby(ctx, name)
+    }
+
     // ============================================================================
     // SESSION KEY / DELEGATE SUPPORT
     // ============================================================================
 
-    pub fn join_game_via_delegate(ctx: Context<JoinGameViaDelegate>) -> Result<()> {
-        instructions::join_game_via_delegate(ctx)
+    pub fn join_game_via_delegate(
+        ctx: Context<JoinGameViaDelegate>,
+        invite_code: Option<String>,
+    ) -> Result<()> {
+        instructions::join_game_via_delegate(ctx, invite_code)
     }
 
     pub fn move_ship_via_delegate(
@@ -66,9 +169,35 @@ pub mod pir8_game {
         ship_id: String,
         to_x: u8,
         to_y: u8,
+        via: Vec<u8>,
         decision_time_ms: Option<u64>,
+        nonce: u64,
+    ) -> Result<()> {
+        instructions::move_ship_via_delegate(
+            ctx,
+            ship_id,
+            to_x,
+            to_y,
+            via,
+            decision_time_ms,
+            nonce,
+        )
+    }
+
+    pub fn open_fee_session(ctx: Context<OpenFeeSession>) -> Result<()> {
+        instructions::open_fee_session(ctx)
+    }
+
+    pub fn sponsored_batch_actions(
+        ctx: Context<SponsoredMove>,
+        actions: Vec<GameAction>,
+        nonce: u64,
     ) -> Result<()> {
-        instructions::move_ship_via_delegate(ctx, ship_id, to_x, to_y, decision_time_ms)
+        instructions::sponsored_batch_actions(ctx, actions, nonce)
+    }
+
+    pub fn close_fee_session(ctx: Context<CloseFeeSession>) -> Result<()> {
+        instructions::close_fee_session(ctx)
     }
 
     // ============================================================================
@@ -76,60 +205,316 @@ pub mod pir8_game {
     // ============================================================================
 
     pub fn move_ship(
-        ctx: Context<MakeMove>,
+        ctx: Context<PlayTurn>,
         ship_id: String,
         to_x: u8,
         to_y: u8,
+        via: Vec<u8>,
         decision_time_ms: Option<u64>,
+        nonce: u64,
     ) -> Result<()> {
-        instructions::move_ship(ctx, ship_id, to_x, to_y, decision_time_ms)
+        instructions::move_ship(ctx, ship_id, to_x, to_y, via, decision_time_ms, nonce)
     }
 
     pub fn attack_ship(
-        ctx: Context<MakeMove>,
+        ctx: Context<PlayTurn>,
         attacker_ship_id: String,
         target_ship_id: String,
+        nonce: u64,
     ) -> Result<()> {
-        instructions::attack_ship(ctx, attacker_ship_id, target_ship_id)
+        instructions::attack_ship(ctx, attacker_ship_id, target_ship_id, nonce)
     }
 
-    pub fn claim_territory(ctx: Context<MakeMove>, ship_id: String) -> Result<()> {
-        instructions::claim_territory(ctx, ship_id)
+    pub fn claim_territory(ctx: Context<PlayTurn>, ship_id: String, nonce: u64) -> Result<()> {
+        instructions::claim_territory(ctx, ship_id, nonce)
     }
 
-    pub fn collect_resources(ctx: Context<MakeMove>) -> Result<()> {
-        instructions::collect_resources(ctx)
+    pub fn collect_resources(ctx: Context<PlayTurn>, nonce: u64) -> Result<()> {
+        instructions::collect_resources(ctx, nonce)
     }
 
     pub fn build_ship(
-        ctx: Context<MakeMove>,
+        ctx: Context<PlayTurn>,
         ship_type: ShipType,
         port_x: u8,
         port_y: u8,
+        nonce: u64,
     ) -> Result<()> {
-        instructions::build_ship(ctx, ship_type, port_x, port_y)
+        instructions::build_ship(ctx, ship_type, port_x, port_y, nonce)
     }
 
     pub fn scan_coordinate(
-        ctx: Context<MakeMove>,
+        ctx: Context<PlayTurn>,
         coordinate_x: u8,
         coordinate_y: u8,
+        nonce: u64,
     ) -> Result<()> {
-        instructions::scan_coordinate(ctx, coordinate_x, coordinate_y)
+        instructions::scan_coordinate(ctx, coordinate_x, coordinate_y, nonce)
     }
 
-    pub fn activate_ghost_fleet(ctx: Context<ActivateGhostFleet>) -> Result<()> {
-        instructions::activate_ghost_fleet_instruction(ctx)
+    pub fn use_spyglass(ctx: Context<PlayTurn>, ship_id: String, nonce: u64) -> Result<()> {
+        instructions::use_spyglass(ctx, ship_id, nonce)
     }
 
-    pub fn end_turn(ctx: Context<MakeMove>) -> Result<()> {
-        instructions::end_turn(ctx)
+    pub fn load_cargo(ctx: Context<PlayTurn>, ship_id: String, nonce: u64) -> Result<()> {
+        instructions::load_cargo(ctx, ship_id, nonce)
+    }
+
+    pub fn deliver_cargo(ctx: Context<PlayTurn>, ship_id: String, nonce: u64) -> Result<()> {
+        instructions::deliver_cargo(ctx, ship_id, nonce)
+    }
+
+    pub fn activate_ghost_fleet(ctx: Context<ActivateGhostFleet>, nonce: u64) -> Result<()> {
+        instructions::activate_ghost_fleet_instruction(ctx, nonce)
+    }
+
+    pub fn cancel_construction(
+        ctx: Context<PlayTurn>,
+        port_x: u8,
+        port_y: u8,
+        nonce: u64,
+    ) -> Result<()> {
+        instructions::cancel_construction(ctx, port_x, port_y, nonce)
+    }
+
+    pub fn end_turn(ctx: Context<PlayTurn>, nonce: u64) -> Result<()> {
+        instructions::end_turn(ctx, nonce)
+    }
+
+    pub fn batch_actions(
+        ctx: Context<PlayTurn>,
+        actions: Vec<GameAction>,
+        nonce: u64,
+    ) -> Result<()> {
+        instructions::batch_actions(ctx, actions, nonce)
+    }
+
+    // ============================================================================
+    // SIMULTANEOUS ROUNDS
+    // ============================================================================
+
+    pub fn commit_orders(
+        ctx: Context<MakeMove>,
+        commitment: [u8; 32],
+        nonce: u64,
+    ) -> Result<()> {
+        instructions::commit_orders(ctx, commitment, nonce)
+    }
+
+    pub fn reveal_orders(
+        ctx: Context<MakeMove>,
+        orders: Vec<ShipOrder>,
+        salt: [u8; 32],
+        nonce: u64,
+    ) -> Result<()> {
+        instructions::reveal_orders(ctx, orders, salt, nonce)
+    }
+
+    pub fn close_reveals<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MakeMove<'info>>,
+    ) -> Result<()> {
+        instructions::close_reveals(ctx)
+    }
+
+    pub fn close_commits<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MakeMove<'info>>,
+    ) -> Result<()> {
+        instructions::close_commits(ctx)
     }
 
     pub fn check_and_complete_game(ctx: Context<MakeMove>) -> Result<()> {
         instructions::check_and_complete_game(ctx)
     }
 
+    pub fn claim_timeout<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MakeMove<'info>>,
+    ) -> Result<()> {
+        instructions::claim_timeout(ctx)
+    }
+
+    pub fn resign(ctx: Context<MakeMove>) -> Result<()> {
+        instructions::resign(ctx)
+    }
+
+    pub fn cancel_resignation(ctx: Context<MakeMove>) -> Result<()> {
+        instructions::cancel_resignation(ctx)
+    }
+
+    pub fn parley(ctx: Context<MakeMove>) -> Result<()> {
+        instructions::parley(ctx)
+    }
+
+    pub fn accept_contract(ctx: Context<MakeMove>, contract_id: u16) -> Result<()> {
+        instructions::accept_contract(ctx, contract_id)
+    }
+
+    pub fn cast_ghost_curse(ctx: Context<MakeMove>, x: u8, y: u8) -> Result<()> {
+        instructions::cast_ghost_curse(ctx, x, y)
+    }
+
+    pub fn claim_payout<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimPayout<'info>>,
+        rank: u8,
+        memo: Option<String>,
+    ) -> Result<()> {
+        instructions::claim_payout(ctx, rank, memo)
+    }
+
+    pub fn claim_all_payouts<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimAllPayouts<'info>>,
+    ) -> Result<()> {
+        instructions::claim_all_payouts(ctx)
+    }
+
+    pub fn reveal_jackpot_roll(ctx: Context<RevealJackpotRoll>) -> Result<()> {
+        instructions::reveal_jackpot_roll(ctx)
+    }
+
+    pub fn claim_creator_fee(ctx: Context<ClaimCreatorFee>) -> Result<()> {
+        instructions::claim_creator_fee(ctx)
+    }
+
+    pub fn settle_liveness_deposit(
+        ctx: Context<SettleLivenessDeposit>,
+        player: Pubkey,
+    ) -> Result<()> {
+        instructions::settle_liveness_deposit(ctx, player)
+    }
+
+    // ============================================================================
+    // PRIZE DISTRIBUTION
+    // ============================================================================
+
+    pub fn create_prize_distributor(
+        ctx: Context<CreatePrizeDistributor>,
+        distributor_id: u64,
+        merkle_root: [u8; 32],
+        total: u64,
+        reclaim_after: i64,
+    ) -> Result<()> {
+        instructions::create_prize_distributor(
+            ctx,
+            distributor_id,
+            merkle_root,
+            total,
+            reclaim_after,
+        )
+    }
+
+    pub fn claim_prize(
+        ctx: Context<ClaimPrize>,
+        index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::claim_prize(ctx, index, amount, proof)
+    }
+
+    pub fn close_prize_distributor(ctx: Context<ClosePrizeDistributor>) -> Result<()> {
+        instructions::close_prize_distributor(ctx)
+    }
+
+    // ============================================================================
+    // STAKE RAISES
+    // ============================================================================
+
+    pub fn propose_raise(ctx: Context<RaiseStakes>) -> Result<()> {
+        instructions::propose_raise(ctx)
+    }
+
+    pub fn accept_raise(ctx: Context<RaiseStakes>) -> Result<()> {
+        instructions::accept_raise(ctx)
+    }
+
+    pub fn decline_raise<'info>(ctx: Context<'_, '_, '_, 'info, RaiseStakes<'info>>) -> Result<()> {
+        instructions::decline_raise(ctx)
+    }
+
+    // ============================================================================
+    // SPECTATOR TIPS
+    // ============================================================================
+
+    pub fn tip_pot(ctx: Context<TipPot>, amount: u64) -> Result<()> {
+        instructions::tip_pot(ctx, amount)
+    }
+
+    // ============================================================================
+    // TURN BEACONS
+    // ============================================================================
+
+    pub fn open_turn_beacon(ctx: Context<OpenTurnBeacon>) -> Result<()> {
+        instructions::open_turn_beacon(ctx)
+    }
+
+    pub fn close_turn_beacon(ctx: Context<CloseTurnBeacon>) -> Result<()> {
+        instructions::close_turn_beacon(ctx)
+    }
+
+    pub fn open_scan_log(ctx: Context<OpenScanLog>) -> Result<()> {
+        instructions::open_scan_log(ctx)
+    }
+
+    pub fn close_scan_log(ctx: Context<CloseScanLog>) -> Result<()> {
+        instructions::close_scan_log(ctx)
+    }
+
+    // ============================================================================
+    // STATE EXPORT
+    // ============================================================================
+
+    pub fn export_state(ctx: Context<ExportState>, chunk_index: u16) -> Result<StateExported> {
+        instructions::export_state(ctx, chunk_index)
+    }
+
+    pub fn reconcile_vault(ctx: Context<ReconcileVault>) -> Result<VaultReconciled> {
+        instructions::reconcile_vault(ctx)
+    }
+
+    // ============================================================================
+    // ACTION VALIDATION
+    // ============================================================================
+
+    pub fn validate_action(
+        ctx: Context<ValidateAction>,
+        player: Pubkey,
+        action: GameAction,
+    ) -> Result<ActionValidation> {
+        instructions::validate_action(ctx, player, action)
+    }
+
+    // ============================================================================
+    // PROGRESSION
+    // ============================================================================
+
+    pub fn settle_profile(ctx: Context<SettleProfile>) -> Result<()> {
+        instructions::settle_profile(ctx)
+    }
+
+    pub fn customize_profile(
+        ctx: Context<CustomizeProfile>,
+        flag_color: u8,
+        avatar_seed: u64,
+        title: u8,
+    ) -> Result<()> {
+        instructions::customize_profile(ctx, flag_color, avatar_seed, title)
+    }
+
+    pub fn submit_decision_time(ctx: Context<SubmitDecisionTime>, player: Pubkey) -> Result<()> {
+        instructions::submit_decision_time(ctx, player)
+    }
+
+    // ============================================================================
+    // SHIP SKINS
+    // ============================================================================
+
+    pub fn set_ship_skin(
+        ctx: Context<SetShipSkin>,
+        ship_id: String,
+        nft_mint: Pubkey,
+    ) -> Result<()> {
+        instructions::set_ship_skin(ctx, ship_id, nft_mint)
+    }
+
     // ============================================================================
     // ADMIN
     // ============================================================================
@@ -137,4 +522,46 @@ pub mod pir8_game {
     pub fn reset_game(ctx: Context<ResetGame>) -> Result<()> {
         instructions::reset_game(ctx)
     }
+
+    pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+        instructions::initialize_config(ctx)
+    }
+
+    pub fn update_config(ctx: Context<UpdateConfig>, params: ConfigParams) -> Result<()> {
+        instructions::update_config(ctx, params)
+    }
+
+    pub fn initialize_rulebook(ctx: Context<InitializeRulebook>, mode: GameMode) -> Result<()> {
+        instructions::initialize_rulebook(ctx, mode)
+    }
+
+    pub fn update_rulebook(ctx: Context<UpdateRulebook>, params: RulebookParams) -> Result<()> {
+        instructions::update_rulebook(ctx, params)
+    }
+
+    pub fn initialize_revenue_splitter(
+        ctx: Context<InitializeRevenueSplitter>,
+        split: RevenueSplit,
+    ) -> Result<()> {
+        instructions::initialize_revenue_splitter(ctx, split)
+    }
+
+    pub fn update_revenue_splitter(
+        ctx: Context<UpdateRevenueSplitter>,
+        split: RevenueSplit,
+    ) -> Result<()> {
+        instructions::update_revenue_splitter(ctx, split)
+    }
+
+    pub fn distribute_fees(ctx: Context<DistributeFees>) -> Result<()> {
+        instructions::distribute_fees(ctx)
+    }
+
+    pub fn release_vested_prize(ctx: Context<ReleaseVestedPrize>, player: Pubkey) -> Result<()> {
+        instructions::release_vested_prize(ctx, player)
+    }
+
+    pub fn freeze_vesting(ctx: Context<FreezeVesting>, player: Pubkey, frozen: bool) -> Result<()> {
+        instructions::freeze_vesting(ctx, player, frozen)
+    }
 }
@@ -0,0 +1,397 @@
+// ============================================================================
+// CORE RULES
+// ============================================================================
+//
+// The rules every client needs to predict an outcome, as pure functions over
+// plain integers. This file depends on nothing but `core` - no Anchor, no
+// Solana, no `crate::` paths - so a client crate (native or wasm) can compile
+// the very same source with `#[path = ".../pir8-game/src/rules.rs"] mod rules;`
+// and stay in lockstep with on-chain validation. The program's own helpers
+// delegate here rather than re-implementing any of it.
+//
+// Cell types are their `TerritoryCellType` discriminants (0 Water, 1 Island,
+// 2 Port, 3 Treasure, ...); resource tuples are (gold, crew, cannons, supplies).
+
+/// Side length of the square map
+pub const MAP_SIZE: u8 = 10;
+
+/// Fixed-point scale for multipliers: 10_000 = 1.0x
+pub const BASIS_POINTS: u32 = 10_000;
+
+/// Furthest an attacker may be from its target
+pub const ATTACK_RANGE: u32 = 1;
+
+/// Share of all surviving hull points that wins by fleet dominance
+pub const FLEET_DOMINANCE_PERCENT: u32 = 65;
+/// Share of valuable cells that wins by territory control
+pub const TERRITORY_CONTROL_PERCENT: u32 = 50;
+/// Resource value that wins an economic victory
+pub const ECONOMIC_VICTORY_VALUE: u32 = 10_000;
+
+/// Where the victory conditions sit for a game; rulebooks can move them
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VictoryThresholds {
+    pub fleet_dominance_percent: u32,
+    pub territory_control_percent: u32,
+    pub economic_victory_value: u32,
+}
+
+pub const DEFAULT_VICTORY_THRESHOLDS: VictoryThresholds = VictoryThresholds {
+    fleet_dominance_percent: FLEET_DOMINANCE_PERCENT,
+    territory_control_percent: TERRITORY_CONTROL_PERCENT,
+    economic_victory_value: ECONOMIC_VICTORY_VALUE,
+};
+
+/// Why an action breaks the rules
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RuleViolation {
+    OutOfBounds,
+    ExceedsSpeed,
+    OutOfRange,
+    BrokenPath,
+    Blocked,
+    ZoneOfControl,
+}
+
+// ---------------------------------------------------------------------------
+// Movement
+// ---------------------------------------------------------------------------
+
+/// Orthogonal steps between two cells
+pub fn manhattan(from: (u8, u8), to: (u8, u8)) -> u32 {
+    from.0.abs_diff(to.0) as u32 + from.1.abs_diff(to.1) as u32
+}
+
+/// King-move steps between two cells (diagonals count as one)
+pub fn chebyshev(from: (u8, u8), to: (u8, u8)) -> u32 {
+    from.0.abs_diff(to.0).max(from.1.abs_diff(to.1)) as u32
+}
+
+/// Squared straight-line distance, for radius checks without a square root
+pub fn squared_euclidean(from: (u8, u8), to: (u8, u8)) -> u32 {
+    let dx = from.0.abs_diff(to.0) as u32;
+    let dy = from.1.abs_diff(to.1) as u32;
+    dx * dx + dy * dy
+}
+
+/// Steps between two cells; casual games allow diagonals
+pub fn grid_distance(from: (u8, u8), to: (u8, u8), diagonals: bool) -> u32 {
+    if diagonals {
+        chebyshev(from, to)
+    } else {
+        manhattan(from, to)
+    }
+}
+
+pub fn in_bounds(x: u8, y: u8) -> bool {
+    x < MAP_SIZE && y < MAP_SIZE
+}
+
+/// A ship with `speed` may sail from `from` to `to`, ignoring other ships
+pub fn check_move(
+    from: (u8, u8),
+    to: (u8, u8),
+    speed: u32,
+    diagonals: bool,
+) -> Result<(), RuleViolation> {
+    if !in_bounds(to.0, to.1) {
+        return Err(RuleViolation::OutOfBounds);
+    }
+    if grid_distance(from, to, diagonals) > speed {
+        return Err(RuleViolation::ExceedsSpeed);
+    }
+    Ok(())
+}
+
+/// A ship with `speed` may sail from `from` through the cells `via` to `to`,
+/// one neighbouring cell per step, without passing through a `blocked` cell.
+/// Entering a `controlled` cell (one in an enemy's zone of control) ends the
+/// move, so only the destination may be one. Holding position is a move with
+/// no steps. The destination itself is left to the caller.
+pub fn check_path(
+    from: (u8, u8),
+    via: &[(u8, u8)],
+    to: (u8, u8),
+    speed: u32,
+    diagonals: bool,
+    blocked: impl Fn((u8, u8)) -> bool,
+    controlled: impl Fn((u8, u8)) -> bool,
+) -> Result<(), RuleViolation> {
+    if !in_bounds(to.0, to.1) {
+        return Err(RuleViolation::OutOfBounds);
+    }
+    if via.is_empty() && from == to {
+        return Ok(());
+    }
+    if via.len() as u32 + 1 > speed {
+        return Err(RuleViolation::ExceedsSpeed);
+    }
+
+    let mut at = from;
+    for &cell in via {
+        if !in_bounds(cell.0, cell.1) {
+            return Err(RuleViolation::OutOfBounds);
+        }
+        if grid_distance(at, cell, diagonals) != 1 {
+            return Err(RuleViolation::BrokenPath);
+        }
+        if blocked(cell) {
+            return Err(RuleViolation::Blocked);
+        }
+        if controlled(cell) {
+            return Err(RuleViolation::ZoneOfControl);
+        }
+        at = cell;
+    }
+    if grid_distance(at, to, diagonals) != 1 {
+        return Err(RuleViolation::BrokenPath);
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Combat
+// ---------------------------------------------------------------------------
+
+pub fn check_attack_range(
+    attacker: (u8, u8),
+    target: (u8, u8),
+    diagonals: bool,
+) -> Result<(), RuleViolation> {
+    if grid_distance(attacker, target, diagonals) > ATTACK_RANGE {
+        return Err(RuleViolation::OutOfRange);
+    }
+    Ok(())
+}
+
+/// Damage dealt by one attack: attack minus defense (minimum 1), scaled by any
+/// ambush bonus (basis points) and never more than the attacker's full attack rating
+pub fn calculate_attack_damage(attack: u32, defense: u32, ambush_bonus_bps: u32) -> u32 {
+    let base_damage = attack.saturating_sub(defense).max(1) as u64;
+    let scaled = base_damage * ambush_bonus_bps as u64 / BASIS_POINTS as u64;
+    (scaled.min(u32::MAX as u64) as u32).clamp(1, attack.max(1))
+}
+
+// ---------------------------------------------------------------------------
+// Income
+// ---------------------------------------------------------------------------
+
+/// Base yield of one collection from a cell, before ship multipliers
+pub fn cell_income(cell_type: u8) -> (u32, u32, u32, u32) {
+    match cell_type {
+        1 => (0, 0, 0, 3),  // Island
+        2 => (5, 2, 0, 0),  // Port
+        3 => (10, 0, 0, 0), // Treasure
+        _ => (0, 0, 0, 0),
+    }
+}
+
+/// Scale a yield by a basis-point multiplier, rounding down
+pub fn apply_multiplier(amount: u32, multiplier_bps: u32) -> u32 {
+    (amount as u64 * multiplier_bps as u64 / BASIS_POINTS as u64).min(u32::MAX as u64) as u32
+}
+
+// ---------------------------------------------------------------------------
+// Map flavor
+// ---------------------------------------------------------------------------
+
+/// Keeps flavor rolls independent of the map and seat-order draws
+const CELL_FLAVOR_DOMAIN: u64 = 0x464C_4156_4F52_5331; // "FLAVORS1"
+
+/// SplitMix64 finalizer - spreads a seed into a well-mixed 64-bit value
+pub fn splitmix64(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Cosmetic id for a cell ("Skull Reef", "Port Tortuga", ...), the same for
+/// every client of a game. Clients map it onto their own name and sprite
+/// tables, e.g. `flavor_id % table.len()`.
+pub fn cell_flavor_id(game_seed: u64, cell_index: usize) -> u16 {
+    splitmix64(splitmix64(game_seed ^ CELL_FLAVOR_DOMAIN) ^ cell_index as u64) as u16
+}
+
+// ---------------------------------------------------------------------------
+// Victory
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Victory {
+    FleetDominance,
+    TerritoryControl,
+    Economic,
+}
+
+impl Victory {
+    /// Rulebook order
+    pub const ALL: [Victory; 3] = [
+        Victory::FleetDominance,
+        Victory::TerritoryControl,
+        Victory::Economic,
+    ];
+
+    /// Flag for this condition in a set of conditions packed into a byte
+    pub fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// Share of a victory threshold at which a player is announced as closing in
+pub const VICTORY_WARNING_PERCENT: u32 = 80;
+
+/// Where one player stands against the victory conditions
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct VictoryProgress {
+    pub fleet_power: u32,
+    pub total_fleet_power: u32,
+    pub territories: u32,
+    pub valuable_territories: u32,
+    pub resource_value: u32,
+}
+
+/// Scoring weight of a stockpile
+pub fn resource_value(gold: u32, crew: u32, cannons: u32, supplies: u32) -> u32 {
+    gold + crew * 10 + cannons * 20 + supplies * 5
+}
+
+/// First victory condition a player meets, checked in rulebook order
+pub fn victory(
+    fleet_power: u32,
+    total_fleet_power: u32,
+    territories: u32,
+    valuable_territories: u32,
+    resource_value: u32,
+    thresholds: &VictoryThresholds,
+) -> Option<Victory> {
+    let progress = VictoryProgress {
+        fleet_power,
+        total_fleet_power,
+        territories,
+        valuable_territories,
+        resource_value,
+    };
+    victories_within(&progress, thresholds, 100)
+        .first()
+        .copied()
+}
+
+/// Every victory condition a player has reached `percent` of, in rulebook
+/// order; at 100 these are the conditions they meet
+pub fn victories_within(
+    progress: &VictoryProgress,
+    thresholds: &VictoryThresholds,
+    percent: u32,
+) -> Vec<Victory> {
+    // share / whole >= threshold% * percent%, without division
+    let reached = |share: u32, whole: u32, threshold_percent: u32| {
+        share as u64 * 10_000 >= whole as u64 * threshold_percent as u64 * percent as u64
+    };
+    let mut reached_victories = Vec::new();
+    if progress.total_fleet_power > 0
+        && reached(
+            progress.fleet_power,
+            progress.total_fleet_power,
+            thresholds.fleet_dominance_percent,
+        )
+    {
+        reached_victories.push(Victory::FleetDominance);
+    }
+    if progress.valuable_territories > 0
+        && reached(
+            progress.territories,
+            progress.valuable_territories,
+            thresholds.territory_control_percent,
+        )
+    {
+        reached_victories.push(Victory::TerritoryControl);
+    }
+    if reached(
+        progress.resource_value,
+        thresholds.economic_victory_value,
+        100,
+    ) {
+        reached_victories.push(Victory::Economic);
+    }
+    reached_victories
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_respect_bounds_and_speed() {
+        assert_eq!(check_move((0, 0), (1, 1), 1, true), Ok(()));
+        assert_eq!(
+            check_move((0, 0), (1, 1), 1, false),
+            Err(RuleViolation::ExceedsSpeed)
+        );
+        assert_eq!(
+            check_move((9, 9), (10, 9), 3, true),
+            Err(RuleViolation::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn paths_step_cell_by_cell_around_obstacles() {
+        let rock = |cell: (u8, u8)| cell == (1, 0);
+        let open = |_: (u8, u8)| false;
+        assert_eq!(
+            check_path((0, 0), &[(0, 1)], (1, 1), 2, false, rock, open),
+            Ok(())
+        );
+        assert_eq!(
+            check_path((0, 0), &[(1, 0)], (2, 0), 2, false, rock, open),
+            Err(RuleViolation::Blocked)
+        );
+        assert_eq!(
+            check_path((0, 0), &[], (2, 0), 3, false, rock, open),
+            Err(RuleViolation::BrokenPath)
+        );
+        assert_eq!(
+            check_path((0, 0), &[(0, 1), (0, 2)], (0, 3), 2, false, rock, open),
+            Err(RuleViolation::ExceedsSpeed)
+        );
+        assert_eq!(check_path((4, 4), &[], (4, 4), 1, true, rock, open), Ok(()));
+    }
+
+    #[test]
+    fn entering_a_zone_of_control_ends_the_move() {
+        // An enemy at (3, 1) controls its orthogonal neighbours
+        let zone = |cell: (u8, u8)| manhattan(cell, (3, 1)) == 1;
+        let open = |_: (u8, u8)| false;
+        assert_eq!(
+            check_path((1, 1), &[(2, 1)], (2, 2), 3, false, open, zone),
+            Err(RuleViolation::ZoneOfControl)
+        );
+        assert_eq!(
+            check_path((1, 0), &[(2, 0)], (3, 0), 3, false, open, zone),
+            Ok(())
+        );
+        // Leaving a zone is free
+        assert_eq!(
+            check_path((2, 1), &[(1, 1)], (0, 1), 3, false, open, zone),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn victory_conditions_apply_in_rulebook_order() {
+        let rules = &DEFAULT_VICTORY_THRESHOLDS;
+        assert_eq!(
+            victory(65, 100, 0, 10, 0, rules),
+            Some(Victory::FleetDominance)
+        );
+        assert_eq!(
+            victory(64, 100, 5, 10, 20_000, rules),
+            Some(Victory::TerritoryControl)
+        );
+        assert_eq!(
+            victory(0, 100, 4, 10, 10_000, rules),
+            Some(Victory::Economic)
+        );
+        assert_eq!(victory(0, 0, 0, 0, 9_999, rules), None);
+    }
+}
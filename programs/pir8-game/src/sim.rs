@@ -0,0 +1,1784 @@
+// ============================================================================
+// SIMULATION PROPERTY TESTS
+// ============================================================================
+// Host-only proptest harness over map generation, combat math and random
+// action sequences, to catch balance regressions before deploy.
+
+use crate::constants::*;
+use crate::errors::GameError;
+use crate::instructions::gameplay::{
+    apply_attack_ship, apply_claim_territory, apply_collect_resources, apply_move_ship,
+    apply_parley, apply_scan_coordinate,
+};
+use crate::instructions::matchmaking::grant_pity_bonus;
+use crate::rules::{victories_within, Victory, VictoryProgress, DEFAULT_VICTORY_THRESHOLDS};
+use crate::state::beacon::{move_deadline, TurnBeacon};
+use crate::state::cargo::{salvage, CARGO_HOLD_GOLD};
+use crate::state::config::{
+    validate_config_params, ConfigParams, DEFAULT_PITY_BONUS_GOLD, MAX_PITY_BONUS_GOLD,
+};
+use crate::state::contract::{
+    credit_sinking, take_contract, Contract, ContractTerms, CONTRACT_ROUNDS, MAX_OPEN_CONTRACTS,
+};
+use crate::state::diff::{
+    ShipChange, ShipDelta, StateSnapshot, STATE_DIFF_VERSION, TAG_RESOURCES, TAG_SHIP_HEALTH,
+    TAG_SHIP_REMOVED, TAG_TURN,
+};
+use crate::state::distributor::{prize_leaf, prize_proof, prize_root, verify_prize_proof};
+use crate::state::export::{encode_state, export_chunk};
+use crate::state::fork::practice_fork;
+use crate::state::game::{
+    begin_game, GameAction, GameMode, GameObjective, GameOptions, GameStatus, PirateGame,
+    VictoryType,
+};
+use crate::state::jackpot::split_pot;
+use crate::state::leaderboard::{
+    DecisionLeaderboard, LeaderboardEntry, LEADERBOARD_SIZE, MIN_RANKED_DECISIONS,
+};
+use crate::state::ledger::VaultLedger;
+use crate::state::lfg::{LfgBoard, LfgEntry};
+use crate::state::liveness::{
+    liveness_refundable, LIVENESS_DEPOSIT_LAMPORTS, MAX_FORGIVEN_TIMEOUTS,
+};
+use crate::state::map::{
+    generate_strategic_map, index_to_coordinate, TerritoryCellType, GOLDEN_PORT_POSITION,
+    SPAWN_FAIRNESS_RADIUS, SPAWN_POSITIONS,
+};
+use crate::state::naming::{is_valid_lobby_name, lobby_name_hash};
+use crate::state::player::{
+    calculate_attack_damage, consume_action_nonce, final_score, get_ship_stats, AgentRegistry,
+    Departure, PlayerRole, ScanDepth, ShipType, AMBUSH_DAMAGE_BONUS_BPS,
+};
+use crate::state::profile::{PlayerProfile, WagerWindow, PITY_STREAK_LOSSES, WAGER_WINDOW_SECONDS};
+use crate::state::revenue::{is_fee_treasury, revenue_splitter_address, FeeSplit, RevenueSplit};
+use crate::state::rng::RngStream;
+use crate::state::rulebook::{Rulebook, RulebookParams};
+use crate::state::scan_log::{revealed_cell_type, ScanLog, ScanLogEntry, MAX_SCAN_LOG_ENTRIES};
+use crate::state::scoring::{ScoringPreset, ScoringWeights, STANDARD_SCORING};
+use crate::state::session::FEE_SESSION_BUDGET_LAMPORTS;
+use crate::state::shipyard::ConstructionOrder;
+use crate::state::simultaneous::{
+    resolve_simultaneous_round, reveal_entropy, reveal_window_closed, OrderCommitment, RoundPhase,
+    ShipOrder,
+};
+use crate::state::tutorial::{
+    tutorial_finished, tutorial_game, TUTORIAL_DERELICT_SEAT, TUTORIAL_FLEET,
+    TUTORIAL_LEARNER_SEAT, TUTORIAL_PORT,
+};
+use crate::state::vesting::{vested_share, VestingTranche, VESTING_DELAY_SECONDS};
+use crate::test_utils::{current_player, play_turn, waiting_game, GameBuilder};
+use anchor_lang::prelude::*;
+use proptest::prelude::*;
+
+fn ship_type() -> impl Strategy<Value = ShipType> {
+    prop_oneof![
+        Just(ShipType::Sloop),
+        Just(ShipType::Frigate),
+        Just(ShipType::Galleon),
+        Just(ShipType::Flagship),
+    ]
+}
+
+#[derive(Clone, Debug)]
+enum SimAction {
+    Move {
+        ship: usize,
+        dx: i8,
+        dy: i8,
+    },
+    Attack {
+        ship: usize,
+        target_player: usize,
+        target_ship: usize,
+    },
+    Claim {
+        ship: usize,
+    },
+    Collect,
+    Scan {
+        x: u8,
+        y: u8,
+    },
+    Pass,
+}
+
+/// Map indices of the cells passed sailing along x, then along y
+fn sailing_path(from: (u8, u8), to: (u8, u8)) -> Vec<u8> {
+    let mut at = from;
+    let mut cells = Vec::new();
+    while at != to {
+        if at.0 != to.0 {
+            at.0 = if at.0 < to.0 { at.0 + 1 } else { at.0 - 1 };
+        } else {
+            at.1 = if at.1 < to.1 { at.1 + 1 } else { at.1 - 1 };
+        }
+        cells.push(at.0 * MAP_SIZE as u8 + at.1);
+    }
+    cells.pop();
+    cells
+}
+
+fn sim_action() -> impl Strategy<Value = SimAction> {
+    prop_oneof![
+        4 => (0..2usize, -3..=3i8, -3..=3i8).prop_map(|(ship, dx, dy)| SimAction::Move { ship, dx, dy }),
+        2 => (0..2usize, 0..4usize, 0..2usize).prop_map(|(ship, target_player, target_ship)| {
+            SimAction::Attack { ship, target_player, target_ship }
+        }),
+        1 => (0..2usize).prop_map(|ship| SimAction::Claim { ship }),
+        1 => Just(SimAction::Collect),
+        1 => (0..MAP_SIZE as u8, 0..MAP_SIZE as u8).prop_map(|(x, y)| SimAction::Scan { x, y }),
+        1 => Just(SimAction::Pass),
+    ]
+}
+
+/// Apply one action for the current player. Failed actions roll back, as a
+/// failed transaction would on-chain.
+fn step(game: &mut PirateGame, action: &SimAction) {
+    let before = game.clone();
+    let player = game.players[game.current_player_index as usize].pubkey;
+    let ship_id = |game: &PirateGame, player_idx: usize, ship: usize| {
+        game.players
+            .get(player_idx)
+            .and_then(|p| p.ships.get(ship))
+            .map(|s| s.id.clone())
+    };
+    let me = game.current_player_index as usize;
+
+    let result = match action.clone() {
+        SimAction::Move { ship, dx, dy } => match game.players[me].ships.get(ship) {
+            Some(s) => {
+                let (id, from, x, y) = (
+                    s.id.clone(),
+                    (s.position_x, s.position_y),
+                    s.position_x as i8 + dx,
+                    s.position_y as i8 + dy,
+                );
+                if x < 0 || y < 0 {
+                    Ok(())
+                } else {
+                    let via = sailing_path(from, (x as u8, y as u8));
+                    apply_move_ship(game, player, &id, x as u8, y as u8, &via, None)
+                }
+            }
+            None => Ok(()),
+        },
+        SimAction::Attack {
+            ship,
+            target_player,
+            target_ship,
+        } => {
+            match (
+                ship_id(game, me, ship),
+                ship_id(game, target_player, target_ship),
+            ) {
+                (Some(a), Some(t)) => apply_attack_ship(game, player, a, t),
+                _ => Ok(()),
+            }
+        }
+        SimAction::Claim { ship } => match ship_id(game, me, ship) {
+            Some(id) => apply_claim_territory(game, player, &id),
+            None => Ok(()),
+        },
+        SimAction::Collect => apply_collect_resources(game, player),
+        SimAction::Scan { x, y } => apply_scan_coordinate(game, player, x, y),
+        SimAction::Pass => Ok(()),
+    };
+
+    if result.is_err() {
+        *game = before;
+    }
+    game.advance_turn();
+}
+
+proptest! {
+    #[test]
+    fn generated_maps_contain_ports_and_treasure(seed in any::<u64>()) {
+        let map = generate_strategic_map(seed);
+        prop_assert_eq!(map.len(), MAP_SIZE * MAP_SIZE);
+
+        let ports = map.iter().filter(|c| c.cell_type == TerritoryCellType::Port).count();
+        let treasures = map.iter().filter(|c| c.cell_type == TerritoryCellType::Treasure).count();
+        prop_assert!(ports >= 4, "only {} ports for seed {}", ports, seed);
+        prop_assert!(treasures >= 2, "only {} treasures for seed {}", treasures, seed);
+    }
+
+    #[test]
+    fn every_spawn_has_water_port_and_resources(seed in any::<u64>()) {
+        let map = generate_strategic_map(seed);
+        let again = generate_strategic_map(seed);
+        prop_assert!(map.iter().zip(again.iter()).all(|(a, b)| a.cell_type == b.cell_type));
+
+        for spawns in SPAWN_POSITIONS.iter() {
+            for &(x, y) in spawns.iter() {
+                prop_assert_eq!(map[x as usize * MAP_SIZE + y as usize].cell_type, TerritoryCellType::Water);
+            }
+
+            let (sx, sy) = spawns[0];
+            let nearby: Vec<TerritoryCellType> = map
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| {
+                    let (x, y) = index_to_coordinate(*index);
+                    x.abs_diff(sx) + y.abs_diff(sy) <= SPAWN_FAIRNESS_RADIUS
+                })
+                .map(|(_, cell)| cell.cell_type)
+                .collect();
+            prop_assert!(nearby.contains(&TerritoryCellType::Port));
+            prop_assert!(nearby.contains(&TerritoryCellType::Island) || nearby.contains(&TerritoryCellType::Treasure));
+        }
+    }
+
+    #[test]
+    fn combat_damage_is_bounded(
+        attacker in ship_type(),
+        defender in ship_type(),
+        ambush in any::<bool>(),
+    ) {
+        let (_, attack, _, _) = get_ship_stats(&attacker);
+        let (_, _, defense, _) = get_ship_stats(&defender);
+        let bonus = if ambush { AMBUSH_DAMAGE_BONUS_BPS } else { BASIS_POINTS };
+
+        let damage = calculate_attack_damage(attack, defense, bonus);
+        prop_assert!(damage >= 1);
+        prop_assert!(damage <= attack);
+    }
+
+    #[test]
+    fn action_sequences_preserve_board_invariants(
+        seed in any::<u64>(),
+        player_count in MIN_PLAYERS..=MAX_PLAYERS,
+        actions in proptest::collection::vec(sim_action(), 0..60),
+    ) {
+        let mut game = waiting_game(player_count);
+        begin_game(&mut game, seed, 0, 0).unwrap();
+
+        prop_assert_eq!(game.spawn_positions.len(), player_count as usize * 2);
+        for spawn in game.spawn_positions.iter() {
+            let cell = game.territory_map[spawn.x as usize * MAP_SIZE + spawn.y as usize];
+            prop_assert_eq!(cell.cell_type, TerritoryCellType::Water);
+        }
+
+        for action in actions.iter() {
+            step(&mut game, action);
+
+            let ships: Vec<_> = game.players.iter().flat_map(|p| p.ships.iter()).collect();
+            for (i, ship) in ships.iter().enumerate() {
+                prop_assert!(ship.is_alive() && ship.health <= ship.max_health);
+                prop_assert!(ship.position_x < MAP_SIZE as u8 && ship.position_y < MAP_SIZE as u8);
+                prop_assert!(!ships[i + 1..]
+                    .iter()
+                    .any(|o| (o.position_x, o.position_y) == (ship.position_x, ship.position_y)));
+            }
+
+            for player in game.players.iter() {
+                prop_assert!(player.ships.len() <= MAX_SHIPS_PER_PLAYER);
+                prop_assert!(player.scan_charges <= 3);
+            }
+
+            for (index, cell) in game.territory_map.iter().enumerate() {
+                if let Some(owner) = cell.owner {
+                    let (x, y) = index_to_coordinate(index);
+                    let coord = format!("{},{}", x, y);
+                    prop_assert!(game
+                        .players
+                        .iter()
+                        .any(|p| p.pubkey == owner && p.controlled_territories.contains(&coord)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn flagship_loss_eliminates_its_owner(
+        seed in any::<u64>(),
+        player_count in MIN_PLAYERS..=MAX_PLAYERS,
+        actions in proptest::collection::vec(sim_action(), 0..60),
+    ) {
+        let mut game = waiting_game(player_count);
+        game.options.objective = GameObjective::CaptureTheFlagship;
+        begin_game(&mut game, seed, 0, 0).unwrap();
+
+        for action in actions.iter() {
+            step(&mut game, action);
+
+            for player in game.players.iter() {
+                let flagships = player.ships.iter().filter(|s| s.is_designated_flagship).count();
+                if player.is_active {
+                    prop_assert_eq!(flagships, 1);
+                } else {
+                    prop_assert!(player.ships.is_empty());
+                    prop_assert_eq!(player.role, PlayerRole::Spectator);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn golden_port_crowns_one_king(
+        seed in any::<u64>(),
+        player_count in MIN_PLAYERS..=MAX_PLAYERS,
+        target_points in 1u16..4,
+        actions in proptest::collection::vec(sim_action(), 0..60),
+    ) {
+        let mut game = waiting_game(player_count);
+        game.options.objective = GameObjective::KingOfTheHill { target_points };
+        begin_game(&mut game, seed, 0, 0).unwrap();
+        let (x, y) = GOLDEN_PORT_POSITION;
+        prop_assert_eq!(
+            game.territory_map[x as usize * MAP_SIZE + y as usize].cell_type,
+            TerritoryCellType::GoldenPort
+        );
+
+        for action in actions.iter() {
+            step(&mut game, action);
+
+            let points: u32 = game.players.iter().map(|p| p.victory_points as u32).sum();
+            prop_assert!(points < game.turn_number);
+            let kings = game
+                .players
+                .iter()
+                .filter(|p| p.victory_points >= target_points)
+                .count();
+            prop_assert!(kings <= 1);
+        }
+    }
+
+    #[test]
+    fn race_seats_get_home_ports_and_wrecks_spill_cargo(
+        seed in any::<u64>(),
+        player_count in MIN_PLAYERS..=MAX_PLAYERS,
+        cargo in 1..=CARGO_HOLD_GOLD,
+    ) {
+        let mut game = waiting_game(player_count);
+        game.options.objective = GameObjective::TreasureRace { target_gold: 100 };
+        begin_game(&mut game, seed, 0, 0).unwrap();
+
+        for (seat, player) in game.players.iter().enumerate() {
+            let port = player.home_port.unwrap() as usize;
+            prop_assert_eq!(game.territory_map[port].cell_type, TerritoryCellType::Port);
+            let (x, y) = index_to_coordinate(port);
+            let (sx, sy) = SPAWN_POSITIONS[seat][0];
+            prop_assert!(x.abs_diff(sx) + y.abs_diff(sy) <= SPAWN_FAIRNESS_RADIUS);
+        }
+
+        // Starting ships sit on open water, so a wreck leaves salvageable flotsam
+        let ship = &mut game.players[0].ships[0];
+        ship.cargo_gold = cargo;
+        ship.health = 0;
+        let wreck = ship.position_x as usize * MAP_SIZE + ship.position_y as usize;
+        game.purge_sunk_ships();
+
+        let cell = &mut game.territory_map[wreck];
+        prop_assert_eq!(cell.cell_type, TerritoryCellType::Flotsam);
+        prop_assert_eq!(salvage(cell, CARGO_HOLD_GOLD), cargo);
+        prop_assert_eq!(cell.cell_type, TerritoryCellType::Water);
+    }
+
+    #[test]
+    fn state_export_chunks_reassemble(
+        seed in any::<u64>(),
+        player_count in MIN_PLAYERS..=MAX_PLAYERS,
+        actions in proptest::collection::vec(sim_action(), 0..30),
+    ) {
+        let mut game = waiting_game(player_count);
+        begin_game(&mut game, seed, 0, 0).unwrap();
+        for action in actions.iter() {
+            step(&mut game, action);
+        }
+
+        let blob = encode_state(&game);
+        let ships: usize = game
+            .players
+            .iter()
+            .flat_map(|p| p.ships.iter())
+            .map(|s| 1 + s.id.len() + 3 + 20)
+            .sum();
+        let expected = 46 + game.players.len() * 54 + ships + MAP_SIZE * MAP_SIZE * 6;
+        prop_assert_eq!(blob.len(), expected);
+
+        let first = export_chunk(&blob, 0).unwrap();
+        let mut reassembled = Vec::new();
+        for index in 0..first.chunk_count {
+            let chunk = export_chunk(&blob, index).unwrap();
+            prop_assert_eq!(chunk.blob_hash, first.blob_hash);
+            reassembled.extend_from_slice(chunk.data);
+        }
+        prop_assert!(export_chunk(&blob, first.chunk_count).is_none());
+        prop_assert_eq!(reassembled, blob);
+    }
+}
+
+#[test]
+fn tutorial_checkpoints_are_reached_in_order() {
+    let learner = Pubkey::new_unique();
+    let mut game = tutorial_game(1, learner, 255, 0);
+    let sloop = game.players[TUTORIAL_LEARNER_SEAT].ships[0].id.clone();
+    let derelict = game.players[TUTORIAL_DERELICT_SEAT].ships[0].id.clone();
+    let (port_x, port_y) = TUTORIAL_PORT;
+
+    // Both lessons done in one turn land together at the end of the round,
+    // and the derelict's seat never takes a turn
+    let via = sailing_path(TUTORIAL_FLEET[0].1, TUTORIAL_PORT);
+    apply_move_ship(&mut game, learner, &sloop, port_x, port_y, &via, None).unwrap();
+    apply_claim_territory(&mut game, learner, &sloop).unwrap();
+    game.advance_turn();
+    assert_eq!(game.tutorial_checkpoint, 2);
+    assert_eq!(game.current_player_index as usize, TUTORIAL_LEARNER_SEAT);
+
+    // Skipping ahead doesn't count until the earlier lesson is done
+    apply_scan_coordinate(&mut game, learner, 9, 9).unwrap();
+    game.advance_turn();
+    assert_eq!(game.tutorial_checkpoint, 2);
+    apply_collect_resources(&mut game, learner).unwrap();
+    game.advance_turn();
+    assert_eq!(game.tutorial_checkpoint, 4);
+
+    apply_move_ship(
+        &mut game,
+        learner,
+        &sloop,
+        port_x + 1,
+        port_y + 1,
+        &[],
+        None,
+    )
+    .unwrap();
+    apply_attack_ship(&mut game, learner, sloop, derelict).unwrap();
+    game.advance_turn();
+    assert_eq!(game.tutorial_checkpoint, 4);
+
+    game.construction_queue.push(ConstructionOrder {
+        owner: learner,
+        ship_type: ShipType::Galleon,
+        port_x,
+        port_y,
+        turns_remaining: 2,
+    });
+    game.advance_turn();
+    assert!(tutorial_finished(&game));
+
+    // The derelict is inactive, but a tutorial is never won by default
+    assert!(!game.complete_if_last_standing(0, VictoryType::Resignation));
+    assert_eq!(game.status, GameStatus::Active);
+}
+
+#[test]
+fn ranked_joins_reject_a_shared_funding_wallet() {
+    let mut game = waiting_game(0);
+    game.mode = GameMode::Competitive;
+    let (creator, funder, smurf) = (game.authority, Pubkey::new_unique(), Pubkey::new_unique());
+    game.log_signer(creator);
+
+    // The creator takes their own seat; a session key funds a second one
+    assert!(game.check_join_funding(&creator, &creator).is_ok());
+    assert!(game
+        .check_join_funding(&Pubkey::new_unique(), &funder)
+        .is_ok());
+    game.log_signer(funder);
+
+    // The same wallet can't fund or take another seat
+    assert!(game.check_join_funding(&smurf, &funder).is_err());
+    assert!(game.check_join_funding(&funder, &funder).is_err());
+    assert!(game.check_join_funding(&smurf, &creator).is_err());
+
+    game.mode = GameMode::Casual;
+    assert!(game.check_join_funding(&smurf, &funder).is_ok());
+
+    for _ in 0..2 * MAX_LOGGED_SIGNERS {
+        game.log_signer(Pubkey::new_unique());
+    }
+    assert_eq!(game.signer_log.len(), MAX_LOGGED_SIGNERS);
+}
+
+#[test]
+fn reveal_order_ignores_arrival_and_non_reveals_pass() {
+    let mut game = waiting_game(2);
+    game.options.simultaneous_turns = true;
+    begin_game(&mut game, 7, 0, 0).unwrap();
+    let round = game.turn_number;
+
+    let ship = &game.players[0].ships[0];
+    let (from_x, from_y) = (ship.position_x, ship.position_y);
+    let to_x = if from_x > 0 { from_x - 1 } else { from_x + 1 };
+    let orders = vec![ShipOrder {
+        ship_index: 0,
+        to_x,
+        to_y: from_y,
+        via: Vec::new(),
+        attack_target: None,
+    }];
+    let commitment = |player_index: u8, revealed: bool| OrderCommitment {
+        player_index,
+        commitment: [player_index + 1; 32],
+        revealed,
+        orders: if revealed { orders.clone() } else { Vec::new() },
+        salt: if revealed { [9; 32] } else { [0; 32] },
+    };
+
+    // The tiebreak seed is the same whichever commitment landed first
+    game.pending_orders = vec![commitment(0, true), commitment(1, false)];
+    let in_seat_order = reveal_entropy(&game);
+    game.pending_orders.reverse();
+    assert_eq!(reveal_entropy(&game), in_seat_order);
+
+    // Seat 1 never revealed: the window has to run out, then it passes
+    game.round_phase = RoundPhase::Reveal;
+    game.turn_started_at = 100;
+    assert!(!reveal_window_closed(
+        &game,
+        100 + REVEAL_WINDOW_SECONDS - 1
+    ));
+    assert!(reveal_window_closed(&game, 100 + REVEAL_WINDOW_SECONDS));
+
+    let fleet_before: Vec<(u8, u8)> = game.players[1]
+        .ships
+        .iter()
+        .map(|s| (s.position_x, s.position_y))
+        .collect();
+    resolve_simultaneous_round(&mut game).unwrap();
+
+    let ship = &game.players[0].ships[0];
+    assert_eq!((ship.position_x, ship.position_y), (to_x, from_y));
+    let fleet_after: Vec<(u8, u8)> = game.players[1]
+        .ships
+        .iter()
+        .map(|s| (s.position_x, s.position_y))
+        .collect();
+    assert_eq!(fleet_after, fleet_before);
+    assert_eq!(game.turn_number, round + 1);
+    assert_eq!(game.round_phase, RoundPhase::Commit);
+}
+
+#[test]
+fn scoring_presets_are_valid_and_reorder_standings() {
+    for preset in [
+        ScoringPreset::Standard,
+        ScoringPreset::Warfare,
+        ScoringPreset::Territorial,
+        ScoringPreset::Mercantile,
+    ] {
+        assert!(preset.weights().is_valid());
+    }
+    let zeroed = ScoringWeights {
+        ship: 0,
+        health: 0,
+        territory: 0,
+        resources: 0,
+    };
+    assert!(!ScoringPreset::Custom(zeroed).weights().is_valid());
+    assert!(!ScoringPreset::Custom(ScoringWeights {
+        health: 11,
+        ..STANDARD_SCORING
+    })
+    .weights()
+    .is_valid());
+
+    // One captain holds territory, the other a fuller hold
+    let mut game = waiting_game(2);
+    game.players[0].controlled_territories = vec!["0,0".to_string(), "0,1".to_string()];
+    game.players[1].resources.gold += 400;
+    let ranking = |weights: &ScoringWeights| {
+        let (a, b) = (
+            final_score(&game.players[0], weights),
+            final_score(&game.players[1], weights),
+        );
+        a > b
+    };
+    assert!(!ranking(&ScoringPreset::Mercantile.weights()));
+    assert!(ranking(&ScoringPreset::Territorial.weights()));
+}
+
+#[test]
+fn retried_actions_are_rejected_by_the_nonce() {
+    let mut game = waiting_game(2);
+    let seat = &mut game.players[0];
+
+    assert!(consume_action_nonce(seat, 0).is_ok());
+    // A wallet retrying the transaction that just landed
+    assert!(consume_action_nonce(seat, 0).is_err());
+    // Or skipping ahead
+    assert!(consume_action_nonce(seat, 2).is_err());
+    assert!(consume_action_nonce(seat, 1).is_ok());
+    assert_eq!(seat.action_nonce, 2);
+
+    // Seats count independently
+    assert!(consume_action_nonce(&mut game.players[1], 0).is_ok());
+}
+
+#[test]
+fn turn_summary_reports_the_same_changes_as_the_state_diff() {
+    let mut game = waiting_game(2);
+    begin_game(&mut game, 11, 0, 0).unwrap();
+    let before = StateSnapshot::capture(&game);
+    assert!(before.changes(&game).is_empty());
+    assert_eq!(before.encode_diff(&game), None);
+
+    let target = &mut game.players[1].ships[0];
+    target.health -= 7;
+    game.players[0].ships.remove(1);
+    game.players[0].resources.gold += 25;
+    game.advance_turn();
+
+    let changes = before.changes(&game);
+    assert_eq!(
+        changes.ships,
+        vec![
+            ShipDelta {
+                player: 0,
+                ship: 1,
+                change: ShipChange::Removed,
+            },
+            ShipDelta {
+                player: 1,
+                ship: 0,
+                change: ShipChange::Health {
+                    health: game.players[1].ships[0].health,
+                    damage: 7,
+                },
+            },
+        ]
+    );
+    assert_eq!(changes.resources.len(), 1);
+    assert_eq!(changes.resources[0].gold, 25);
+    assert!(changes.turn.is_some());
+
+    // The compact summary and the tagged payload carry the same entries
+    let data = changes.encode().unwrap();
+    assert_eq!(data[0], STATE_DIFF_VERSION);
+    for tag in [TAG_TURN, TAG_SHIP_REMOVED, TAG_SHIP_HEALTH, TAG_RESOURCES] {
+        assert!(data.contains(&tag));
+    }
+}
+
+#[test]
+fn finishers_share_a_capped_move_rebate_pool() {
+    let mut game = waiting_game(3);
+    game.total_pot = 3 * SOL_ENTRY_FEE_LAMPORTS;
+    for (player, moves) in game.players.iter_mut().zip([30u16, 10, 40]) {
+        player.total_moves = moves;
+        player.pot_eligible = true;
+    }
+    // The third captain quit: no standing, no rebate
+    game.players[2].pot_eligible = false;
+    let (first, second, quitter) = (
+        game.players[0].pubkey,
+        game.players[1].pubkey,
+        game.players[2].pubkey,
+    );
+
+    game.record_standings(&[first]);
+    assert_eq!(game.rebate_pool, 40 * MOVE_REBATE_LAMPORTS);
+    assert_eq!(game.move_rebate(&first), 30 * MOVE_REBATE_LAMPORTS);
+    assert_eq!(game.move_rebate(&second), 10 * MOVE_REBATE_LAMPORTS);
+    assert_eq!(game.move_rebate(&quitter), 0);
+    assert_eq!(
+        game.payout_pot + game.creator_fee_due + game.rebate_pool,
+        game.total_pot
+    );
+
+    // A small pot caps the pool, and the finishers split it by actions
+    game.total_pot = 1_000_000;
+    game.record_standings(&[first]);
+    let cap = 1_000_000 * MAX_REBATE_POOL_BPS as u64 / BASIS_POINTS as u64;
+    assert_eq!(game.rebate_pool, cap);
+    assert_eq!(game.move_rebate(&first), cap * 3 / 4);
+    assert_eq!(game.move_rebate(&second), cap / 4);
+}
+
+#[test]
+fn turn_constraints_track_seats_and_the_current_turn() {
+    let mut game = waiting_game(3);
+    let (first, second, third) = (
+        game.players[0].pubkey,
+        game.players[1].pubkey,
+        game.players[2].pubkey,
+    );
+    game.current_player_index = 0;
+    assert!(game.is_current_player(&first));
+    assert!(!game.is_current_player(&second));
+    assert!(!game.has_seat(&Pubkey::new_unique()));
+
+    // A departed seat can still act out of turn, but never holds the turn
+    game.players[2].is_active = false;
+    game.current_player_index = 2;
+    assert!(game.has_seat(&third));
+    assert!(!game.is_current_player(&third));
+}
+
+#[test]
+fn repeated_agent_strikes_slash_the_bond() {
+    let mut agent = AgentRegistry {
+        owner: Pubkey::new_unique(),
+        delegate: None,
+        name: "bot".into(),
+        version: "1".into(),
+        twitter: None,
+        website: None,
+        games_played: 0,
+        wins: 0,
+        last_active: 0,
+        strategy_hash: [0; 32],
+        strategy_updated_at: 0,
+        bond: 1_000_000,
+        bond_locked_until: 0,
+        strikes: 0,
+    };
+
+    agent.lock_bond(100);
+    assert_eq!(agent.bond_locked_until, 100 + AGENT_BOND_LOCK_SECONDS);
+
+    // The first strike is forgiven; every one after takes a share of what is left
+    assert_eq!(agent.record_strike(), 0);
+    assert_eq!(agent.record_strike(), 250_000);
+    assert_eq!(agent.record_strike(), 187_500);
+    assert_eq!(agent.bond, 562_500);
+    assert_eq!(agent.strikes, 3);
+
+    // An unbonded agent is never locked
+    agent.bond = 0;
+    agent.bond_locked_until = 0;
+    agent.lock_bond(100);
+    assert_eq!(agent.bond_locked_until, 0);
+}
+
+#[test]
+fn turn_beacons_ring_for_the_player_to_move() {
+    let mut game = waiting_game(2);
+    game.status = GameStatus::Active;
+    game.current_player_index = 0;
+    game.turn_number = 4;
+    game.turn_started_at = 1_000;
+    let (first, second) = (game.players[0].pubkey, game.players[1].pubkey);
+    let bank = game.players[0].time_bank_seconds as i64;
+
+    let beacon_for = |player| TurnBeacon {
+        player,
+        game_id: 0,
+        turn_number: 0,
+        deadline: 0,
+        bump: 0,
+    };
+    let mut on_turn = beacon_for(first);
+    let mut waiting = beacon_for(second);
+    on_turn.ring(&game);
+    waiting.ring(&game);
+    assert_eq!(
+        (on_turn.game_id, on_turn.turn_number, on_turn.deadline),
+        (game.game_id, 4, 1_000 + bank)
+    );
+    assert_eq!((waiting.game_id, waiting.deadline), (0, 0));
+
+    // Once the turn passes, the mover's beacon stands down
+    game.current_player_index = 1;
+    on_turn.ring(&game);
+    assert_eq!((on_turn.game_id, on_turn.deadline), (game.game_id, 0));
+    assert_eq!(move_deadline(&game, &second), Some(1_000 + bank));
+
+    // Simultaneous: on the commit window until committed, then on the reveal window
+    game.options.simultaneous_turns = true;
+    assert_eq!(
+        move_deadline(&game, &first),
+        Some(1_000 + COMMIT_WINDOW_SECONDS)
+    );
+    game.pending_orders.push(OrderCommitment {
+        player_index: 0,
+        commitment: [0; 32],
+        revealed: false,
+        orders: Vec::new(),
+        salt: [0; 32],
+    });
+    assert_eq!(move_deadline(&game, &first), None);
+    game.round_phase = RoundPhase::Reveal;
+    assert_eq!(
+        move_deadline(&game, &first),
+        Some(1_000 + REVEAL_WINDOW_SECONDS)
+    );
+}
+
+#[test]
+fn ships_sail_around_islands_and_enemy_ships() {
+    let mut game = waiting_game(2);
+    begin_game(&mut game, 7, 0, 0).unwrap();
+    let me = game.players[0].pubkey;
+    let ship = game.players[0].ships[0].clone();
+    let from = (ship.position_x, ship.position_y);
+    let index = |(x, y): (u8, u8)| x as usize * MAP_SIZE + y as usize;
+
+    // Clear a two-step lane, then put an island in the middle of it
+    let step_x = if from.0 < 5 { from.0 + 1 } else { from.0 - 1 };
+    let beyond = if from.0 < 5 { from.0 + 2 } else { from.0 - 2 };
+    for cell in [(step_x, from.1), (beyond, from.1)] {
+        game.territory_map[index(cell)].cell_type = TerritoryCellType::Water;
+        for player in game.players.iter_mut() {
+            player
+                .ships
+                .retain(|s| (s.position_x, s.position_y) != cell);
+        }
+    }
+    game.territory_map[index((step_x, from.1))].cell_type = TerritoryCellType::Island;
+    let via = [index((step_x, from.1)) as u8];
+    let blocked = apply_move_ship(&mut game, me, &ship.id, beyond, from.1, &via, None);
+    assert_eq!(blocked, Err(GameError::PathBlocked.into()));
+
+    // An enemy ship blocks the lane just the same
+    game.territory_map[index((step_x, from.1))].cell_type = TerritoryCellType::Water;
+    let mut enemy = game.players[1].ships[0].clone();
+    (enemy.position_x, enemy.position_y) = (step_x, from.1);
+    game.players[1].ships[0] = enemy;
+    let blocked = apply_move_ship(&mut game, me, &ship.id, beyond, from.1, &via, None);
+    assert_eq!(blocked, Err(GameError::PathBlocked.into()));
+
+    // Skipping the cell in between is no path at all
+    let jumped = apply_move_ship(&mut game, me, &ship.id, beyond, from.1, &[], None);
+    assert_eq!(jumped, Err(GameError::InvalidPath.into()));
+
+    game.players[1].ships[0].position_x = from.0;
+    game.players[1].ships[0].position_y = if from.1 < 5 { from.1 + 3 } else { from.1 - 3 };
+    apply_move_ship(&mut game, me, &ship.id, beyond, from.1, &via, None).unwrap();
+}
+
+#[test]
+fn ranked_moves_stop_in_an_enemy_zone_of_control() {
+    let mut game = waiting_game(2);
+    begin_game(&mut game, 7, 0, 0).unwrap();
+    for cell in game.territory_map.iter_mut() {
+        cell.cell_type = TerritoryCellType::Water;
+    }
+    let me = game.players[0].pubkey;
+    game.players[0].ships.truncate(1);
+    game.players[1].ships.truncate(1);
+    let id = game.players[0].ships[0].id.clone();
+    let place = |game: &mut PirateGame, seat: usize, at: (u8, u8)| {
+        let ship = &mut game.players[seat].ships[0];
+        (ship.position_x, ship.position_y) = at;
+    };
+
+    // Sailing (1,1) -> (2,1) -> (2,2) passes (2,1), next to an enemy at (3,1)
+    place(&mut game, 1, (3, 1));
+    let via = [2 * MAP_SIZE as u8 + 1];
+    game.mode = GameMode::Competitive;
+    place(&mut game, 0, (1, 1));
+    let stopped = apply_move_ship(&mut game, me, &id, 2, 2, &via, None);
+    assert_eq!(stopped, Err(GameError::ZoneOfControl.into()));
+
+    // Ending the move there is fine, and casual games ignore the zone
+    apply_move_ship(&mut game, me, &id, 2, 1, &[], None).unwrap();
+    game.mode = GameMode::Casual;
+    place(&mut game, 0, (1, 1));
+    apply_move_ship(&mut game, me, &id, 2, 2, &via, None).unwrap();
+}
+
+#[test]
+fn sudden_death_shrinks_the_map_from_the_edge() {
+    let mut game = waiting_game(2);
+    begin_game(&mut game, 7, 0, 0).unwrap();
+    let me = game.players[0].pubkey;
+    let index = |(x, y): (u8, u8)| x as usize * MAP_SIZE + y as usize;
+    game.territory_map[index((0, 4))].owner = Some(me);
+    game.players[0]
+        .controlled_territories
+        .push("0,4".to_string());
+    game.players[0].ships.truncate(1);
+    let ship = &mut game.players[0].ships[0];
+    (ship.position_x, ship.position_y) = (0, 5);
+    let health = ship.health;
+
+    game.turn_number = SUDDEN_DEATH_TURN;
+    game.shrink_map();
+    assert_ne!(
+        game.territory_map[index((0, 4))].cell_type,
+        TerritoryCellType::Storm
+    );
+
+    game.turn_number = SUDDEN_DEATH_TURN + 1;
+    game.shrink_map();
+    let edge = &game.territory_map[index((0, 4))];
+    assert_eq!(edge.cell_type, TerritoryCellType::Storm);
+    assert_eq!(edge.owner, None);
+    assert!(game.players[0].controlled_territories.is_empty());
+    assert_ne!(
+        game.territory_map[index((1, 4))].cell_type,
+        TerritoryCellType::Storm
+    );
+    assert_eq!(
+        game.players[0].ships[0].health,
+        health - SUDDEN_DEATH_STORM_DAMAGE
+    );
+
+    // The storm stops at the inner rings and keeps battering those caught in it
+    let center = index((MAP_SIZE as u8 / 2, MAP_SIZE as u8 / 2));
+    let center_type = game.territory_map[center].cell_type;
+    game.turn_number = SUDDEN_DEATH_TURN + 10;
+    game.shrink_map();
+    assert_eq!(game.territory_map[center].cell_type, center_type);
+    assert_eq!(
+        game.players[0].ships[0].health,
+        health - 2 * SUDDEN_DEATH_STORM_DAMAGE
+    );
+}
+
+#[test]
+fn forks_play_on_from_the_source_position_for_nothing() {
+    let mut source = waiting_game(2);
+    begin_game(&mut source, 7, 0, 0).unwrap();
+    source.turn_number = 12;
+    source.total_pot = 2 * SOL_ENTRY_FEE_LAMPORTS;
+    source.players[0].bonded_agent = true;
+    let winner = source.players[0].pubkey;
+    source.complete_with_winner(100, winner, VictoryType::TimeLimit);
+
+    let forker = Pubkey::new_unique();
+    let mut fork = practice_fork(&source, 9, forker, 255, 200);
+    assert_eq!(fork.status, GameStatus::Active);
+    assert_eq!(fork.forked_from, Some(source.game_id));
+    assert_eq!((fork.turn_number, fork.authority), (12, forker));
+    assert_eq!((fork.winner, fork.total_pot), (None, 0));
+    assert!(fork.standings.is_empty() && !fork.players[0].bonded_agent);
+    let board = |game: &PirateGame| {
+        let ships: Vec<_> = game
+            .players
+            .iter()
+            .flat_map(|p| p.ships.iter())
+            .map(|s| (s.id.clone(), s.position_x, s.position_y, s.health))
+            .collect();
+        let cells: Vec<_> = game
+            .territory_map
+            .iter()
+            .map(|c| (c.cell_type, c.owner))
+            .collect();
+        (ships, cells)
+    };
+    assert_eq!(board(&fork), board(&source));
+
+    // The fork is played on by the source's seats, leaving the source alone
+    let seat = fork.get_current_player().unwrap().pubkey;
+    apply_scan_coordinate(&mut fork, seat, 5, 5).unwrap();
+    fork.advance_turn();
+    assert!(source
+        .players
+        .iter()
+        .all(|p| p.scanned_coordinates.is_empty()));
+    assert_eq!(source.status, GameStatus::Completed);
+}
+
+#[test]
+fn completion_seals_a_reproducible_final_state_hash() {
+    let mut game = waiting_game(2);
+    begin_game(&mut game, 7, 0, 0).unwrap();
+    let mut replay = game.clone();
+    let winner = game.players[1].pubkey;
+    game.complete_with_winner(100, winner, VictoryType::TimeLimit);
+    replay.complete_with_winner(100, winner, VictoryType::TimeLimit);
+
+    assert_ne!(game.final_state_hash, [0; 32]);
+    assert_eq!(game.final_state_hash, replay.final_state_hash);
+    let blob = encode_state(&game);
+    let chunk = export_chunk(&blob, 0).unwrap();
+    assert_eq!(game.final_state_hash, chunk.blob_hash);
+}
+
+#[test]
+fn prize_proofs_verify_only_their_own_leaf() {
+    let wallets: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+    let leaves: Vec<[u8; 32]> = wallets
+        .iter()
+        .enumerate()
+        .map(|(i, w)| prize_leaf(i as u32, w, 1_000 * (i as u64 + 1)))
+        .collect();
+    let root = prize_root(&leaves);
+
+    for (i, wallet) in wallets.iter().enumerate() {
+        let proof = prize_proof(&leaves, i);
+        let amount = 1_000 * (i as u64 + 1);
+        assert!(verify_prize_proof(
+            &proof,
+            &root,
+            prize_leaf(i as u32, wallet, amount)
+        ));
+        // Not for more, not for someone else and not under another index
+        assert!(!verify_prize_proof(
+            &proof,
+            &root,
+            prize_leaf(i as u32, wallet, amount + 1)
+        ));
+        let other = &wallets[(i + 1) % wallets.len()];
+        assert!(!verify_prize_proof(
+            &proof,
+            &root,
+            prize_leaf(i as u32, other, amount)
+        ));
+        let shifted = prize_leaf((i as u32 + 1) % 5, wallet, amount);
+        assert!(!verify_prize_proof(&proof, &root, shifted));
+    }
+}
+
+#[test]
+fn vault_ledger_balances_money_in_against_money_out() {
+    let mut ledger = VaultLedger::default();
+    for _ in 0..3 {
+        ledger.deposit(SOL_ENTRY_FEE_LAMPORTS);
+    }
+    ledger.pay_out(FEE_SESSION_BUDGET_LAMPORTS);
+
+    // A prize claim splits between the winner and the fees
+    let pot = 2 * SOL_ENTRY_FEE_LAMPORTS;
+    let split = split_pot(pot);
+    ledger.pay_out(split.payout);
+    ledger.accrue_fee(split.platform_fee + split.jackpot_contribution);
+    assert_eq!(
+        ledger.balance(),
+        Some(SOL_ENTRY_FEE_LAMPORTS - FEE_SESSION_BUDGET_LAMPORTS)
+    );
+
+    // Paying out more than came in can never reconcile
+    ledger.pay_out(SOL_ENTRY_FEE_LAMPORTS);
+    assert_eq!(ledger.balance(), None);
+}
+
+#[test]
+fn resignations_can_be_withdrawn_until_play_moves_on() {
+    let mut game = waiting_game(3);
+    begin_game(&mut game, 7, 0, 0).unwrap();
+    game.resign_grace = true;
+    let resign = |game: &mut PirateGame, seat: usize, now: i64| {
+        let player = &mut game.players[seat];
+        player.is_active = false;
+        player.departure = Departure::Resigned;
+        let pubkey = player.pubkey;
+        game.open_resignation_grace(pubkey, now);
+        pubkey
+    };
+
+    // Someone else cannot withdraw it, and the owner can only once
+    let quitter = resign(&mut game, 1, 100);
+    let other = game.players[0].pubkey;
+    let not_theirs = game.withdraw_resignation(&other, 110);
+    assert_eq!(not_theirs, Err(GameError::NoResignationToCancel.into()));
+    game.withdraw_resignation(&quitter, 110).unwrap();
+    assert!(game.players[1].is_active);
+    assert_eq!(game.players[1].departure, Departure::Stayed);
+    let again = game.withdraw_resignation(&quitter, 110);
+    assert_eq!(again, Err(GameError::NoResignationToCancel.into()));
+
+    // Too late once the window closes or the turn passes
+    resign(&mut game, 1, 200);
+    let late = game.withdraw_resignation(&quitter, 200 + RESIGN_GRACE_SECONDS + 1);
+    assert_eq!(late, Err(GameError::ResignationGraceOver.into()));
+    game.advance_turn();
+    let moved_on = game.withdraw_resignation(&quitter, 210);
+    assert_eq!(moved_on, Err(GameError::ResignationGraceOver.into()));
+
+    // Without the config flag there is nothing to withdraw
+    game.resign_grace = false;
+    game.pending_resignation = None;
+    resign(&mut game, 2, 300);
+    let ungraced = game.players[2].pubkey;
+    let refused = game.withdraw_resignation(&ungraced, 300);
+    assert_eq!(refused, Err(GameError::NoResignationToCancel.into()));
+}
+
+#[test]
+fn lfg_entries_match_within_each_others_band() {
+    let entry = |rating: u32, min_rating: u32, max_rating: u32| LfgEntry {
+        player: Pubkey::new_unique(),
+        rating,
+        min_rating,
+        max_rating,
+        mode: GameMode::Competitive,
+        stake_level: 1,
+        expires_at: 100,
+    };
+    let host = entry(1200, 1100, 1300);
+    assert!(host.matches(&entry(1250, 1000, 1400)));
+    assert_eq!(host.stake_lamports(), 2 * SOL_ENTRY_FEE_LAMPORTS);
+
+    // Both bands must hold, and the game wanted must be the same
+    assert!(!host.matches(&entry(1350, 1000, 1400)));
+    assert!(!host.matches(&entry(1250, 1210, 1400)));
+    let cheaper = LfgEntry {
+        stake_level: 0,
+        ..entry(1250, 1000, 1400)
+    };
+    assert!(!host.matches(&cheaper));
+    let casual = LfgEntry {
+        mode: GameMode::Casual,
+        ..entry(1250, 1000, 1400)
+    };
+    assert!(!host.matches(&casual));
+    assert!(!host.matches(&host));
+
+    // Expired entries cannot be taken and are swept away
+    let mut board = LfgBoard {
+        entries: vec![host.clone(), entry(1250, 1000, 1400)],
+        bump: 0,
+    };
+    assert_eq!(board.take(&host.player, 100), None);
+    board.sweep_expired(100);
+    assert!(board.entries.is_empty());
+}
+
+#[test]
+fn games_play_the_rulebook_they_started_with() {
+    let defaults = RulebookParams::default();
+    assert!(defaults.is_valid());
+    assert_eq!(
+        defaults.ship_stats(&ShipType::Galleon),
+        get_ship_stats(&ShipType::Galleon)
+    );
+    assert_eq!(defaults.speed_bonus(5_000), 100);
+    assert_eq!(defaults.speed_bonus(5_001), 50);
+    assert_eq!(defaults.speed_bonus(15_001), 0);
+
+    let mut patched = defaults.clone();
+    patched.ship_stats[0].health = 140;
+    patched.fleet_dominance_percent = 90;
+    let mut rulebook = Rulebook {
+        authority: Pubkey::new_unique(),
+        mode: GameMode::Casual,
+        version: 2,
+        params: patched,
+        bump: 0,
+    };
+
+    let mut game = waiting_game(2);
+    game.adopt_rulebook(Some(&rulebook));
+    begin_game(&mut game, 7, 0, 0).unwrap();
+    assert_eq!(game.rulebook_version, 2);
+    assert_eq!(game.rules.victory_thresholds().fleet_dominance_percent, 90);
+    let sloop = game.players[0]
+        .ships
+        .iter()
+        .find(|ship| ship.ship_type == ShipType::Sloop)
+        .unwrap();
+    assert_eq!(sloop.max_health, 140);
+
+    // A later patch reaches games started after it, not the one in flight
+    rulebook.version = 3;
+    rulebook.params.ship_stats[0].health = 60;
+    let mut next = waiting_game(2);
+    next.adopt_rulebook(Some(&rulebook));
+    assert_eq!(next.rules.ship_stats(&ShipType::Sloop).0, 60);
+    assert_eq!(game.rules.ship_stats(&ShipType::Sloop).0, 140);
+
+    // Nor may a patch strand ships or set unreachable thresholds
+    let mut broken = defaults.clone();
+    broken.ship_stats[1].speed = 0;
+    assert!(!broken.is_valid());
+    let mut broken = defaults;
+    broken.territory_control_percent = 101;
+    assert!(!broken.is_valid());
+}
+
+#[test]
+fn scans_are_a_free_action_once_per_turn() {
+    let mut game = waiting_game(2);
+    begin_game(&mut game, 7, 0, 0).unwrap();
+    let seat = game.current_player_index;
+    let scanner = game.players[seat as usize].pubkey;
+
+    apply_scan_coordinate(&mut game, scanner, 4, 4).unwrap();
+    assert_eq!(game.current_player_index, seat);
+    let again = apply_scan_coordinate(&mut game, scanner, 4, 5);
+    assert_eq!(again, Err(GameError::AlreadyScannedThisTurn.into()));
+
+    // The allowance comes back with the scanner's next turn
+    game.advance_turn();
+    game.advance_turn();
+    assert_eq!(game.current_player_index, seat);
+    apply_scan_coordinate(&mut game, scanner, 4, 5).unwrap();
+}
+
+#[test]
+fn spectator_tips_grow_the_pot_that_gets_paid_out() {
+    let mut game = waiting_game(2);
+    begin_game(&mut game, 7, 0, 0).unwrap();
+    game.total_pot = 2 * SOL_ENTRY_FEE_LAMPORTS;
+    game.ledger.deposit(game.total_pot);
+
+    let fan = Pubkey::new_unique();
+    game.add_tip(&fan, SOL_ENTRY_FEE_LAMPORTS).unwrap();
+    assert_eq!(game.tips_total, SOL_ENTRY_FEE_LAMPORTS);
+    assert_eq!(game.ledger.balance(), Some(3 * SOL_ENTRY_FEE_LAMPORTS));
+
+    // Captains, empty tips and tips past the cap or the cutoff are refused
+    let captain = game.players[0].pubkey;
+    let own_game = game.add_tip(&captain, 1);
+    assert_eq!(own_game, Err(GameError::CaptainCannotTip.into()));
+    assert_eq!(game.add_tip(&fan, 0), Err(GameError::InvalidTip.into()));
+    let over_cap = game.add_tip(&fan, MAX_TIPS_LAMPORTS);
+    assert_eq!(over_cap, Err(GameError::InvalidTip.into()));
+    game.turn_number = TIP_CUTOFF_TURN + 1;
+    let late = game.add_tip(&fan, 1);
+    assert_eq!(late, Err(GameError::TipsClosed.into()));
+
+    let winner = game.players[1].pubkey;
+    game.record_standings(&[winner]);
+    assert_eq!(
+        game.payout_pot + game.creator_fee_due + game.rebate_pool,
+        3 * SOL_ENTRY_FEE_LAMPORTS
+    );
+}
+
+#[test]
+fn decision_leaderboard_ranks_on_chain_clocked_averages() {
+    let mut game = waiting_game(2);
+    begin_game(&mut game, 7, 0, 0).unwrap();
+    let seat = game.current_player_index as usize;
+    let start = game.turn_started_at;
+    game.charge_time_bank(start + 4).unwrap();
+    game.charge_time_bank(start + 10).unwrap();
+    assert_eq!(game.players[seat].clocked_decisions, 2);
+    assert_eq!(game.players[seat].clocked_decision_ms(), Some(5_000));
+
+    let entry = |player: Pubkey, average_decision_ms: u64| LeaderboardEntry {
+        player,
+        game_id: 1,
+        average_decision_ms,
+        decisions: MIN_RANKED_DECISIONS,
+    };
+    let mut board = DecisionLeaderboard {
+        entries: Vec::new(),
+        bump: 0,
+    };
+    let fast = Pubkey::new_unique();
+    assert_eq!(board.submit(entry(fast, 3_000)), Some(0));
+    for i in 0..LEADERBOARD_SIZE as u64 {
+        board.submit(entry(Pubkey::new_unique(), 4_000 + i));
+    }
+    assert_eq!(board.entries.len(), LEADERBOARD_SIZE);
+    assert_eq!(board.submit(entry(Pubkey::new_unique(), 60_000)), None);
+
+    // A player keeps one row, replaced only by a faster game
+    assert_eq!(board.submit(entry(fast, 3_500)), None);
+    assert_eq!(board.submit(entry(fast, 2_000)), Some(0));
+    assert_eq!(board.entries.iter().filter(|e| e.player == fast).count(), 1);
+    assert!(board
+        .entries
+        .windows(2)
+        .all(|pair| pair[0].average_decision_ms <= pair[1].average_decision_ms));
+}
+
+#[test]
+fn lobby_names_have_one_spelling() {
+    assert!(is_valid_lobby_name("blackbeard-cup"));
+    assert!(is_valid_lobby_name("round-2"));
+    for bad in [
+        "",
+        "Blackbeard-Cup",
+        "-cup",
+        "cup-",
+        "black beard",
+        "ümlaut",
+    ] {
+        assert!(!is_valid_lobby_name(bad), "{bad:?}");
+    }
+    assert!(is_valid_lobby_name(&"a".repeat(32)));
+    assert!(!is_valid_lobby_name(&"a".repeat(33)));
+    assert_ne!(lobby_name_hash("cup-1"), lobby_name_hash("cup-2"));
+}
+
+#[test]
+fn weather_replays_from_the_seed_and_draw_counter() {
+    let play = || {
+        let mut game = waiting_game(2);
+        begin_game(&mut game, 42, 0, 0).unwrap();
+        let mut fronts = Vec::new();
+        for _ in 0..12 {
+            game.end_round();
+            fronts.push((game.weather_type.clone(), game.weather_duration));
+        }
+        (game.rng, fronts)
+    };
+    let (stream, fronts) = play();
+    assert_eq!(play().1, fronts);
+    assert!(stream.counter > 0);
+    assert!(fronts
+        .iter()
+        .all(|(_, rounds)| (1..=WEATHER_MAX_ROUNDS).contains(rounds)));
+
+    // Draw n depends only on the seed and n
+    let mut replay = RngStream::new(stream.seed);
+    let draws: Vec<u64> = (0..stream.counter).map(|_| replay.next_u64()).collect();
+    assert_eq!(replay, stream);
+    let mut again = RngStream::new(stream.seed);
+    again.counter = 1;
+    assert_eq!(again.next_u64(), draws[1]);
+}
+
+#[test]
+fn liveness_deposits_come_back_only_to_players_who_stay() {
+    let mut game = waiting_game(3);
+    for seat in game.players.iter_mut() {
+        seat.liveness_deposit = LIVENESS_DEPOSIT_LAMPORTS;
+    }
+    assert_eq!(game.held_in_escrow(), 3 * LIVENESS_DEPOSIT_LAMPORTS);
+
+    // Missed reveals are forgiven up to a point
+    game.players[0].timeouts = MAX_FORGIVEN_TIMEOUTS;
+    assert!(liveness_refundable(&game.players[0]));
+    game.players[0].timeouts += 1;
+    assert!(!liveness_refundable(&game.players[0]));
+
+    // Leaving before the end forfeits, however it happens
+    game.players[1].departure = Departure::TimedOut;
+    assert!(!liveness_refundable(&game.players[1]));
+    game.players[2].departure = Departure::Resigned;
+    assert!(!liveness_refundable(&game.players[2]));
+
+    game.players[0].liveness_deposit = 0;
+    assert_eq!(game.liveness_escrow(), 2 * LIVENESS_DEPOSIT_LAMPORTS);
+}
+
+#[test]
+fn test_utils_play_turns_through_the_batch_handler_logic() {
+    let mut game = GameBuilder::new(2).seed(7).started_at(100).turn(3).build();
+    assert_eq!(game.status, GameStatus::Active);
+    assert_eq!(game.turn_number, 3);
+
+    let first = current_player(&game);
+    let actions = vec![GameAction::CollectResources, GameAction::EndTurn];
+    play_turn(&mut game, actions, 104).unwrap();
+    assert_ne!(current_player(&game), first);
+    let seat = game.players.iter().find(|p| p.pubkey == first).unwrap();
+    assert_eq!(seat.action_nonce, 1);
+    assert_eq!(seat.clocked_seconds, 4);
+
+    // A rejected turn changes nothing
+    let second = current_player(&game);
+    let empty = play_turn(&mut game, Vec::new(), 110);
+    assert_eq!(empty, Err(GameError::EmptyActionBatch.into()));
+    assert_eq!(current_player(&game), second);
+    assert_eq!(
+        game.players[game.current_player_index as usize].action_nonce,
+        0
+    );
+}
+
+#[test]
+fn agent_metadata_is_trimmed_and_capped_to_the_account_space() {
+    let mut agent = AgentRegistry {
+        owner: Pubkey::new_unique(),
+        delegate: None,
+        name: "bot".into(),
+        version: "1".into(),
+        twitter: None,
+        website: None,
+        games_played: 0,
+        wins: 0,
+        last_active: 0,
+        strategy_hash: [0; 32],
+        strategy_updated_at: 0,
+        bond: 0,
+        bond_locked_until: 0,
+        strikes: 0,
+    };
+    agent
+        .set_metadata(" Dread Bot ", "2.1", Some("@dreadbot"), Some("  "))
+        .unwrap();
+    assert_eq!(agent.name, "Dread Bot");
+    assert_eq!(agent.twitter.as_deref(), Some("dreadbot"));
+    assert_eq!(agent.website, None);
+
+    let long_name = "x".repeat(MAX_AGENT_NAME_LEN + 1);
+    let too_long = agent.set_metadata(&long_name, "2.1", None, None);
+    assert_eq!(too_long, Err(GameError::AgentNameTooLong.into()));
+    let long_site = "x".repeat(MAX_AGENT_WEBSITE_LEN + 1);
+    let too_long = agent.set_metadata("bot", "2.1", None, Some(&long_site));
+    assert_eq!(too_long, Err(GameError::AgentWebsiteTooLong.into()));
+    let blank = agent.set_metadata("   ", "2.1", None, None);
+    assert_eq!(blank, Err(GameError::InvalidAgentMetadata.into()));
+    let control = agent.set_metadata("bot\n", "2\u{7}", None, None);
+    assert_eq!(control, Err(GameError::InvalidAgentMetadata.into()));
+    // A rejected update leaves the profile as it was
+    assert_eq!(agent.name, "Dread Bot");
+
+    // The largest profile still serializes within the account
+    let name = "n".repeat(MAX_AGENT_NAME_LEN);
+    let version = "v".repeat(MAX_AGENT_VERSION_LEN);
+    let twitter = "t".repeat(MAX_AGENT_TWITTER_LEN);
+    let website = "w".repeat(MAX_AGENT_WEBSITE_LEN);
+    agent
+        .set_metadata(&name, &version, Some(&twitter), Some(&website))
+        .unwrap();
+    assert!(8 + agent.try_to_vec().unwrap().len() <= AgentRegistry::SPACE);
+}
+
+#[test]
+fn scan_logs_record_what_each_scan_revealed_once() {
+    assert_eq!(
+        revealed_cell_type(TerritoryCellType::Treasure, ScanDepth::Surface),
+        TerritoryCellType::Island
+    );
+    assert_eq!(
+        revealed_cell_type(TerritoryCellType::Whirlpool, ScanDepth::Surface),
+        TerritoryCellType::Water
+    );
+    assert_eq!(
+        revealed_cell_type(TerritoryCellType::Treasure, ScanDepth::CellType),
+        TerritoryCellType::Treasure
+    );
+
+    // Scanning under one's own ship is a full-depth scan of that cell
+    let mut game = GameBuilder::new(2).seed(3).build();
+    let player = current_player(&game);
+    let ship = game.get_player(&player).unwrap().ships[0].clone();
+    let (x, y) = (ship.position_x, ship.position_y);
+    apply_scan_coordinate(&mut game, player, x, y).unwrap();
+    let entry = game.get_player(&player).unwrap().last_scan.unwrap();
+    let index = x as usize * MAP_SIZE + y as usize;
+    assert_eq!(entry.coordinate as usize, index);
+    assert_eq!(entry.turn_number, game.turn_number);
+    assert_eq!(entry.depth, ScanDepth::Full);
+    assert_eq!(entry.revealed, game.territory_map[index].cell_type);
+
+    let mut log = ScanLog {
+        game_id: game.game_id,
+        player,
+        entries: Vec::new(),
+        bump: 0,
+    };
+    log.record(entry);
+    log.record(entry);
+    assert_eq!(log.entries, vec![entry]);
+
+    // A log of every cell still fits the account
+    log.entries = (0..MAX_SCAN_LOG_ENTRIES as u8)
+        .map(|coordinate| ScanLogEntry {
+            coordinate,
+            ..entry
+        })
+        .collect();
+    assert!(8 + log.try_to_vec().unwrap().len() <= ScanLog::SPACE);
+}
+
+#[test]
+fn parley_buys_the_player_on_turn_one_extension() {
+    let mut game = GameBuilder::new(2).started_at(100).build();
+    let first = current_player(&game);
+    let second = game
+        .players
+        .iter()
+        .find(|p| p.pubkey != first)
+        .unwrap()
+        .pubkey;
+    let deadline = move_deadline(&game, &first).unwrap();
+
+    let off_turn = apply_parley(&mut game, second, 150);
+    assert_eq!(off_turn, Err(GameError::NotPlayerTurn.into()));
+    apply_parley(&mut game, first, 150).unwrap();
+    assert!(game.parley_this_turn);
+    assert_eq!(
+        move_deadline(&game, &first).unwrap(),
+        deadline + PARLEY_EXTENSION_SECONDS
+    );
+    let again = apply_parley(&mut game, first, 150);
+    assert_eq!(again, Err(GameError::ParleyUsed.into()));
+
+    // The extension is not charged to the bank, and lapses with the turn
+    game.charge_time_bank(100 + PARLEY_EXTENSION_SECONDS + 20)
+        .unwrap();
+    assert_eq!(
+        game.players[game.current_player_index as usize].time_bank_seconds,
+        TIME_BANK_SECONDS - 20 + TIME_INCREMENT_SECONDS
+    );
+    game.advance_turn();
+    assert!(!game.parley_this_turn);
+    assert_eq!(current_player(&game), second);
+
+    // Out of time is out of time
+    game.turn_started_at = 200;
+    let late = 200 + TIME_BANK_SECONDS as i64 + 1;
+    assert_eq!(
+        apply_parley(&mut game, second, late),
+        Err(GameError::TimeBankExhausted.into())
+    );
+    apply_parley(&mut game, second, 200).unwrap();
+}
+
+#[test]
+fn victory_types_display_as_the_labels_indexers_used_to_parse() {
+    assert_eq!(
+        VictoryType::from(crate::rules::Victory::Economic).to_string(),
+        "Economic Victory"
+    );
+    assert_eq!(VictoryType::SuddenDeath.to_string(), "Sudden Death");
+    assert_eq!(
+        VictoryType::FlagshipCaptured.to_string(),
+        "Flagship Captured"
+    );
+
+    // The enum, not its label, is what goes over the wire
+    let encoded = VictoryType::KingOfTheHill.try_to_vec().unwrap();
+    assert_eq!(encoded, vec![VictoryType::KingOfTheHill as u8]);
+}
+
+#[test]
+fn wager_caps_bound_entry_fees_and_a_wallets_rolling_day() {
+    let params = ConfigParams {
+        max_entry_fee_lamports: Some(2 * SOL_ENTRY_FEE_LAMPORTS),
+        ..ConfigParams::default()
+    };
+    assert!(params.allows_entry_fee(2 * SOL_ENTRY_FEE_LAMPORTS));
+    assert!(!params.allows_entry_fee(4 * SOL_ENTRY_FEE_LAMPORTS));
+    assert!(ConfigParams::default().allows_entry_fee(u64::MAX));
+
+    let cap = Some(3 * SOL_ENTRY_FEE_LAMPORTS);
+    let day = WAGER_WINDOW_SECONDS;
+    let mut window = WagerWindow::default();
+    assert!(window.try_record(day, SOL_ENTRY_FEE_LAMPORTS, cap));
+    assert!(window.try_record(day + 10, 2 * SOL_ENTRY_FEE_LAMPORTS, cap));
+    assert!(!window.try_record(day + 20, SOL_ENTRY_FEE_LAMPORTS, cap));
+    assert_eq!(window.total(day + 20), 3 * SOL_ENTRY_FEE_LAMPORTS);
+
+    // Halfway into the next day, half of yesterday still counts
+    let noon = 2 * day + day / 2;
+    assert_eq!(window.total(noon), 3 * SOL_ENTRY_FEE_LAMPORTS / 2);
+    assert!(window.try_record(noon, SOL_ENTRY_FEE_LAMPORTS, cap));
+    assert!(!window.try_record(noon, SOL_ENTRY_FEE_LAMPORTS, cap));
+    // Without a cap, wagers are only tracked
+    assert!(window.try_record(noon, u64::MAX, None));
+}
+
+#[test]
+fn privateer_contracts_are_drawn_from_the_stream_and_paid_once_verified() {
+    let mut game = GameBuilder::new(2).seed(11).build();
+    let mut replay = game.clone();
+    game.end_round();
+    replay.end_round();
+    assert!((1..=2).contains(&game.contracts.len()));
+    assert_eq!(game.contracts, replay.contracts);
+    let offer = game.contracts[0].clone();
+    assert_eq!(offer.holder, None);
+    assert_eq!(offer.deadline_turn, game.turn_number + 4);
+
+    let first = game.players[0].pubkey;
+    let second = game.players[1].pubkey;
+    take_contract(&mut game, first, offer.id).unwrap();
+    let taken = take_contract(&mut game, second, offer.id);
+    assert_eq!(taken, Err(GameError::ContractTaken.into()));
+    let missing = take_contract(&mut game, second, u16::MAX);
+    assert_eq!(missing, Err(GameError::ContractNotFound.into()));
+
+    // A sink contract pays when the holder sinks that type, and only then
+    let gold = game.players[0].resources.gold;
+    game.contracts = vec![Contract {
+        id: 7,
+        terms: ContractTerms::SinkShip {
+            ship_type: ShipType::Frigate,
+        },
+        reward_gold: 600,
+        deadline_turn: game.turn_number,
+        holder: Some(first),
+        rounds_held: 0,
+    }];
+    credit_sinking(&mut game, &first, &ShipType::Sloop);
+    credit_sinking(&mut game, &second, &ShipType::Frigate);
+    assert_eq!(game.players[0].resources.gold, gold);
+    credit_sinking(&mut game, &first, &ShipType::Frigate);
+    assert_eq!(game.players[0].resources.gold, gold + 600);
+    assert!(game.contracts.is_empty());
+
+    // A hold contract needs the cell held at the end of consecutive rounds
+    let cell = game
+        .territory_map
+        .iter()
+        .position(|c| c.cell_type.is_land())
+        .unwrap();
+    game.contracts = vec![Contract {
+        id: 8,
+        terms: ContractTerms::HoldCell {
+            cell: cell as u8,
+            rounds: 2,
+        },
+        reward_gold: 300,
+        deadline_turn: game.turn_number + 4,
+        holder: Some(second),
+        rounds_held: 0,
+    }];
+    let gold = game.players[1].resources.gold;
+    game.territory_map[cell].owner = Some(second);
+    game.end_round();
+    game.territory_map[cell].owner = None;
+    game.end_round();
+    assert_eq!(game.contracts[0].rounds_held, 0);
+    game.territory_map[cell].owner = Some(second);
+    game.end_round();
+    game.end_round();
+    assert_eq!(game.players[1].resources.gold, gold + 300);
+    assert!(game.contracts.iter().all(|c| c.id != 8));
+
+    // Offers lapse at their deadline, and a full board still fits the account
+    game.turn_number += CONTRACT_ROUNDS;
+    game.contracts.retain(|c| c.holder.is_none());
+    game.end_round();
+    assert!(game
+        .contracts
+        .iter()
+        .all(|c| c.deadline_turn >= game.turn_number));
+
+    let mut full = GameBuilder::new(4).build();
+    full.contracts = (0..MAX_OPEN_CONTRACTS as u16)
+        .map(|id| Contract {
+            id,
+            holder: Some(full.players[0].pubkey),
+            ..offer.clone()
+        })
+        .collect();
+    assert!(8 + full.try_to_vec().unwrap().len() <= PirateGame::SPACE);
+}
+
+#[test]
+fn a_losing_streak_earns_starting_gold_in_casual_lobbies() {
+    let zeroed = vec![0u8; PlayerProfile::SPACE - 8];
+    let mut profile = PlayerProfile::deserialize(&mut zeroed.as_slice()).unwrap();
+    for _ in 1..PITY_STREAK_LOSSES {
+        profile.record_result(false);
+    }
+    assert_eq!(profile.pity_bonus(DEFAULT_PITY_BONUS_GOLD), 0);
+    profile.record_result(false);
+    assert_eq!(profile.losing_streak, PITY_STREAK_LOSSES);
+    assert_eq!(
+        profile.pity_bonus(DEFAULT_PITY_BONUS_GOLD),
+        DEFAULT_PITY_BONUS_GOLD
+    );
+
+    let mut game = waiting_game(2);
+    game.pity_bonus_gold = DEFAULT_PITY_BONUS_GOLD;
+    let mut seat = game.players[0].clone();
+    let gold = seat.resources.gold;
+    grant_pity_bonus(&game, &mut seat, &profile);
+    assert_eq!(seat.resources.gold, gold + DEFAULT_PITY_BONUS_GOLD);
+
+    // Ranked lobbies copy no bonus, and one win ends the streak
+    game.pity_bonus_gold = 0;
+    let mut ranked_seat = game.players[0].clone();
+    grant_pity_bonus(&game, &mut ranked_seat, &profile);
+    assert_eq!(ranked_seat.resources.gold, gold);
+    profile.record_result(true);
+    assert_eq!(profile.pity_bonus(DEFAULT_PITY_BONUS_GOLD), 0);
+
+    let params = ConfigParams {
+        pity_bonus_gold: MAX_PITY_BONUS_GOLD + 1,
+        ..ConfigParams::default()
+    };
+    assert!(!validate_config_params(&params));
+}
+
+#[test]
+fn the_revenue_splitter_shares_a_sweep_and_gives_the_dust_to_devs() {
+    let split = RevenueSplit {
+        dev_wallet: Pubkey::new_unique(),
+        prize_pool: Pubkey::new_unique(),
+        dev_share_bps: 5_000,
+        prize_pool_share_bps: 3_333,
+        jackpot_share_bps: 1_667,
+    };
+    assert!(split.is_valid());
+    assert_eq!(
+        split.split(1_000_001),
+        FeeSplit {
+            dev: 500_001,
+            prize_pool: 333_300,
+            jackpot: 166_700,
+        }
+    );
+    assert!(!RevenueSplit {
+        jackpot_share_bps: 1_666,
+        ..split
+    }
+    .is_valid());
+
+    let authority = Pubkey::new_unique();
+    assert!(is_fee_treasury(&authority, &authority));
+    assert!(is_fee_treasury(&authority, &revenue_splitter_address()));
+    assert!(!is_fee_treasury(&authority, &Pubkey::new_unique()));
+}
+
+#[test]
+fn payouts_from_pots_over_the_threshold_vest_half_for_a_week() {
+    let threshold = Some(10 * SOL_ENTRY_FEE_LAMPORTS);
+    assert_eq!(
+        vested_share(1_001, 10 * SOL_ENTRY_FEE_LAMPORTS, threshold),
+        0
+    );
+    assert_eq!(
+        vested_share(1_001, 11 * SOL_ENTRY_FEE_LAMPORTS, threshold),
+        500
+    );
+    assert_eq!(vested_share(1_001, u64::MAX, None), 0);
+
+    let mut tranche = VestingTranche {
+        player: Pubkey::new_unique(),
+        recipient: Pubkey::new_unique(),
+        amount: 500,
+        unlocks_at: VESTING_DELAY_SECONDS,
+        frozen_until: 0,
+    };
+    assert!(!tranche.is_releasable(VESTING_DELAY_SECONDS - 1));
+    assert!(tranche.is_releasable(VESTING_DELAY_SECONDS));
+    tranche.frozen_until = tranche.freeze_deadline();
+    assert!(!tranche.is_releasable(VESTING_DELAY_SECONDS * 2));
+
+    // A game with a held tranche is not settled, so it cannot be closed
+    let mut game = waiting_game(2);
+    for player in game.players.iter_mut() {
+        player.profile_settled = true;
+    }
+    assert!(game.is_fully_settled());
+    game.vesting.push(tranche);
+    assert!(!game.is_fully_settled());
+}
+
+#[test]
+fn captains_are_flagged_once_when_they_close_in_on_a_victory() {
+    let near = VictoryProgress {
+        fleet_power: 52,
+        total_fleet_power: 100,
+        territories: 4,
+        valuable_territories: 10,
+        resource_value: 7_999,
+    };
+    assert_eq!(
+        victories_within(&near, &DEFAULT_VICTORY_THRESHOLDS, 80),
+        vec![Victory::FleetDominance, Victory::TerritoryControl]
+    );
+    assert!(victories_within(&near, &DEFAULT_VICTORY_THRESHOLDS, 100).is_empty());
+
+    let mut game = GameBuilder::new(2).build();
+    let economic = Victory::Economic.bit();
+    for player in game.players.iter_mut() {
+        player.resources.crew = 0;
+        player.resources.cannons = 0;
+        player.resources.supplies = 0;
+    }
+    game.players[0].resources.gold = 8_000;
+    game.players[1].resources.gold = 0;
+    game.warn_victory_proximity();
+    assert_eq!(game.players[0].victory_warnings & economic, economic);
+    assert_eq!(game.players[1].victory_warnings & economic, 0);
+
+    // Falling back clears the flag, so closing in again is announced again
+    game.players[0].resources.gold = 0;
+    game.warn_victory_proximity();
+    assert_eq!(game.players[0].victory_warnings & economic, 0);
+}
+
+#[test]
+fn deterministic_games_replay_from_their_seed() {
+    let options = GameOptions {
+        deterministic: true,
+        ..GameOptions::default()
+    };
+    let game = GameBuilder::new(2).options(options).build();
+
+    // Every roll after the seed comes from the stream, so a replay matches
+    let mut replay = game.clone();
+    let mut game = game;
+    for _ in 0..3 {
+        game.end_round();
+        replay.end_round();
+    }
+    assert_eq!(game.rng, replay.rng);
+    assert_eq!(game.weather_type, replay.weather_type);
+    assert_eq!(game.contracts, replay.contracts);
+}
@@ -0,0 +1,194 @@
+use crate::constants::*;
+use crate::state::game::{GameObjective, GameStatus, PirateGame};
+use crate::state::player::PlayerRole;
+use crate::state::simultaneous::RoundPhase;
+use anchor_lang::prelude::*;
+
+pub const TURN_BEACON_SEED: &[u8] = b"turn_beacon";
+
+// ============================================================================
+// TURN BEACONS
+// ============================================================================
+//
+// A game account runs to several KB, which is a lot to stream to a phone just
+// to learn whose move it is. A player can open one tiny beacon; any
+// instruction that changes the turn rings every beacon passed with it: the
+// named `beacon` account and any writable beacons among the remaining
+// accounts, so with three or more captains, or simultaneous rounds, one
+// transaction can wake everyone now to move. Clients subscribe to their own
+// beacon and fetch the game only when it rings. Rings are best effort: a
+// transaction that omits a beacon still changes the turn.
+
+#[account]
+pub struct TurnBeacon {
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub turn_number: u32,
+    pub deadline: i64, // When the player's clock runs out; zero if untimed or not on the clock
+    pub bump: u8,
+}
+
+impl TurnBeacon {
+    pub const SPACE: usize = 8 + 32 + 8 + 4 + 8 + 1;
+
+    /// Point the beacon at `game` if its player is now to move there. A
+    /// beacon left on this game after the player's move is stood down; one
+    /// pointing at another game is left alone.
+    pub fn ring(&mut self, game: &PirateGame) {
+        match move_deadline(game, &self.player) {
+            Some(deadline) => {
+                self.game_id = game.game_id;
+                self.turn_number = game.turn_number;
+                self.deadline = deadline;
+            }
+            None if self.game_id == game.game_id => {
+                self.turn_number = game.turn_number;
+                self.deadline = 0;
+            }
+            None => {}
+        }
+    }
+}
+
+/// When `player` must move by, if they are to move at all: the end of their
+/// time bank in sequential games, the commit or reveal window in simultaneous
+/// ones. Zero means to move but untimed.
+pub fn move_deadline(game: &PirateGame, player: &Pubkey) -> Option<i64> {
+    if game.status != GameStatus::Active {
+        return None;
+    }
+    if !game.options.simultaneous_turns {
+        let seat = game.get_current_player()?;
+        if !game.is_current_player(player) {
+            return None;
+        }
+        if game.options.objective == GameObjective::Tutorial {
+            return Some(0);
+        }
+        return Some(
+            game.turn_started_at
+                .saturating_add(seat.time_bank_seconds as i64),
+        );
+    }
+
+    let index = game
+        .players
+        .iter()
+        .position(|p| p.pubkey == *player && p.is_active && p.role == PlayerRole::Captain)?;
+    let commitment = game
+        .pending_orders
+        .iter()
+        .find(|c| c.player_index as usize == index);
+    match (game.round_phase, commitment) {
+        (RoundPhase::Commit, None) => {
+            Some(game.turn_started_at.saturating_add(COMMIT_WINDOW_SECONDS))
+        }
+        (RoundPhase::Reveal, Some(c)) if !c.revealed => {
+            Some(game.turn_started_at.saturating_add(REVEAL_WINDOW_SECONDS))
+        }
+        _ => None,
+    }
+}
+
+/// Ring the beacon passed with a turn-changing instruction, if any, and
+/// every beacon among its remaining accounts
+pub fn ring_beacon(
+    beacon: &mut Option<Account<TurnBeacon>>,
+    remaining: &[AccountInfo],
+    game: &PirateGame,
+) -> Result<()> {
+    if let Some(beacon) = beacon.as_mut() {
+        beacon.ring(game);
+    }
+    ring_beacons(remaining, game)
+}
+
+/// Ring every writable beacon in `accounts`, skipping anything else passed
+/// alongside them, such as agent registries
+pub fn ring_beacons(accounts: &[AccountInfo], game: &PirateGame) -> Result<()> {
+    for info in accounts
+        .iter()
+        .filter(|info| info.is_writable && *info.owner == crate::ID)
+    {
+        let mut data = info.try_borrow_mut_data()?;
+        let Ok(mut beacon) = TurnBeacon::try_deserialize(&mut &data[..]) else {
+            continue;
+        };
+        beacon.ring(game);
+        beacon.try_serialize(&mut &mut data[..])?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::GameBuilder;
+
+    fn beacon_data(player: Pubkey) -> Vec<u8> {
+        let beacon = TurnBeacon {
+            player,
+            game_id: 0,
+            turn_number: 0,
+            deadline: 0,
+            bump: 0,
+        };
+        let mut data = Vec::new();
+        beacon.try_serialize(&mut data).unwrap();
+        data
+    }
+
+    fn read(data: &[u8]) -> TurnBeacon {
+        TurnBeacon::try_deserialize(&mut &data[..]).unwrap()
+    }
+
+    #[test]
+    fn every_opposing_beacon_rings() {
+        let mut game = GameBuilder::new(3).started_at(1_000).build();
+        let players: Vec<Pubkey> = game.players.iter().map(|p| p.pubkey).collect();
+        let keys: Vec<Pubkey> = players.iter().map(|_| Pubkey::new_unique()).collect();
+        let mut data: Vec<Vec<u8>> = players.iter().map(|p| beacon_data(*p)).collect();
+        let mut lamports = [0u64; 3];
+        let program = crate::ID;
+
+        // The first mover is on the clock; the others wait
+        let next = game.turn_order[game.current_player_index as usize] as usize;
+        {
+            let infos: Vec<AccountInfo> = data
+                .iter_mut()
+                .zip(lamports.iter_mut())
+                .zip(keys.iter())
+                .map(|((data, lamports), key)| {
+                    AccountInfo::new(key, false, true, lamports, data, &program, false, 0)
+                })
+                .collect();
+            ring_beacons(&infos, &game).unwrap();
+        }
+        for (seat, data) in data.iter().enumerate() {
+            let rung = read(data).game_id == game.game_id;
+            assert_eq!(rung, seat == next, "seat {seat}");
+        }
+
+        // Simultaneous rounds wake every captain at once, but a beacon passed
+        // read-only is left alone
+        game.options.simultaneous_turns = true;
+        {
+            let infos: Vec<AccountInfo> = data
+                .iter_mut()
+                .zip(lamports.iter_mut())
+                .zip(keys.iter())
+                .enumerate()
+                .map(|(seat, ((data, lamports), key))| {
+                    AccountInfo::new(key, false, seat != 2, lamports, data, &program, false, 0)
+                })
+                .collect();
+            ring_beacons(&infos, &game).unwrap();
+        }
+        for data in &data[..2] {
+            let beacon = read(data);
+            let deadline = game.turn_started_at + COMMIT_WINDOW_SECONDS;
+            assert_eq!((beacon.game_id, beacon.deadline), (game.game_id, deadline));
+        }
+        assert_eq!(read(&data[2]).game_id == game.game_id, next == 2);
+    }
+}
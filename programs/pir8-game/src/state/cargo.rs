@@ -0,0 +1,60 @@
+use crate::state::distance::manhattan;
+use crate::state::map::{index_to_coordinate, mine_treasure, TerritoryCell, TerritoryCellType};
+
+// ============================================================================
+// TREASURE RACE CARGO
+// ============================================================================
+//
+// In treasure-race games ships haul gold instead of banking it: a ship on a
+// Treasure (or Flotsam) cell loads gold into its hold, and only gold carried
+// to the owner's home port counts towards the win. A ship sunk with cargo
+// aboard spills it onto the cell it went down on, where anyone can salvage it.
+
+/// Gold a single ship's hold can carry
+pub const CARGO_HOLD_GOLD: u32 = 50;
+
+/// Map index of the Port nearest `spawn`, ties broken by (x, y)
+pub fn home_port(territory_map: &[TerritoryCell], spawn: (u8, u8)) -> Option<u8> {
+    territory_map
+        .iter()
+        .enumerate()
+        .filter(|(_, cell)| cell.cell_type == TerritoryCellType::Port)
+        .map(|(index, _)| (index, index_to_coordinate(index)))
+        .min_by_key(|&(_, (x, y))| (manhattan((x, y), spawn), x, y))
+        .map(|(index, _)| index as u8)
+}
+
+/// Take up to `room` gold from a Treasure or Flotsam cell. Flotsam picked
+/// clean is open Water again.
+pub fn salvage(cell: &mut TerritoryCell, room: u32) -> u32 {
+    match cell.cell_type {
+        TerritoryCellType::Treasure => mine_treasure(cell, room),
+        TerritoryCellType::Flotsam => {
+            let taken = room.min(cell.remaining_yield as u32);
+            cell.remaining_yield -= taken as u16;
+            if cell.remaining_yield == 0 {
+                cell.cell_type = TerritoryCellType::Water;
+            }
+            taken
+        }
+        _ => 0,
+    }
+}
+
+/// Spill a sunk ship's cargo onto its wreck cell and return how much can be
+/// salvaged. Open water becomes Flotsam and Treasure keeps the gold; on any
+/// other cell the cargo is lost.
+pub fn spill_cargo(cell: &mut TerritoryCell, gold: u32) -> u32 {
+    let gold = gold.min(u16::MAX as u32) as u16;
+    match cell.cell_type {
+        TerritoryCellType::Water => {
+            cell.cell_type = TerritoryCellType::Flotsam;
+            cell.remaining_yield = gold;
+        }
+        TerritoryCellType::Treasure | TerritoryCellType::Flotsam => {
+            cell.remaining_yield = cell.remaining_yield.saturating_add(gold);
+        }
+        _ => return 0,
+    }
+    gold as u32
+}
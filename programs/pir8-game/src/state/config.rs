@@ -0,0 +1,171 @@
+use crate::constants::{BASIS_POINTS, MAX_SHIPS_PER_PLAYER};
+use crate::state::player::Resources;
+use anchor_lang::prelude::*;
+
+pub const CONFIG_SEED: &[u8] = b"config";
+
+// ============================================================================
+// GLOBAL BALANCE CONFIG
+// ============================================================================
+//
+// Tunable balance values owned by the program authority. Games copy the
+// values at creation, so an update only affects games created afterwards.
+
+/// Resource yield multiplier per ship type, in basis points (Sloop, Frigate, Galleon, Flagship)
+pub const DEFAULT_RESOURCE_MULTIPLIERS_BPS: [u32; 4] = [10_000, 12_000, 15_000, 13_000];
+
+/// Upper bound on any configured multiplier (5.0x)
+pub const MAX_MULTIPLIER_BPS: u32 = 5 * BASIS_POINTS;
+
+/// Default ceiling on the starting resources a game creator may choose
+pub const DEFAULT_MAX_STARTING_RESOURCES: Resources = Resources {
+    gold: 5_000,
+    crew: 200,
+    cannons: 50,
+    supplies: 500,
+};
+
+/// Default ceiling on the starting fleet size a game creator may choose
+pub const DEFAULT_MAX_STARTING_SHIPS: u8 = 4;
+
+/// Default ceiling on the creator fee a lobby host may take (2%)
+pub const DEFAULT_MAX_CREATOR_FEE_BPS: u16 = 200;
+
+/// Hard ceiling on any configured creator fee ceiling (10%)
+pub const CREATOR_FEE_CEILING_BPS: u16 = 1_000;
+
+/// Default per-wallet throttles on lobby creation and joining, per sliding hour
+pub const DEFAULT_MAX_CREATES_PER_HOUR: u16 = 10;
+pub const DEFAULT_MAX_JOINS_PER_HOUR: u16 = 30;
+
+/// Default gold a casual lobby adds to the starting stockpile of a wallet on
+/// a losing streak, and the ceiling any config may set
+pub const DEFAULT_PITY_BONUS_GOLD: u32 = 250;
+pub const MAX_PITY_BONUS_GOLD: u32 = 1_000;
+
+/// Most NFT collections whose members may be worn as ship skins
+pub const MAX_SKIN_COLLECTIONS: usize = 8;
+
+/// Values the authority can tune without a redeploy
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct ConfigParams {
+    pub resource_multipliers_bps: [u32; 4],
+    pub max_starting_resources: Resources,
+    pub max_starting_ships: u8,
+    pub max_creator_fee_bps: u16,
+    pub max_creates_per_hour: u16,
+    pub max_joins_per_hour: u16,
+    /// Token-2022 collection (group) mints approved for ship skins
+    pub skin_collections: Vec<Pubkey>,
+    /// New games log one `TurnSummary` per instruction instead of a
+    /// `StateDiff` plus per-action events
+    pub compact_events: bool,
+    /// New games let a player withdraw a resignation within
+    /// RESIGN_GRACE_SECONDS, until play moves on
+    pub resign_grace: bool,
+    /// Largest SOL entry fee a lobby may charge each player; None for no cap
+    pub max_entry_fee_lamports: Option<u64>,
+    /// Most SOL a wallet may pay in entry fees over a rolling 24 hours;
+    /// None for no cap
+    pub daily_wager_cap_lamports: Option<u64>,
+    /// Starting gold new casual lobbies add for a wallet on a losing streak;
+    /// zero turns the pity bonus off
+    pub pity_bonus_gold: u32,
+    /// SOL pot above which new games pay winners half at claim time and half
+    /// after VESTING_DELAY_SECONDS; None for no vesting
+    pub vesting_threshold_lamports: Option<u64>,
+}
+
+impl Default for ConfigParams {
+    fn default() -> Self {
+        Self {
+            resource_multipliers_bps: DEFAULT_RESOURCE_MULTIPLIERS_BPS,
+            max_starting_resources: DEFAULT_MAX_STARTING_RESOURCES,
+            max_starting_ships: DEFAULT_MAX_STARTING_SHIPS,
+            max_creator_fee_bps: DEFAULT_MAX_CREATOR_FEE_BPS,
+            max_creates_per_hour: DEFAULT_MAX_CREATES_PER_HOUR,
+            max_joins_per_hour: DEFAULT_MAX_JOINS_PER_HOUR,
+            skin_collections: Vec::new(),
+            compact_events: false,
+            resign_grace: false,
+            max_entry_fee_lamports: None,
+            daily_wager_cap_lamports: None,
+            pity_bonus_gold: DEFAULT_PITY_BONUS_GOLD,
+            vesting_threshold_lamports: None,
+        }
+    }
+}
+
+impl ConfigParams {
+    /// Whether a lobby may charge `entry_fee` lamports per player
+    pub fn allows_entry_fee(&self, entry_fee: u64) -> bool {
+        match self.max_entry_fee_lamports {
+            Some(max) => entry_fee <= max,
+            None => true,
+        }
+    }
+
+    /// Whether a lobby's creator may take `creator_fee_bps` of the pot
+    pub fn allows_creator_fee(&self, creator_fee_bps: u16) -> bool {
+        creator_fee_bps <= self.max_creator_fee_bps
+    }
+}
+
+#[account]
+pub struct GameConfig {
+    pub authority: Pubkey,
+    pub params: ConfigParams,
+    pub bump: u8,
+}
+
+impl GameConfig {
+    pub const SPACE: usize = 8
+        + 32
+        + (4 * 4 + 4 * 4 + 1 + 2 + 2 + 2 + 4 + 32 * MAX_SKIN_COLLECTIONS + 1 + 1 + 9 + 9 + 4 + 9)
+        + 1;
+}
+
+/// Params from the config account when one is passed, defaults otherwise
+pub fn params_or_default(config: Option<&GameConfig>) -> ConfigParams {
+    config
+        .map(|config| config.params.clone())
+        .unwrap_or_default()
+}
+
+pub fn validate_multipliers(multipliers_bps: &[u32]) -> bool {
+    multipliers_bps
+        .iter()
+        .all(|&bps| bps > 0 && bps <= MAX_MULTIPLIER_BPS)
+}
+
+pub fn validate_config_params(params: &ConfigParams) -> bool {
+    validate_multipliers(&params.resource_multipliers_bps)
+        && params.max_starting_ships > 0
+        && params.max_starting_ships as usize <= MAX_SHIPS_PER_PLAYER
+        && params.max_creator_fee_bps <= CREATOR_FEE_CEILING_BPS
+        && params.max_creates_per_hour > 0
+        && params.max_joins_per_hour > 0
+        && params.skin_collections.len() <= MAX_SKIN_COLLECTIONS
+        && params.max_entry_fee_lamports != Some(0)
+        && params.daily_wager_cap_lamports != Some(0)
+        && params.pity_bonus_gold <= MAX_PITY_BONUS_GOLD
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn creator_fees_stay_under_the_configured_cap() {
+        let mut params = ConfigParams::default();
+        assert!(params.allows_creator_fee(DEFAULT_MAX_CREATOR_FEE_BPS));
+        assert!(!params.allows_creator_fee(DEFAULT_MAX_CREATOR_FEE_BPS + 1));
+
+        // The authority may raise the cap, but never past the ceiling
+        params.max_creator_fee_bps = CREATOR_FEE_CEILING_BPS;
+        assert!(validate_config_params(&params));
+        assert!(params.allows_creator_fee(CREATOR_FEE_CEILING_BPS));
+        params.max_creator_fee_bps = CREATOR_FEE_CEILING_BPS + 1;
+        assert!(!validate_config_params(&params));
+    }
+}
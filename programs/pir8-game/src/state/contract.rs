@@ -0,0 +1,205 @@
+use crate::errors::GameError;
+use crate::events::{ContractCompleted, ContractExpired, ContractOffered};
+use crate::state::game::{GameObjective, GameStatus, PirateGame};
+use crate::state::player::{PlayerRole, ShipType};
+use anchor_lang::prelude::*;
+
+// ============================================================================
+// PRIVATEER CONTRACTS
+// ============================================================================
+//
+// Missions offered by the game itself. At the end of each round the game
+// draws one or two offers from its RngStream: sink a ship of a given type,
+// or hold a given land cell for a run of rounds. Any captain may accept an
+// open offer, one contract at a time; it pays out in gold once combat or the
+// round's upkeep verifies it, and lapses with its deadline either way.
+
+/// Most offers and accepted contracts a game tracks at once
+pub const MAX_OPEN_CONTRACTS: usize = 6;
+
+/// Rounds from an offer to its deadline, inclusive
+pub const CONTRACT_ROUNDS: u32 = 5;
+
+/// Shortest and longest hold a cell contract asks for, in rounds
+pub const CONTRACT_HOLD_MIN_ROUNDS: u8 = 2;
+pub const CONTRACT_HOLD_MAX_ROUNDS: u8 = 3;
+
+/// Gold a hold contract pays per round held
+pub const CONTRACT_HOLD_GOLD_PER_ROUND: u32 = 150;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub enum ContractTerms {
+    /// Sink any enemy ship of this type
+    SinkShip { ship_type: ShipType },
+    /// Own this map cell at the end of `rounds` consecutive rounds
+    HoldCell { cell: u8, rounds: u8 },
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct Contract {
+    pub id: u16,
+    pub terms: ContractTerms,
+    pub reward_gold: u32,
+    pub deadline_turn: u32,     // Last round it can be completed in
+    pub holder: Option<Pubkey>, // None while on offer
+    pub rounds_held: u8,        // Hold contracts: consecutive rounds held so far
+}
+
+impl Contract {
+    pub const SPACE: usize = 2 + 3 + 4 + 4 + 33 + 1;
+}
+
+/// Take an open offer for `player`, who may hold one contract at a time
+pub fn take_contract(game: &mut PirateGame, player: Pubkey, contract_id: u16) -> Result<u32> {
+    require!(game.status == GameStatus::Active, GameError::GameNotActive);
+    let seat = game.get_player(&player).ok_or(GameError::PlayerNotInGame)?;
+    require!(
+        seat.role == PlayerRole::Captain,
+        GameError::SpectatorCannotAct
+    );
+    require!(
+        !game.contracts.iter().any(|c| c.holder == Some(player)),
+        GameError::AlreadyHoldingContract
+    );
+
+    let contract = game
+        .contracts
+        .iter_mut()
+        .find(|c| c.id == contract_id)
+        .ok_or(GameError::ContractNotFound)?;
+    require!(contract.holder.is_none(), GameError::ContractTaken);
+    contract.holder = Some(player);
+    Ok(contract.deadline_turn)
+}
+
+/// Pay out `attacker`'s sink contract if the ship they just sank fits it
+pub fn credit_sinking(game: &mut PirateGame, attacker: &Pubkey, ship_type: &ShipType) {
+    let terms = ContractTerms::SinkShip {
+        ship_type: ship_type.clone(),
+    };
+    let fulfilled = game
+        .contracts
+        .iter()
+        .position(|c| c.holder == Some(*attacker) && c.terms == terms);
+    if let Some(index) = fulfilled {
+        pay_contract(game, index);
+    }
+}
+
+/// Round upkeep: score held cells, retire lapsed contracts and post new offers
+pub fn advance_contracts(game: &mut PirateGame) {
+    if game.options.objective == GameObjective::Tutorial {
+        return;
+    }
+
+    let mut index = 0;
+    while index < game.contracts.len() {
+        let contract = &mut game.contracts[index];
+        if let (ContractTerms::HoldCell { cell, rounds }, Some(holder)) =
+            (contract.terms.clone(), contract.holder)
+        {
+            let held = game
+                .territory_map
+                .get(cell as usize)
+                .is_some_and(|c| c.owner == Some(holder));
+            contract.rounds_held = if held { contract.rounds_held + 1 } else { 0 };
+            if contract.rounds_held >= rounds {
+                pay_contract(game, index);
+                continue;
+            }
+        }
+        index += 1;
+    }
+
+    let turn_number = game.turn_number;
+    let game_id = game.game_id;
+    game.contracts.retain(|c| {
+        let lapsed = turn_number > c.deadline_turn;
+        if lapsed {
+            emit!(ContractExpired {
+                game_id,
+                contract_id: c.id,
+                holder: c.holder,
+            });
+        }
+        !lapsed
+    });
+
+    offer_contracts(game);
+}
+
+/// Draw this round's one or two offers from the game's stream
+fn offer_contracts(game: &mut PirateGame) {
+    let offers = 1 + game.rng.next_below(2);
+    for _ in 0..offers {
+        if game.contracts.len() >= MAX_OPEN_CONTRACTS {
+            return;
+        }
+        let Some((terms, reward_gold)) = draw_terms(game) else {
+            continue;
+        };
+        let contract = Contract {
+            id: game.next_contract_id,
+            terms,
+            reward_gold,
+            deadline_turn: game.turn_number + CONTRACT_ROUNDS - 1,
+            holder: None,
+            rounds_held: 0,
+        };
+        game.next_contract_id = game.next_contract_id.wrapping_add(1);
+        emit!(ContractOffered {
+            game_id: game.game_id,
+            contract: contract.clone(),
+        });
+        game.contracts.push(contract);
+    }
+}
+
+/// Terms and reward of one offer: half a ship's gold cost to sink it, or a
+/// fixed rate per round to hold a land cell
+fn draw_terms(game: &mut PirateGame) -> Option<(ContractTerms, u32)> {
+    if game.rng.next_below(2) == 0 {
+        let ship_type = match game.rng.next_below(4) {
+            0 => ShipType::Sloop,
+            1 => ShipType::Frigate,
+            2 => ShipType::Galleon,
+            _ => ShipType::Flagship,
+        };
+        let reward_gold = game.rules.ship_costs(&ship_type).gold / 2;
+        return Some((ContractTerms::SinkShip { ship_type }, reward_gold));
+    }
+
+    let land: Vec<u8> = game
+        .territory_map
+        .iter()
+        .enumerate()
+        .filter(|(_, cell)| cell.cell_type.is_land())
+        .map(|(index, _)| index as u8)
+        .collect();
+    if land.is_empty() {
+        return None;
+    }
+    let cell = land[game.rng.next_below(land.len() as u64) as usize];
+    let spread = (CONTRACT_HOLD_MAX_ROUNDS - CONTRACT_HOLD_MIN_ROUNDS + 1) as u64;
+    let rounds = CONTRACT_HOLD_MIN_ROUNDS + game.rng.next_below(spread) as u8;
+    let reward_gold = CONTRACT_HOLD_GOLD_PER_ROUND * rounds as u32;
+    Some((ContractTerms::HoldCell { cell, rounds }, reward_gold))
+}
+
+/// Remove a fulfilled contract and pay its holder, if they are still in play
+fn pay_contract(game: &mut PirateGame, index: usize) {
+    let contract = game.contracts.remove(index);
+    let Some(holder) = contract.holder else {
+        return;
+    };
+    let game_id = game.game_id;
+    if let Some(player) = game.get_player_mut(&holder) {
+        player.resources.gold = player.resources.gold.saturating_add(contract.reward_gold);
+        emit!(ContractCompleted {
+            game_id,
+            contract_id: contract.id,
+            player: holder,
+            reward_gold: contract.reward_gold,
+        });
+    }
+}
@@ -0,0 +1,428 @@
+use crate::events::{StateDiff, TurnSummary};
+use crate::state::game::{GameStatus, PirateGame};
+use crate::state::map::TerritoryCellType;
+use crate::state::player::{final_score, Resources, ShipType};
+use anchor_lang::prelude::*;
+
+// ============================================================================
+// STATE DIFF ENCODING
+// ============================================================================
+//
+// Payload layout: [version: u8] followed by tagged entries. All integers are
+// little-endian. Ships are addressed by (player index, ship index); removals
+// refer to indices in the pre-instruction fleet and are emitted in descending
+// order so they can be applied in sequence before any other ship entry.
+//
+//   TAG_TURN          current_player_index: u8, turn_number: u32
+//   TAG_STATUS        status: u8 (0 = Waiting, 1 = Active, 2 = Completed)
+//   TAG_SHIP_REMOVED  player: u8, ship: u8
+//   TAG_SHIP_ADDED    player: u8, ship: u8, ship_type: u8, x: u8, y: u8, health: u32
+//   TAG_SHIP_MOVED    player: u8, ship: u8, x: u8, y: u8
+//   TAG_SHIP_HEALTH   player: u8, ship: u8, health: u32
+//   TAG_RESOURCES     player: u8, gold: i32, crew: i32, cannons: i32, supplies: i32 (deltas)
+//   TAG_CELL_OWNER    x: u8, y: u8, owner: u8 (player index, NO_OWNER when unclaimed)
+//   TAG_CELL_TYPE     x: u8, y: u8, cell_type: u8 (TerritoryCellType declaration order)
+//
+// Games created with `ConfigParams::compact_events` report the same changes
+// as a structured `TurnSummary` instead, alongside every seat's score, and
+// skip the per-action events it makes redundant (ShipMoved, ShipAttacked,
+// ShipBuilt, TerritoryClaimed, ResourcesCollected). Indexers then get one
+// log per instruction.
+
+pub const STATE_DIFF_VERSION: u8 = 1;
+
+pub const TAG_TURN: u8 = 0x01;
+pub const TAG_STATUS: u8 = 0x02;
+pub const TAG_SHIP_REMOVED: u8 = 0x03;
+pub const TAG_SHIP_ADDED: u8 = 0x04;
+pub const TAG_SHIP_MOVED: u8 = 0x05;
+pub const TAG_SHIP_HEALTH: u8 = 0x06;
+pub const TAG_RESOURCES: u8 = 0x07;
+pub const TAG_CELL_OWNER: u8 = 0x08;
+pub const TAG_CELL_TYPE: u8 = 0x09;
+
+/// Owner byte used in `TAG_CELL_OWNER` entries for unclaimed cells
+pub const NO_OWNER: u8 = u8::MAX;
+
+#[derive(Clone)]
+struct ShipSnapshot {
+    id: String,
+    position_x: u8,
+    position_y: u8,
+    health: u32,
+}
+
+/// Compact copy of the mirrored fields, captured before an instruction runs
+pub struct StateSnapshot {
+    status: GameStatus,
+    current_player_index: u8,
+    turn_number: u32,
+    fleets: Vec<Vec<ShipSnapshot>>,
+    resources: Vec<Resources>,
+    owners: Vec<u8>,
+    cell_types: Vec<TerritoryCellType>,
+}
+
+impl StateSnapshot {
+    pub fn capture(game: &PirateGame) -> Self {
+        Self {
+            status: game.status.clone(),
+            current_player_index: game.current_player_index,
+            turn_number: game.turn_number,
+            fleets: game
+                .players
+                .iter()
+                .map(|p| {
+                    p.ships
+                        .iter()
+                        .map(|s| ShipSnapshot {
+                            id: s.id.clone(),
+                            position_x: s.position_x,
+                            position_y: s.position_y,
+                            health: s.health,
+                        })
+                        .collect()
+                })
+                .collect(),
+            resources: game.players.iter().map(|p| p.resources.clone()).collect(),
+            owners: owner_indices(game),
+            cell_types: game.territory_map.iter().map(|c| c.cell_type).collect(),
+        }
+    }
+
+    /// Every mirrored field that changed since the snapshot was taken, in
+    /// the order the `StateDiff` payload lists them
+    pub fn changes(&self, game: &PirateGame) -> StateChanges {
+        let mut changes = StateChanges::default();
+
+        if self.current_player_index != game.current_player_index
+            || self.turn_number != game.turn_number
+        {
+            changes.turn = Some((game.current_player_index, game.turn_number));
+        }
+
+        if self.status != game.status {
+            changes.status = Some(status_code(&game.status));
+        }
+
+        for (player_idx, player) in game.players.iter().enumerate() {
+            let player_idx = player_idx as u8;
+            let before = self
+                .fleets
+                .get(player_idx as usize)
+                .cloned()
+                .unwrap_or_default();
+
+            // Removals first, highest index first, so indices stay valid while applying
+            for (ship_idx, old) in before.iter().enumerate().rev() {
+                if !player.ships.iter().any(|s| s.id == old.id) {
+                    changes.ships.push(ShipDelta {
+                        player: player_idx,
+                        ship: ship_idx as u8,
+                        change: ShipChange::Removed,
+                    });
+                }
+            }
+
+            for (ship_idx, ship) in player.ships.iter().enumerate() {
+                let ship_idx = ship_idx as u8;
+                match before.iter().find(|s| s.id == ship.id) {
+                    None => changes.ships.push(ShipDelta {
+                        player: player_idx,
+                        ship: ship_idx,
+                        change: ShipChange::Added {
+                            ship_type: ship_type_code(&ship.ship_type),
+                            x: ship.position_x,
+                            y: ship.position_y,
+                            health: ship.health,
+                        },
+                    }),
+                    Some(old) => {
+                        if old.position_x != ship.position_x || old.position_y != ship.position_y {
+                            changes.ships.push(ShipDelta {
+                                player: player_idx,
+                                ship: ship_idx,
+                                change: ShipChange::Moved {
+                                    x: ship.position_x,
+                                    y: ship.position_y,
+                                },
+                            });
+                        }
+                        if old.health != ship.health {
+                            changes.ships.push(ShipDelta {
+                                player: player_idx,
+                                ship: ship_idx,
+                                change: ShipChange::Health {
+                                    health: ship.health,
+                                    damage: old.health.saturating_sub(ship.health),
+                                },
+                            });
+                        }
+                    }
+                }
+            }
+
+            let old = self
+                .resources
+                .get(player_idx as usize)
+                .cloned()
+                .unwrap_or_default();
+            let new = &player.resources;
+            if old.gold != new.gold
+                || old.crew != new.crew
+                || old.cannons != new.cannons
+                || old.supplies != new.supplies
+            {
+                changes.resources.push(ResourceDelta {
+                    player: player_idx,
+                    gold: delta(old.gold, new.gold),
+                    crew: delta(old.crew, new.crew),
+                    cannons: delta(old.cannons, new.cannons),
+                    supplies: delta(old.supplies, new.supplies),
+                });
+            }
+        }
+
+        let owners = owner_indices(game);
+        for (index, owner) in owners.iter().enumerate() {
+            if self.owners.get(index) != Some(owner) {
+                let (x, y) = crate::state::map::index_to_coordinate(index);
+                changes.cells.push(CellDelta {
+                    x,
+                    y,
+                    change: CellChange::Owner { owner: *owner },
+                });
+            }
+        }
+
+        for (index, cell) in game.territory_map.iter().enumerate() {
+            if self.cell_types.get(index) != Some(&cell.cell_type) {
+                let (x, y) = crate::state::map::index_to_coordinate(index);
+                changes.cells.push(CellDelta {
+                    x,
+                    y,
+                    change: CellChange::CellType {
+                        cell_type: cell.cell_type as u8,
+                    },
+                });
+            }
+        }
+
+        changes
+    }
+
+    /// Encode every mirrored field that changed since the snapshot was taken.
+    /// Returns None when nothing changed.
+    pub fn encode_diff(&self, game: &PirateGame) -> Option<Vec<u8>> {
+        self.changes(game).encode()
+    }
+}
+
+/// One ship entry, addressed like the `StateDiff` payload: removals by the
+/// ship's index in the pre-instruction fleet, everything else by its index now
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct ShipDelta {
+    pub player: u8,
+    pub ship: u8,
+    pub change: ShipChange,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub enum ShipChange {
+    Removed,
+    Added {
+        ship_type: u8,
+        x: u8,
+        y: u8,
+        health: u32,
+    },
+    Moved {
+        x: u8,
+        y: u8,
+    },
+    /// Hull now, and how much of it was lost (0 for a repair)
+    Health {
+        health: u32,
+        damage: u32,
+    },
+}
+
+/// Change in one player's resources
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct ResourceDelta {
+    pub player: u8,
+    pub gold: i32,
+    pub crew: i32,
+    pub cannons: i32,
+    pub supplies: i32,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct CellDelta {
+    pub x: u8,
+    pub y: u8,
+    pub change: CellChange,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub enum CellChange {
+    /// Player index, NO_OWNER when unclaimed
+    Owner { owner: u8 },
+    /// `TerritoryCellType` declaration order
+    CellType { cell_type: u8 },
+}
+
+/// Everything an instruction changed in the mirrored fields
+#[derive(Default)]
+pub struct StateChanges {
+    pub turn: Option<(u8, u32)>,
+    pub status: Option<u8>,
+    pub ships: Vec<ShipDelta>,
+    pub resources: Vec<ResourceDelta>,
+    pub cells: Vec<CellDelta>,
+}
+
+impl StateChanges {
+    pub fn is_empty(&self) -> bool {
+        self.turn.is_none()
+            && self.status.is_none()
+            && self.ships.is_empty()
+            && self.resources.is_empty()
+            && self.cells.is_empty()
+    }
+
+    /// The `StateDiff` payload, or None when nothing changed
+    pub fn encode(&self) -> Option<Vec<u8>> {
+        if self.is_empty() {
+            return None;
+        }
+        let mut data = vec![STATE_DIFF_VERSION];
+
+        if let Some((current_player_index, turn_number)) = self.turn {
+            data.push(TAG_TURN);
+            data.push(current_player_index);
+            data.extend_from_slice(&turn_number.to_le_bytes());
+        }
+        if let Some(status) = self.status {
+            data.extend_from_slice(&[TAG_STATUS, status]);
+        }
+
+        // Each player's ships, then their resources, player by player
+        let players = self
+            .ships
+            .iter()
+            .map(|d| d.player)
+            .chain(self.resources.iter().map(|d| d.player))
+            .max();
+        for player in 0..=players.unwrap_or(0) {
+            for delta in self.ships.iter().filter(|d| d.player == player) {
+                let (p, s) = (delta.player, delta.ship);
+                match delta.change {
+                    ShipChange::Removed => data.extend_from_slice(&[TAG_SHIP_REMOVED, p, s]),
+                    ShipChange::Added {
+                        ship_type,
+                        x,
+                        y,
+                        health,
+                    } => {
+                        data.extend_from_slice(&[TAG_SHIP_ADDED, p, s, ship_type, x, y]);
+                        data.extend_from_slice(&health.to_le_bytes());
+                    }
+                    ShipChange::Moved { x, y } => {
+                        data.extend_from_slice(&[TAG_SHIP_MOVED, p, s, x, y])
+                    }
+                    ShipChange::Health { health, .. } => {
+                        data.extend_from_slice(&[TAG_SHIP_HEALTH, p, s]);
+                        data.extend_from_slice(&health.to_le_bytes());
+                    }
+                }
+            }
+            for delta in self.resources.iter().filter(|d| d.player == player) {
+                data.extend_from_slice(&[TAG_RESOURCES, delta.player]);
+                data.extend_from_slice(&delta.gold.to_le_bytes());
+                data.extend_from_slice(&delta.crew.to_le_bytes());
+                data.extend_from_slice(&delta.cannons.to_le_bytes());
+                data.extend_from_slice(&delta.supplies.to_le_bytes());
+            }
+        }
+
+        for delta in self.cells.iter() {
+            match delta.change {
+                CellChange::Owner { owner } => {
+                    data.extend_from_slice(&[TAG_CELL_OWNER, delta.x, delta.y, owner])
+                }
+                CellChange::CellType { cell_type } => {
+                    data.extend_from_slice(&[TAG_CELL_TYPE, delta.x, delta.y, cell_type])
+                }
+            }
+        }
+
+        Some(data)
+    }
+}
+
+/// Report everything that changed since `before` was captured: a `StateDiff`
+/// payload by default, or a structured `TurnSummary` with every seat's score
+/// when the game was created with compact events
+pub fn emit_state_diff(before: &StateSnapshot, game: &PirateGame) {
+    let changes = before.changes(game);
+    if changes.is_empty() {
+        return;
+    }
+
+    if game.compact_events {
+        emit!(TurnSummary {
+            game_id: game.game_id,
+            turn_number: game.turn_number,
+            current_player_index: game.current_player_index,
+            status: status_code(&game.status),
+            ships: changes.ships,
+            resources: changes.resources,
+            cells: changes.cells,
+            scores: game
+                .players
+                .iter()
+                .map(|p| final_score(p, &game.scoring))
+                .collect(),
+        });
+    } else if let Some(data) = changes.encode() {
+        emit!(StateDiff {
+            game_id: game.game_id,
+            turn_number: game.turn_number,
+            data,
+        });
+    }
+}
+
+pub(crate) fn owner_indices(game: &PirateGame) -> Vec<u8> {
+    game.territory_map
+        .iter()
+        .map(|cell| {
+            cell.owner
+                .and_then(|owner| game.players.iter().position(|p| p.pubkey == owner))
+                .map(|idx| idx as u8)
+                .unwrap_or(NO_OWNER)
+        })
+        .collect()
+}
+
+fn delta(old: u32, new: u32) -> i32 {
+    (new as i64 - old as i64).clamp(i32::MIN as i64, i32::MAX as i64) as i32
+}
+
+pub(crate) fn status_code(status: &GameStatus) -> u8 {
+    match status {
+        GameStatus::Waiting => 0,
+        GameStatus::Active => 1,
+        GameStatus::Completed => 2,
+    }
+}
+
+pub(crate) fn ship_type_code(ship_type: &ShipType) -> u8 {
+    match ship_type {
+        ShipType::Sloop => 0,
+        ShipType::Frigate => 1,
+        ShipType::Galleon => 2,
+        ShipType::Flagship => 3,
+    }
+}
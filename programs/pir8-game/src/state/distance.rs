@@ -0,0 +1,85 @@
+use crate::state::game::GameMode;
+use anchor_lang::prelude::*;
+
+pub use crate::rules::{chebyshev, manhattan, squared_euclidean};
+
+// ============================================================================
+// GRID DISTANCE
+// ============================================================================
+//
+// Integer-only distance math shared by every movement, combat and scan path.
+// Casual games allow diagonal steps (Chebyshev); ranked and agent games keep
+// the stricter orthogonal Manhattan metric.
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum DistanceMetric {
+    Manhattan,
+    Chebyshev,
+}
+
+impl DistanceMetric {
+    pub fn for_mode(mode: &GameMode) -> Self {
+        match mode {
+            GameMode::Casual => DistanceMetric::Chebyshev,
+            GameMode::Competitive | GameMode::AgentArena => DistanceMetric::Manhattan,
+        }
+    }
+
+    pub fn between(&self, from: (u8, u8), to: (u8, u8)) -> u32 {
+        match self {
+            DistanceMetric::Manhattan => manhattan(from, to),
+            DistanceMetric::Chebyshev => chebyshev(from, to),
+        }
+    }
+
+    /// Whether diagonal steps count as one, as the rules module takes it
+    pub fn allows_diagonals(&self) -> bool {
+        *self == DistanceMetric::Chebyshev
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manhattan_counts_orthogonal_steps() {
+        assert_eq!(manhattan((0, 0), (0, 0)), 0);
+        assert_eq!(manhattan((1, 1), (4, 3)), 5);
+        assert_eq!(manhattan((4, 3), (1, 1)), 5);
+        assert_eq!(manhattan((0, 9), (9, 0)), 18);
+    }
+
+    #[test]
+    fn chebyshev_counts_diagonals_as_one_step() {
+        assert_eq!(chebyshev((0, 0), (0, 0)), 0);
+        assert_eq!(chebyshev((1, 1), (4, 3)), 3);
+        assert_eq!(chebyshev((2, 2), (3, 3)), 1);
+        assert_eq!(chebyshev((0, 9), (9, 0)), 9);
+    }
+
+    #[test]
+    fn squared_euclidean_matches_pythagoras() {
+        assert_eq!(squared_euclidean((0, 0), (3, 4)), 25);
+        assert_eq!(squared_euclidean((3, 4), (0, 0)), 25);
+        assert_eq!(squared_euclidean((5, 5), (5, 5)), 0);
+    }
+
+    #[test]
+    fn metric_follows_game_mode() {
+        assert_eq!(
+            DistanceMetric::for_mode(&GameMode::Casual),
+            DistanceMetric::Chebyshev
+        );
+        assert_eq!(
+            DistanceMetric::for_mode(&GameMode::Competitive),
+            DistanceMetric::Manhattan
+        );
+        assert_eq!(
+            DistanceMetric::for_mode(&GameMode::AgentArena),
+            DistanceMetric::Manhattan
+        );
+        assert_eq!(DistanceMetric::Chebyshev.between((1, 1), (2, 2)), 1);
+        assert_eq!(DistanceMetric::Manhattan.between((1, 1), (2, 2)), 2);
+    }
+}
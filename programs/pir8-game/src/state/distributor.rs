@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+pub const PRIZE_DISTRIBUTOR_SEED: &[u8] = b"prize_distributor";
+pub const PRIZE_CLAIM_SEED: &[u8] = b"prize_claim";
+
+// ============================================================================
+// MERKLE PRIZE DISTRIBUTION
+// ============================================================================
+//
+// Tournament and season prizes can have thousands of recipients, far more
+// than a game's standings. An organizer funds a distributor with the whole
+// prize and posts only the merkle root of its (index, wallet, amount)
+// leaves; each recipient claims their leaf with a proof. A claim leaves a
+// tiny receipt PDA keyed by the leaf index, which is what stops it being
+// claimed twice. Once the reclaim time passes the organizer may close the
+// distributor and take back whatever is left.
+//
+// Leaves are hash(0x00 || index: u32 LE || wallet || amount: u64 LE) and
+// nodes are hash(0x01 || lower || higher), with each pair sorted so proofs
+// need no left/right flags.
+
+#[account]
+pub struct PrizeDistributor {
+    pub organizer: Pubkey,
+    pub distributor_id: u64,
+    pub merkle_root: [u8; 32],
+    pub total: u64,         // Lamports funded for prizes, above rent exemption
+    pub claimed: u64,       // Lamports paid out so far
+    pub claim_count: u32,   // Leaves claimed so far
+    pub reclaim_after: i64, // When the organizer may close and take back the rest
+    pub bump: u8,
+}
+
+impl PrizeDistributor {
+    pub const SPACE: usize = 8 + 32 + 8 + 32 + 8 + 8 + 4 + 8 + 1;
+}
+
+/// Receipt for one claimed leaf
+#[account]
+pub struct PrizeClaim {
+    pub distributor: Pubkey,
+    pub index: u32,
+    pub claimant: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+impl PrizeClaim {
+    pub const SPACE: usize = 8 + 32 + 4 + 32 + 8 + 1;
+}
+
+pub fn prize_leaf(index: u32, wallet: &Pubkey, amount: u64) -> [u8; 32] {
+    hashv(&[
+        &[0],
+        &index.to_le_bytes(),
+        wallet.as_ref(),
+        &amount.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+fn prize_node(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (lower, higher) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[&[1], lower, higher]).to_bytes()
+}
+
+/// Whether `proof` leads from `leaf` up to `root`
+pub fn verify_prize_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    proof
+        .iter()
+        .fold(leaf, |node, sibling| prize_node(&node, sibling))
+        == *root
+}
+
+/// Pair up one level of the tree; an odd last node is carried up unpaired
+fn next_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [a, b] => prize_node(a, b),
+            _ => pair[0],
+        })
+        .collect()
+}
+
+/// Root of the tree over `leaves`; for organizers' tooling and tests
+pub fn prize_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    level.first().copied().unwrap_or_default()
+}
+
+/// The proof for `leaves[index]` in the tree `prize_root` builds
+pub fn prize_proof(leaves: &[[u8; 32]], mut index: usize) -> Vec<[u8; 32]> {
+    let mut proof = Vec::new();
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        if let Some(sibling) = level.get(index ^ 1) {
+            proof.push(*sibling);
+        }
+        level = next_level(&level);
+        index /= 2;
+    }
+    proof
+}
@@ -0,0 +1,130 @@
+use crate::constants::MAP_SIZE;
+use crate::state::diff::{owner_indices, ship_type_code, status_code, NO_OWNER};
+use crate::state::game::{GameMode, PirateGame};
+use anchor_lang::solana_program::hash::hash;
+
+// ============================================================================
+// STATE EXPORT ENCODING
+// ============================================================================
+//
+// Canonical snapshot of the authoritative game state for replays, analytics
+// and dispute tooling. All integers are little-endian. Players are referred
+// to by their index in the player list, and NO_OWNER (0xFF) stands for none.
+//
+//   header   magic: [u8; 4] = "P8GS", version: u8, game_id: u64, status: u8,
+//            mode: u8, turn_number: u32, current_player_index: u8, seed: u64,
+//            winner: u8, total_pot: u64, token_pot: u64, player_count: u8
+//   player   pubkey: [u8; 32], is_active: u8, total_score: u32, gold: u32,
+//            crew: u32, cannons: u32, supplies: u32, ship_count: u8
+//   ship     id_len: u8, id: [u8; id_len], ship_type: u8, x: u8, y: u8,
+//            health: u32, max_health: u32, attack: u32, defense: u32, speed: u32
+//   cell     cell_type: u8, owner: u8, remaining_yield: u16,
+//            blockaded_until_turn: u16 (MAP_SIZE * MAP_SIZE cells, by map index)
+//
+// The blob is larger than Solana's return data limit, so it is served in
+// EXPORT_CHUNK_BYTES slices, each carrying the hash of the whole blob.
+
+pub const STATE_EXPORT_VERSION: u8 = 1;
+pub const STATE_EXPORT_MAGIC: [u8; 4] = *b"P8GS";
+
+/// Blob bytes per chunk, leaving room for the chunk header in return data
+pub const EXPORT_CHUNK_BYTES: usize = 900;
+
+pub fn encode_state(game: &PirateGame) -> Vec<u8> {
+    let mut data = Vec::with_capacity(2048);
+    data.extend_from_slice(&STATE_EXPORT_MAGIC);
+    data.push(STATE_EXPORT_VERSION);
+    data.extend_from_slice(&game.game_id.to_le_bytes());
+    data.push(status_code(&game.status));
+    data.push(mode_code(&game.mode));
+    data.extend_from_slice(&game.turn_number.to_le_bytes());
+    data.push(game.current_player_index);
+    data.extend_from_slice(&game.seed.to_le_bytes());
+    data.push(
+        game.winner
+            .and_then(|winner| game.players.iter().position(|p| p.pubkey == winner))
+            .map(|idx| idx as u8)
+            .unwrap_or(NO_OWNER),
+    );
+    data.extend_from_slice(&game.total_pot.to_le_bytes());
+    data.extend_from_slice(&game.token_pot.to_le_bytes());
+    data.push(game.players.len() as u8);
+
+    for player in game.players.iter() {
+        data.extend_from_slice(player.pubkey.as_ref());
+        data.push(player.is_active as u8);
+        data.extend_from_slice(&player.total_score.to_le_bytes());
+        data.extend_from_slice(&player.resources.gold.to_le_bytes());
+        data.extend_from_slice(&player.resources.crew.to_le_bytes());
+        data.extend_from_slice(&player.resources.cannons.to_le_bytes());
+        data.extend_from_slice(&player.resources.supplies.to_le_bytes());
+        data.push(player.ships.len() as u8);
+
+        for ship in player.ships.iter() {
+            let id = ship.id.as_bytes();
+            let id = &id[..id.len().min(u8::MAX as usize)];
+            data.push(id.len() as u8);
+            data.extend_from_slice(id);
+            data.extend_from_slice(&[
+                ship_type_code(&ship.ship_type),
+                ship.position_x,
+                ship.position_y,
+            ]);
+            data.extend_from_slice(&ship.health.to_le_bytes());
+            data.extend_from_slice(&ship.max_health.to_le_bytes());
+            data.extend_from_slice(&ship.attack.to_le_bytes());
+            data.extend_from_slice(&ship.defense.to_le_bytes());
+            data.extend_from_slice(&ship.speed.to_le_bytes());
+        }
+    }
+
+    let owners = owner_indices(game);
+    for (cell, owner) in game.territory_map.iter().zip(owners) {
+        data.extend_from_slice(&[cell.cell_type as u8, owner]);
+        data.extend_from_slice(&cell.remaining_yield.to_le_bytes());
+        data.extend_from_slice(&cell.blockaded_until_turn.to_le_bytes());
+    }
+    // Lobbies have no map yet; pad so the layout stays fixed
+    for _ in game.territory_map.len()..MAP_SIZE * MAP_SIZE {
+        data.extend_from_slice(&[0, NO_OWNER, 0, 0, 0, 0]);
+    }
+
+    data
+}
+
+/// Hash of the encoded state; equal to every export chunk's `blob_hash`
+pub fn state_hash(game: &PirateGame) -> [u8; 32] {
+    hash(&encode_state(game)).to_bytes()
+}
+
+/// Slice of an encoded blob, with what a client needs to reassemble and verify it
+pub struct ExportChunk<'a> {
+    pub chunk_count: u16,
+    pub blob_len: u32,
+    pub blob_hash: [u8; 32],
+    pub data: &'a [u8],
+}
+
+/// The `chunk_index`th slice of `blob`, or None past the end
+pub fn export_chunk(blob: &[u8], chunk_index: u16) -> Option<ExportChunk<'_>> {
+    let chunk_count = blob.len().div_ceil(EXPORT_CHUNK_BYTES).max(1) as u16;
+    if chunk_index >= chunk_count {
+        return None;
+    }
+    let start = chunk_index as usize * EXPORT_CHUNK_BYTES;
+    let end = (start + EXPORT_CHUNK_BYTES).min(blob.len());
+    Some(ExportChunk {
+        chunk_count,
+        blob_len: blob.len() as u32,
+        blob_hash: hash(blob).to_bytes(),
+        data: &blob[start..end],
+    })
+}
+
+fn mode_code(mode: &GameMode) -> u8 {
+    match mode {
+        GameMode::Casual => 0,
+        GameMode::Competitive => 1,
+        GameMode::AgentArena => 2,
+    }
+}
@@ -0,0 +1,75 @@
+use crate::state::game::{GameStatus, PirateGame};
+use crate::state::ledger::VaultLedger;
+use anchor_lang::prelude::*;
+
+// ============================================================================
+// PRACTICE FORKS
+// ============================================================================
+//
+// A fork copies the board, fleets and treasuries of a started or completed
+// game into a new zero-stakes game, so its players (or their agents) can play
+// on from that position and try other lines. The program keeps no move
+// history, so a fork always starts from the source's current position; to
+// study a particular turn, fork the game while it is on that turn. Seats keep
+// their original wallets. Forks have no pot, cannot be raised and never count
+// towards a profile; the forker owns the new account.
+
+/// A practice copy of `source`, in play from its current position
+pub fn practice_fork(
+    source: &PirateGame,
+    game_id: u64,
+    forker: Pubkey,
+    bump: u8,
+    now: i64,
+) -> PirateGame {
+    let mut game = source.clone();
+
+    game.game_id = game_id;
+    game.authority = forker;
+    game.bump = bump;
+    game.status = GameStatus::Active;
+    game.created_at = now;
+    game.started_at = Some(now);
+    game.completed_at = None;
+    game.winner = None;
+    game.turn_started_at = now;
+    game.scanned_this_turn = false;
+    game.parley_this_turn = false;
+    game.forked_from = Some(source.game_id);
+    game.signer_log = vec![forker];
+
+    // Nothing at stake
+    game.total_pot = 0;
+    game.token_pot = 0;
+    game.ledger = VaultLedger::default();
+    game.tips_total = 0;
+    game.options.stake = None;
+    game.options.jackpot_eligible = false;
+    game.options.creator_fee_bps = 0;
+    game.stake_raises = 0;
+    game.pending_raise = None;
+    game.pending_resignation = None;
+
+    // Results belong to the source
+    game.standings.clear();
+    game.payout_claims = 0;
+    game.vesting.clear();
+    game.open_fee_sessions = 0;
+    game.pending_jackpot = None;
+    game.final_state_hash = [0; 32];
+    game.payout_pot = 0;
+    game.payout_token_pot = 0;
+    game.creator_fee_due = 0;
+    game.rebate_pool = 0;
+    for player in game.players.iter_mut() {
+        player.profile_settled = false;
+        // The source vault holds the deposits
+        player.liveness_deposit = 0;
+        // The fork's pot has funded no sessions
+        player.fee_session_opened = false;
+        // Timeouts here slash no bond
+        player.bonded_agent = false;
+    }
+
+    game
+}
@@ -1,7 +1,37 @@
 use crate::constants::*;
 use crate::errors::GameError;
-use crate::state::map::TerritoryCell;
-use crate::state::player::{PlayerData, ShipData, ShipType, tick_ghost_fleet};
+use crate::events::{
+    CargoSpilled, GameCompleted, MapShrunk, PlayerEliminated, PortBlockaded, ShipSunk,
+    StartCommitted, VictoryPointsScored, VictoryThreatened,
+};
+use crate::rules::{
+    check_path, resource_value, victories_within, Victory, VictoryProgress, VICTORY_WARNING_PERCENT,
+};
+use crate::state::cargo::{home_port, spill_cargo};
+use crate::state::config::ConfigParams;
+use crate::state::contract::{advance_contracts, Contract};
+use crate::state::distance::{chebyshev, DistanceMetric};
+use crate::state::export::state_hash;
+use crate::state::jackpot::JackpotCommit;
+use crate::state::ledger::VaultLedger;
+use crate::state::map::{
+    generate_strategic_map, index_to_coordinate, nearest_open_water, place_golden_port,
+    regenerate_treasure, ring_of, TerritoryCell, TerritoryCellType, GOLDEN_PORT_POSITION,
+    SPAWN_POSITIONS,
+};
+use crate::state::payout::{PayoutSchedule, Standing};
+use crate::state::player::{
+    final_score, is_pot_eligible, tick_ghost_fleet, Departure, PlayerData, PlayerRole, Resources,
+    ShipData, ShipType,
+};
+use crate::state::raise::StakeRaise;
+use crate::state::rng::{shuffled_indices, RngAudit, RngStream, REVEAL_DELAY_SLOTS};
+use crate::state::rulebook::{Rulebook, RulebookParams};
+use crate::state::scoring::{ScoringPreset, ScoringWeights};
+use crate::state::shipyard::{advance_construction, ConstructionOrder};
+use crate::state::simultaneous::{OrderCommitment, RoundPhase};
+use crate::state::tutorial::advance_tutorial;
+use crate::state::vesting::VestingTranche;
 use anchor_lang::prelude::*;
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
@@ -26,6 +56,261 @@ pub enum GameMode {
     AgentArena,
 }
 
+impl GameMode {
+    /// Whether entering a cell next to an enemy ship ends a ship's move.
+    /// Casual games sail freely; ranked and agent games make screening count.
+    pub fn zone_of_control(&self) -> bool {
+        !matches!(self, GameMode::Casual)
+    }
+}
+
+/// Who may join a lobby. Empty invite list and no code hash means open to all.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct LobbyAccess {
+    pub min_rating: Option<u32>,
+    pub max_rating: Option<u32>,
+    pub min_level: Option<u8>,
+    pub min_reputation: Option<u16>,
+    /// Every seat must belong to a registered agent
+    pub agents_only: bool,
+    /// AgentRegistry `games_played` floor in agents_only lobbies
+    pub min_agent_games: Option<u64>,
+    /// AgentRegistry bond floor, in lamports, in agents_only lobbies
+    pub min_agent_bond: Option<u64>,
+    pub invite_list: Vec<Pubkey>,           // Up to MAX_PLAYERS wallets
+    pub invite_code_hash: Option<[u8; 32]>, // sha256 of the shared invite code
+}
+
+impl LobbyAccess {
+    pub fn is_invite_only(&self) -> bool {
+        !self.invite_list.is_empty() || self.invite_code_hash.is_some()
+    }
+}
+
+/// What every player starts with. Bounded by `GameConfig` at creation.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct StartingLoadout {
+    pub resources: Resources,
+    pub fleet: Vec<ShipType>,
+}
+
+impl Default for StartingLoadout {
+    fn default() -> Self {
+        Self {
+            resources: Resources {
+                gold: 1000,
+                crew: 50,
+                cannons: 10,
+                supplies: 100,
+            },
+            fleet: vec![ShipType::Sloop, ShipType::Frigate],
+        }
+    }
+}
+
+impl StartingLoadout {
+    pub fn is_within(&self, params: &ConfigParams) -> bool {
+        let max = &params.max_starting_resources;
+        !self.fleet.is_empty()
+            && self.fleet.len() <= params.max_starting_ships as usize
+            && self.resources.gold <= max.gold
+            && self.resources.crew <= max.crew
+            && self.resources.cannons <= max.cannons
+            && self.resources.supplies <= max.supplies
+    }
+}
+
+/// SPL Token or Token-2022 entry fee used instead of the default SOL stake
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct TokenStake {
+    pub mint: Pubkey,
+    pub entry_fee: u64, // Base units sent by each player, before any transfer fee
+}
+
+/// How a time-limit finish with several players level on score is settled
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum TieBreak {
+    /// Tied leaders play up to OVERTIME_ROUNDS more rounds; the first to pull
+    /// ahead wins, and the pot is split if they are still level after that
+    #[default]
+    Overtime,
+    /// Split the pot evenly between the tied leaders straight away
+    SplitPot,
+}
+
+/// What a game is won by
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum GameObjective {
+    /// Fleet dominance, territory control or economic victory
+    #[default]
+    Conquest,
+    /// Each player has one designated flagship; losing it eliminates them
+    /// outright and the last captain with a flagship afloat wins
+    CaptureTheFlagship,
+    /// Hold the Golden Port at the center of the map: each round it scores a
+    /// victory point for its holder, and the first to `target_points` wins
+    KingOfTheHill { target_points: u16 },
+    /// Haul treasure home: gold only counts once a ship carries it to its
+    /// owner's home port, and the first to deliver `target_gold` wins
+    TreasureRace { target_gold: u32 },
+    /// Single-player walkthrough on the fixed tutorial map, won by reaching
+    /// every checkpoint; see `state::tutorial`
+    Tutorial,
+}
+
+/// How a game was won, as `GameCompleted` reports it
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VictoryType {
+    FleetDominance,
+    TerritoryControl,
+    Economic,
+    /// Highest score when the round limit was reached
+    TimeLimit,
+    /// Sole leader after an overtime round
+    SuddenDeath,
+    /// Last player left after the others timed out
+    TimeForfeit,
+    /// Last player left after the others resigned
+    Resignation,
+    /// Last flagship afloat
+    FlagshipCaptured,
+    /// The Golden Port's holder reached the points target
+    KingOfTheHill,
+    /// First to land the treasure-race target at their home port
+    TreasureRace,
+    /// The learner reached the last tutorial checkpoint
+    TutorialComplete,
+}
+
+impl VictoryType {
+    pub fn label(&self) -> &'static str {
+        match self {
+            VictoryType::FleetDominance => "Fleet Dominance",
+            VictoryType::TerritoryControl => "Territory Control",
+            VictoryType::Economic => "Economic Victory",
+            VictoryType::TimeLimit => "Time Limit",
+            VictoryType::SuddenDeath => "Sudden Death",
+            VictoryType::TimeForfeit => "Time Forfeit",
+            VictoryType::Resignation => "Resignation",
+            VictoryType::FlagshipCaptured => "Flagship Captured",
+            VictoryType::KingOfTheHill => "King of the Hill",
+            VictoryType::TreasureRace => "Treasure Race",
+            VictoryType::TutorialComplete => "Tutorial Complete",
+        }
+    }
+}
+
+impl std::fmt::Display for VictoryType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+impl From<Victory> for VictoryType {
+    fn from(victory: Victory) -> Self {
+        match victory {
+            Victory::FleetDominance => VictoryType::FleetDominance,
+            Victory::TerritoryControl => VictoryType::TerritoryControl,
+            Victory::Economic => VictoryType::Economic,
+        }
+    }
+}
+/// Victory points the Golden Port scores each round
+pub const GOLDEN_PORT_POINTS_PER_ROUND: u16 = 1;
+
+/// Per-game rule options chosen at creation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct GameOptions {
+    /// Alternate the seat order every round (1-2-3-4, 4-3-2-1, ...)
+    pub serpentine_turns: bool,
+    /// All players commit and reveal orders each round instead of taking turns
+    pub simultaneous_turns: bool,
+    pub access: LobbyAccess,
+    pub loadout: StartingLoadout,
+    pub stake: Option<TokenStake>,
+    /// The winner rolls for the progressive jackpot when claiming
+    pub jackpot_eligible: bool,
+    pub tie_break: TieBreak,
+    pub payout_schedule: PayoutSchedule,
+    /// Share of the SOL pot set aside for the lobby's creator, in basis points
+    pub creator_fee_bps: u16,
+    pub objective: GameObjective,
+    pub scoring: ScoringPreset,
+    /// Tournament play: no randomness but the seed, so the seed and the
+    /// recorded instructions replay the game bit for bit; see state::rng
+    pub deterministic: bool,
+}
+
+impl GameOptions {
+    /// Whether the winner rolls for the jackpot. Deterministic games never
+    /// do, as no replay of the seed could reproduce the slot hash it uses.
+    pub fn rolls_for_jackpot(&self) -> bool {
+        self.jackpot_eligible && !self.deterministic
+    }
+}
+
+/// A resignation that can still be withdrawn: until `expires_at`, and only
+/// while play is still where the resignation left it
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ResignationGrace {
+    pub player: Pubkey,
+    pub expires_at: i64,
+    pub turn_number: u32,
+    pub turn_slot: u8,
+}
+
+/// Cell a starting ship was actually deployed to
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct SpawnPosition {
+    pub player_index: u8,
+    pub x: u8,
+    pub y: u8,
+}
+
+/// A single turn action, used to submit several actions in one transaction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub enum GameAction {
+    MoveShip {
+        ship_id: String,
+        to_x: u8,
+        to_y: u8,
+        via: Vec<u8>,
+        decision_time_ms: Option<u64>,
+    },
+    AttackShip {
+        attacker_ship_id: String,
+        target_ship_id: String,
+    },
+    ClaimTerritory {
+        ship_id: String,
+    },
+    CollectResources,
+    BuildShip {
+        ship_type: ShipType,
+        port_x: u8,
+        port_y: u8,
+    },
+    ScanCoordinate {
+        coordinate_x: u8,
+        coordinate_y: u8,
+    },
+    ActivateGhostFleet,
+    EndTurn,
+    CancelConstruction {
+        port_x: u8,
+        port_y: u8,
+    },
+    UseSpyglass {
+        ship_id: String,
+    },
+    LoadCargo {
+        ship_id: String,
+    },
+    DeliverCargo {
+        ship_id: String,
+    },
+}
+
 #[account]
 pub struct PirateGame {
     pub game_id: u64,
@@ -42,6 +327,51 @@ pub struct PirateGame {
     pub weather_type: WeatherType,
     pub weather_duration: u8,
     pub bump: u8,
+    pub total_pot: u64, // Entry fees held in the game account above rent exemption
+    pub token_pot: u64, // Token stakes actually credited to the vault, net of transfer fees
+    pub ledger: VaultLedger, // Lamports moved in and out of the game account; see state::ledger
+    pub tips_total: u64, // Lamports spectators have tipped into total_pot
+    pub options: GameOptions,
+    pub seed: u64,
+    pub rng_audit: RngAudit,
+    pub rng: RngStream, // Weather and initiative rolls, seeded from `seed` at start
+    pub start_reveal_slot: Option<u64>, // Slot whose hash seeds a started lobby; see reveal_seed
+    pub turn_order: Vec<u8>, // Player indices in seat order, shuffled from the seed at start
+    pub turn_slot: u8,  // Position within the current round's seat order
+    pub turn_started_at: i64, // When the current sequential turn, commit or reveal window began
+    pub scanned_this_turn: bool, // The current player has taken their free scan
+    pub parley_this_turn: bool, // The current player called a parley; see apply_parley
+    pub round_phase: RoundPhase, // Simultaneous games only
+    pub pending_orders: Vec<OrderCommitment>, // Simultaneous games only, cleared each round
+    pub spawn_positions: Vec<SpawnPosition>, // Where each starting ship was placed, for auditing
+    pub resource_multipliers_bps: [u32; 4], // Copied from GameConfig at creation
+    pub scoring: ScoringWeights, // Resolved from the creator's preset at creation
+    pub compact_events: bool, // Copied from GameConfig at creation; see state::diff
+    pub resign_grace: bool, // Copied from GameConfig at creation; see cancel_resignation
+    pub pity_bonus_gold: u32, // Copied from GameConfig at creation; zero outside casual lobbies
+    pub vesting_threshold_lamports: Option<u64>, // Copied from GameConfig at creation; see state::vesting
+    pub rulebook_version: u32, // Version of the mode's Rulebook copied at start; 0 for built-in rules
+    pub rules: RulebookParams, // Balance values the game plays under; see state::rulebook
+    pub construction_queue: Vec<ConstructionOrder>, // At most one order per port
+    pub contracts: Vec<Contract>, // Privateer offers and accepted contracts; see state::contract
+    pub next_contract_id: u16,
+    pub overtime_players: Vec<Pubkey>, // Tied leaders still contending in sudden-death overtime
+    pub standings: Vec<Standing>,      // Final places of pot-eligible players, best first
+    pub final_state_hash: [u8; 32], // Hash of the exported state at completion; see state::export
+    pub payout_claims: u8,          // Bit i set once standings[i] has claimed their payout
+    pub payout_pot: u64,            // total_pot when the game completed, divided by the schedule
+    pub payout_token_pot: u64,      // token_pot when the game completed
+    pub creator_fee_due: u64,       // Creator's cut of the SOL pot, claimable after completion
+    pub rebate_pool: u64,           // SOL set aside at completion for finishers' move rebates
+    pub vesting: Vec<VestingTranche>, // Winners' unreleased second tranches, still in total_pot
+    pub open_fee_sessions: u8,      // Sessions holding budget out of the pot; claims wait for them
+    pub pending_jackpot: Option<JackpotCommit>, // First place's roll; see reveal_jackpot_roll
+    pub stake_raises: u8,           // Times the SOL stake has been doubled mid-game
+    pub pending_raise: Option<StakeRaise>, // Raise awaiting the other captains' answers
+    pub pending_resignation: Option<ResignationGrace>, // Latest resignation, while it may be withdrawn
+    pub tutorial_checkpoint: u8,                       // Tutorial checkpoints reached so far
+    pub forked_from: Option<u64>,                      // Game this practice fork was copied from
+    pub signer_log: Vec<Pubkey>, // Wallets seen signing or paying for this game, oldest first
     pub players: Vec<PlayerData>,
     pub territory_map: Vec<TerritoryCell>, // Flattened MAP_SIZE x MAP_SIZE
 }
@@ -51,25 +381,553 @@ impl PirateGame {
     // Solana has a 10KB limit for account reallocation in inner instructions
     pub const SPACE: usize = 10240; // 10KB - maximum allowed
 
+    /// Lamports each captain has staked so far, after any raises
+    pub fn sol_stake_per_player(&self) -> u64 {
+        SOL_ENTRY_FEE_LAMPORTS << self.stake_raises
+    }
+
+    /// Lamports escrowed for a pending raise, held outside the pot
+    pub fn raise_escrow(&self) -> u64 {
+        self.pending_raise
+            .as_ref()
+            .map(StakeRaise::escrow)
+            .unwrap_or(0)
+    }
+
+    /// Liveness deposits still held for their seats, outside the pot
+    pub fn liveness_escrow(&self) -> u64 {
+        self.players.iter().map(|p| p.liveness_deposit).sum()
+    }
+
+    /// Everything the vault holds above rent exemption that is not pot
+    pub fn held_in_escrow(&self) -> u64 {
+        self.raise_escrow().saturating_add(self.liveness_escrow())
+    }
+
+    /// Remember a wallet that signed or paid for an instruction on this game.
+    /// The log stops growing once it holds MAX_LOGGED_SIGNERS wallets.
+    pub fn log_signer(&mut self, signer: Pubkey) {
+        if self.signer_log.len() < MAX_LOGGED_SIGNERS && !self.signer_log.contains(&signer) {
+            self.signer_log.push(signer);
+        }
+    }
+
+    /// Ranked lobbies turn away a join when either the seat's wallet or the
+    /// wallet paying for it has already been seen in this game - one funding
+    /// wallet running several seats. The creator may still take their own seat.
+    pub fn check_join_funding(&self, player: &Pubkey, payer: &Pubkey) -> Result<()> {
+        if self.mode != GameMode::Competitive {
+            return Ok(());
+        }
+        let own_lobby = *player == self.authority && payer == player;
+        let seen = self.signer_log.contains(player) || self.signer_log.contains(payer);
+        require!(own_lobby || !seen, GameError::SharedFundingWallet);
+        Ok(())
+    }
+
+    /// Pass the turn to the next seat, skipping players who have forfeited
     pub fn advance_turn(&mut self) {
-        if self.player_count > 0 {
-            self.current_player_index = (self.current_player_index + 1) % self.player_count;
-            if self.current_player_index == 0 {
+        self.warn_victory_proximity();
+        self.scanned_this_turn = false;
+        self.parley_this_turn = false;
+        for _ in 0..self.player_count {
+            self.turn_slot = (self.turn_slot + 1) % self.player_count;
+            if self.turn_slot == 0 {
                 self.turn_number += 1;
-                // Decrement ghost fleet turns at end of full round
-                for player in &mut self.players {
-                    if player.is_active {
-                        tick_ghost_fleet(player);
+                self.end_round();
+            }
+            self.current_player_index = self.seat_for_slot(self.turn_slot);
+            let seated = self
+                .get_current_player()
+                .map(|p| p.is_active)
+                .unwrap_or(true);
+            if seated {
+                break;
+            }
+        }
+    }
+
+    /// Count a spectator's tip into the pot, while tips are open and under
+    /// the cap. The caller moves the lamports.
+    pub fn add_tip(&mut self, tipper: &Pubkey, amount: u64) -> Result<()> {
+        require!(self.status == GameStatus::Active, GameError::GameNotActive);
+        require!(self.options.stake.is_none(), GameError::StakeAssetMismatch);
+        require!(
+            self.forked_from.is_none() && self.options.objective != GameObjective::Tutorial,
+            GameError::PracticeGame
+        );
+        require!(self.turn_number <= TIP_CUTOFF_TURN, GameError::TipsClosed);
+        // Captains would only be paying themselves
+        require!(
+            !self
+                .players
+                .iter()
+                .any(|p| p.pubkey == *tipper && p.role == PlayerRole::Captain),
+            GameError::CaptainCannotTip
+        );
+        self.tips_total = self
+            .tips_total
+            .checked_add(amount)
+            .filter(|total| amount > 0 && *total <= MAX_TIPS_LAMPORTS)
+            .ok_or(GameError::InvalidTip)?;
+        self.total_pot = self.total_pot.saturating_add(amount);
+        self.ledger.deposit(amount);
+        Ok(())
+    }
+
+    /// Whole seconds since the current sequential turn began
+    pub fn turn_elapsed_seconds(&self, now: i64) -> u32 {
+        now.saturating_sub(self.turn_started_at)
+            .clamp(0, u32::MAX as i64) as u32
+    }
+
+    /// Charge the current player's bank for the time their turn took, bank
+    /// the increment and restart the clock. Fails once the bank has run out.
+    pub fn charge_time_bank(&mut self, now: i64) -> Result<()> {
+        // Tutorials are untimed
+        if self.options.objective == GameObjective::Tutorial {
+            self.turn_started_at = now;
+            return Ok(());
+        }
+        let elapsed = self.turn_elapsed_seconds(now);
+        let player = self
+            .players
+            .get_mut(self.current_player_index as usize)
+            .ok_or(GameError::NotPlayerTurn)?;
+        require!(
+            elapsed <= player.time_bank_seconds,
+            GameError::TimeBankExhausted
+        );
+        player.time_bank_seconds = player.time_bank_seconds - elapsed + TIME_INCREMENT_SECONDS;
+        player.clocked_decisions = player.clocked_decisions.saturating_add(1);
+        player.clocked_seconds = player.clocked_seconds.saturating_add(elapsed);
+        self.turn_started_at = now;
+        Ok(())
+    }
+
+    /// Per-round upkeep shared by sequential and simultaneous games
+    pub fn end_round(&mut self) {
+        // Decrement ghost fleet turns at end of full round
+        for player in &mut self.players {
+            if player.is_active {
+                tick_ghost_fleet(player);
+            }
+        }
+        regenerate_treasure(&mut self.territory_map);
+        self.roll_weather();
+        self.update_blockades();
+        self.shrink_map();
+        advance_construction(self);
+        advance_contracts(self);
+        match self.options.objective {
+            GameObjective::KingOfTheHill { target_points } => self.score_golden_port(target_points),
+            GameObjective::Tutorial => advance_tutorial(self),
+            _ => {}
+        }
+    }
+
+    /// Count down the current weather, drawing the next front from the
+    /// game's stream once it has blown over. Tutorials keep calm seas.
+    pub fn roll_weather(&mut self) {
+        if self.options.objective == GameObjective::Tutorial {
+            return;
+        }
+        self.weather_duration = self.weather_duration.saturating_sub(1);
+        if self.weather_duration > 0 {
+            return;
+        }
+        self.weather_type = match self.rng.next_below(4) {
+            0 => WeatherType::Calm,
+            1 => WeatherType::TradeWinds,
+            2 => WeatherType::Storm,
+            _ => WeatherType::Fog,
+        };
+        let spread = (WEATHER_MAX_ROUNDS - WEATHER_MIN_ROUNDS + 1) as u64;
+        self.weather_duration = WEATHER_MIN_ROUNDS + self.rng.next_below(spread) as u8;
+    }
+
+    /// Sudden death, battle-royale style: each round after SUDDEN_DEATH_TURN
+    /// turns the next ring of the map, from the edge in, to Storm. Consumed
+    /// cells lose their owner and with it their income, and every ship still
+    /// inside the storm takes damage each round, sinking if it runs out.
+    pub fn shrink_map(&mut self) {
+        if self.options.objective == GameObjective::Tutorial
+            || self.turn_number <= SUDDEN_DEATH_TURN
+        {
+            return;
+        }
+        let rounds = self.turn_number - SUDDEN_DEATH_TURN;
+        let rings_consumed = rounds.min(SUDDEN_DEATH_RINGS as u32) as u8;
+
+        let mut cells_consumed = 0u8;
+        if rounds <= SUDDEN_DEATH_RINGS as u32 {
+            let ring = rings_consumed - 1;
+            for index in 0..self.territory_map.len() {
+                let (x, y) = index_to_coordinate(index);
+                if ring_of((x, y)) != ring {
+                    continue;
+                }
+                let cell = &mut self.territory_map[index];
+                if let Some(owner) = cell.owner.take() {
+                    let coord = format!("{},{}", x, y);
+                    if let Some(player) = self.players.iter_mut().find(|p| p.pubkey == owner) {
+                        player.controlled_territories.retain(|c| *c != coord);
                     }
                 }
+                cell.cell_type = TerritoryCellType::Storm;
+                cell.remaining_yield = 0;
+                cell.blockaded_until_turn = 0;
+                cells_consumed += 1;
+            }
+        }
+
+        let mut ships_hit = 0u8;
+        for ship in self
+            .players
+            .iter_mut()
+            .filter(|p| p.is_active)
+            .flat_map(|p| p.ships.iter_mut())
+            .filter(|s| ring_of((s.position_x, s.position_y)) < rings_consumed)
+        {
+            ship.health = ship.health.saturating_sub(SUDDEN_DEATH_STORM_DAMAGE);
+            ships_hit += 1;
+        }
+
+        emit!(MapShrunk {
+            game_id: self.game_id,
+            turn_number: self.turn_number,
+            rings_consumed,
+            cells_consumed,
+            ships_hit,
+        });
+        self.purge_sunk_ships();
+    }
+
+    /// Award the round's victory point to whoever holds the Golden Port: the
+    /// only player with a ship on or next to it, or its owner when no one is
+    /// there. A contested port scores for no one, and scoring stops once
+    /// someone has reached the target so there is exactly one king.
+    pub fn score_golden_port(&mut self, target_points: u16) {
+        if self.hill_king(target_points).is_some() {
+            return;
+        }
+        let (x, y) = GOLDEN_PORT_POSITION;
+        let Some(cell) = self.territory_map.get(x as usize * MAP_SIZE + y as usize) else {
+            return;
+        };
+
+        let mut present = self
+            .players
+            .iter()
+            .filter(|p| p.is_active)
+            .filter(|p| {
+                p.ships
+                    .iter()
+                    .any(|s| chebyshev((s.position_x, s.position_y), (x, y)) <= 1)
+            })
+            .map(|p| p.pubkey);
+        let holder = match (present.next(), present.next()) {
+            (Some(holder), None) => holder,
+            (None, _) => match cell.owner {
+                Some(owner) => owner,
+                None => return,
+            },
+            _ => return,
+        };
+
+        let game_id = self.game_id;
+        let turn_number = self.turn_number;
+        let Some(player) = self.get_player_mut(&holder) else {
+            return;
+        };
+        player.victory_points = player
+            .victory_points
+            .saturating_add(GOLDEN_PORT_POINTS_PER_ROUND);
+        emit!(VictoryPointsScored {
+            game_id,
+            player: holder,
+            points: GOLDEN_PORT_POINTS_PER_ROUND,
+            total: player.victory_points,
+            turn_number,
+        });
+    }
+
+    /// The active player who has reached a king-of-the-hill target, if any
+    pub fn hill_king(&self, target_points: u16) -> Option<Pubkey> {
+        self.players
+            .iter()
+            .find(|p| p.is_active && p.victory_points >= target_points)
+            .map(|p| p.pubkey)
+    }
+
+    /// Where a player stands against the Conquest victory conditions
+    pub fn victory_progress(&self, player: &PlayerData) -> VictoryProgress {
+        let fleet_power = |p: &PlayerData| -> u32 {
+            p.ships
+                .iter()
+                .filter(|s| s.is_alive())
+                .map(|s| s.health)
+                .sum()
+        };
+        let valuable_territories = self
+            .territory_map
+            .iter()
+            .filter(|cell| {
+                matches!(
+                    cell.cell_type,
+                    TerritoryCellType::Port
+                        | TerritoryCellType::Island
+                        | TerritoryCellType::Treasure
+                )
+            })
+            .count() as u32;
+
+        VictoryProgress {
+            fleet_power: fleet_power(player),
+            total_fleet_power: self.players.iter().map(fleet_power).sum(),
+            territories: player.controlled_territories.len() as u32,
+            valuable_territories,
+            resource_value: resource_value(
+                player.resources.gold,
+                player.resources.crew,
+                player.resources.cannons,
+                player.resources.supplies,
+            ),
+        }
+    }
+
+    /// Announce each captain who has come within VICTORY_WARNING_PERCENT of
+    /// a Conquest victory condition, once per crossing: a condition they
+    /// fall back from is announced again if they close in once more
+    pub fn warn_victory_proximity(&mut self) {
+        if self.status != GameStatus::Active || self.options.objective != GameObjective::Conquest {
+            return;
+        }
+        let thresholds = self.rules.victory_thresholds();
+        for index in 0..self.players.len() {
+            let player = &self.players[index];
+            if !player.is_active || player.role != PlayerRole::Captain {
+                continue;
+            }
+            let progress = self.victory_progress(player);
+            let near = victories_within(&progress, &thresholds, VICTORY_WARNING_PERCENT)
+                .into_iter()
+                .fold(0, |flags, victory| flags | victory.bit());
+            let crossed = near & !player.victory_warnings;
+            for victory in Victory::ALL {
+                if crossed & victory.bit() != 0 {
+                    emit!(VictoryThreatened {
+                        game_id: self.game_id,
+                        player: player.pubkey,
+                        victory: VictoryType::from(victory),
+                        turn_number: self.turn_number,
+                    });
+                }
             }
+            self.players[index].victory_warnings = near;
         }
     }
 
+    /// Blockade every owned Port with an enemy ship on or next to it at the end
+    /// of the round. The blockade lasts through the round that is starting.
+    pub fn update_blockades(&mut self) {
+        let turn_number = self.turn_number;
+        for index in 0..self.territory_map.len() {
+            let cell = self.territory_map[index];
+            let Some(owner) = cell.owner else {
+                continue;
+            };
+            if cell.cell_type != TerritoryCellType::Port {
+                continue;
+            }
+
+            let port = index_to_coordinate(index);
+            let blockaded = self
+                .players
+                .iter()
+                .filter(|p| p.pubkey != owner && p.is_active)
+                .flat_map(|p| p.ships.iter())
+                .any(|s| chebyshev((s.position_x, s.position_y), port) <= 1);
+            if !blockaded {
+                continue;
+            }
+
+            self.territory_map[index].blockaded_until_turn =
+                turn_number.min(u16::MAX as u32) as u16;
+            emit!(PortBlockaded {
+                game_id: self.game_id,
+                owner,
+                port_x: port.0,
+                port_y: port.1,
+                blockaded_until_turn: turn_number,
+            });
+        }
+    }
+
+    /// Player index holding `slot` in the current round. Serpentine games walk
+    /// the seat order backwards on even rounds.
+    pub fn seat_for_slot(&self, slot: u8) -> u8 {
+        let count = self.turn_order.len();
+        if count == 0 {
+            return slot;
+        }
+        let position = if self.options.serpentine_turns && self.turn_number & 1 == 0 {
+            count - 1 - slot as usize % count
+        } else {
+            slot as usize % count
+        };
+        self.turn_order[position]
+    }
+
+    /// Bank action XP for a player (capped per game)
+    pub fn grant_xp(&mut self, pubkey: &Pubkey, amount: u32) {
+        if let Some(player) = self.get_player_mut(pubkey) {
+            crate::state::profile::grant_xp(player, amount);
+        }
+    }
+
+    /// Recompute which players have acted enough to be scored and share the pot.
+    /// If nobody qualifies, every active player stays eligible so the game can still end.
+    pub fn refresh_pot_eligibility(&mut self) {
+        let any_eligible = self.players.iter().any(is_pot_eligible);
+        for player in self.players.iter_mut() {
+            player.pot_eligible = if any_eligible {
+                is_pot_eligible(player)
+            } else {
+                player.is_active
+            };
+        }
+    }
+
+    /// Whether `pubkey` won outright or shares first place in a drawn game
+    pub fn is_winner(&self, pubkey: &Pubkey) -> bool {
+        self.winner == Some(*pubkey)
+            || self
+                .standings
+                .iter()
+                .any(|s| s.player == *pubkey && s.place == 0)
+    }
+
+    /// Whether every nonzero payout has been claimed, every profile settled
+    /// and every liveness deposit returned or forfeited, so nothing further
+    /// needs the game account
+    pub fn is_fully_settled(&self) -> bool {
+        let payouts_claimed = self.standings.iter().enumerate().all(|(seat, standing)| {
+            let tied = self
+                .standings
+                .iter()
+                .filter(|s| s.place == standing.place)
+                .count() as u8;
+            self.payout_claims & (1 << seat) != 0
+                || (self.options.payout_schedule.share_bps(standing.place, tied) == 0
+                    && self.move_rebate(&standing.player) == 0)
+        });
+        payouts_claimed
+            && self.players.iter().all(|p| p.profile_settled)
+            && self.pending_raise.is_none()
+            && self.vesting.is_empty()
+            && self.open_fee_sessions == 0
+            && self.pending_jackpot.is_none()
+            && self.liveness_escrow() == 0
+    }
+
+    /// Whether resetting would wipe nothing anyone still has a claim on: a
+    /// lobby nobody has joined, or a finished game with everything settled
+    pub fn is_resettable(&self) -> bool {
+        match self.status {
+            GameStatus::Waiting => self.player_count == 0,
+            GameStatus::Completed => self.is_fully_settled(),
+            _ => false,
+        }
+    }
+
+    /// Fix the final places at completion: `leaders` share first place and
+    /// every other pot-eligible player follows by score. Also sets aside the
+    /// creator fee and freezes the pots the payout schedule divides.
+    pub fn record_standings(&mut self, leaders: &[Pubkey]) {
+        let mut rest: Vec<(Pubkey, u32)> = self
+            .players
+            .iter()
+            .filter(|p| p.pot_eligible && !leaders.contains(&p.pubkey))
+            .map(|p| (p.pubkey, final_score(p, &self.scoring)))
+            .collect();
+        rest.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+
+        self.standings = leaders
+            .iter()
+            .map(|&player| Standing { player, place: 0 })
+            .collect();
+        let mut place = leaders.len() as u8;
+        for (i, &(player, score)) in rest.iter().enumerate() {
+            if i > 0 && rest[i - 1].1 != score {
+                place = leaders.len() as u8 + i as u8;
+            }
+            self.standings.push(Standing { player, place });
+        }
+
+        self.payout_claims = 0;
+        self.creator_fee_due = (self.total_pot as u128 * self.options.creator_fee_bps as u128
+            / BASIS_POINTS as u128) as u64;
+        self.rebate_pool = self.move_rebate_pool();
+        self.payout_pot = self.total_pot - self.creator_fee_due - self.rebate_pool;
+        self.payout_token_pot = self.token_pot;
+    }
+
+    /// Actions taken by the players in the standings, who share the rebates
+    fn rebated_moves(&self) -> u64 {
+        self.standings
+            .iter()
+            .filter_map(|s| self.players.iter().find(|p| p.pubkey == s.player))
+            .map(|p| p.total_moves as u64)
+            .sum()
+    }
+
+    /// Gas rebates owed to the finishers: a fixed amount per action, capped
+    /// at a fraction of the SOL pot. Players who left early aren't in the
+    /// standings and get nothing.
+    pub fn move_rebate_pool(&self) -> u64 {
+        let cap =
+            (self.total_pot as u128 * MAX_REBATE_POOL_BPS as u128 / BASIS_POINTS as u128) as u64;
+        self.rebated_moves()
+            .saturating_mul(MOVE_REBATE_LAMPORTS)
+            .min(cap)
+    }
+
+    /// One finisher's part of the rebate pool, in proportion to their actions
+    pub fn move_rebate(&self, player: &Pubkey) -> u64 {
+        if !self.standings.iter().any(|s| s.player == *player) {
+            return 0;
+        }
+        let moves = self
+            .players
+            .iter()
+            .find(|p| p.pubkey == *player)
+            .map(|p| p.total_moves as u64)
+            .unwrap_or(0);
+        let total = self.rebated_moves();
+        if total == 0 {
+            return 0;
+        }
+        (self.rebate_pool as u128 * moves as u128 / total as u128) as u64
+    }
+
     pub fn get_current_player(&self) -> Option<&PlayerData> {
         self.players.get(self.current_player_index as usize)
     }
 
+    /// Whether `pubkey` holds a seat, including one that has since left or
+    /// been eliminated
+    pub fn has_seat(&self, pubkey: &Pubkey) -> bool {
+        self.players.iter().any(|p| p.pubkey == *pubkey)
+    }
+
+    /// Whether `pubkey` holds the current sequential turn
+    pub fn is_current_player(&self, pubkey: &Pubkey) -> bool {
+        self.get_current_player()
+            .map(|p| p.pubkey == *pubkey && p.is_active)
+            .unwrap_or(false)
+    }
+
     pub fn get_player(&self, pubkey: &Pubkey) -> Option<&PlayerData> {
         self.players
             .iter()
@@ -81,19 +939,274 @@ impl PirateGame {
             .iter_mut()
             .find(|p| p.pubkey == *pubkey && p.is_active)
     }
+
+    /// Check that `player`'s ship with `speed` may sail from `from` through
+    /// the map indices in `via` to `to`. Islands and enemy ships block
+    /// passage, and in ranked modes entering a cell next to an enemy ship
+    /// ends the move. Whether the destination is free is left to the caller.
+    pub fn check_route(
+        &self,
+        player: &Pubkey,
+        from: (u8, u8),
+        via: &[u8],
+        to: (u8, u8),
+        speed: u32,
+    ) -> Result<()> {
+        let metric = DistanceMetric::for_mode(&self.mode);
+        require!(
+            via.iter().all(|&i| (i as usize) < MAP_SIZE * MAP_SIZE),
+            GameError::InvalidCoordinate
+        );
+        let path: Vec<(u8, u8)> = via
+            .iter()
+            .map(|&i| index_to_coordinate(i as usize))
+            .collect();
+        let enemy_positions: Vec<(u8, u8)> = self
+            .players
+            .iter()
+            .filter(|p| p.pubkey != *player)
+            .flat_map(|p| p.ships.iter())
+            .filter(|s| s.is_alive())
+            .map(|s| (s.position_x, s.position_y))
+            .collect();
+        let blocked = |(x, y): (u8, u8)| {
+            let island = self
+                .territory_map
+                .get(x as usize * MAP_SIZE + y as usize)
+                .map(|cell| cell.cell_type == TerritoryCellType::Island)
+                .unwrap_or(false);
+            island || enemy_positions.contains(&(x, y))
+        };
+        let zone_of_control = self.mode.zone_of_control();
+        let controlled = |cell: (u8, u8)| {
+            zone_of_control
+                && enemy_positions
+                    .iter()
+                    .any(|&enemy| metric.between(cell, enemy) == 1)
+        };
+        check_path(
+            from,
+            &path,
+            to,
+            speed,
+            metric.allows_diagonals(),
+            blocked,
+            controlled,
+        )
+        .map_err(GameError::from)?;
+        Ok(())
+    }
+
+    /// Remove every ship that is no longer alive, emitting `ShipSunk` with its
+    /// wreck location. Returns how many ships were removed.
+    pub fn purge_sunk_ships(&mut self) -> usize {
+        let game_id = self.game_id;
+        let turn_number = self.turn_number;
+        let mut sunk = 0;
+
+        for player in self.players.iter_mut() {
+            let mut flagship_lost = None;
+            for ship in player.ships.iter().filter(|s| !s.is_alive()) {
+                emit!(ShipSunk {
+                    game_id,
+                    owner: player.pubkey,
+                    ship_id: ship.id.clone(),
+                    ship_type: ship.ship_type.clone(),
+                    wreck_x: ship.position_x,
+                    wreck_y: ship.position_y,
+                    turn_number,
+                });
+                if ship.cargo_gold > 0 {
+                    let index = ship.position_x as usize * MAP_SIZE + ship.position_y as usize;
+                    let salvageable = self
+                        .territory_map
+                        .get_mut(index)
+                        .map(|cell| spill_cargo(cell, ship.cargo_gold))
+                        .unwrap_or(0);
+                    emit!(CargoSpilled {
+                        game_id,
+                        owner: player.pubkey,
+                        ship_id: ship.id.clone(),
+                        gold: ship.cargo_gold,
+                        salvageable,
+                        x: ship.position_x,
+                        y: ship.position_y,
+                    });
+                }
+                if ship.is_designated_flagship {
+                    flagship_lost = Some(ship.id.clone());
+                }
+                sunk += 1;
+            }
+            player.ships.retain(|s| s.is_alive());
+
+            // Losing the flagship strikes the whole fleet's colors
+            if let Some(flagship_id) = flagship_lost.filter(|_| player.is_active) {
+                player.is_active = false;
+                player.role = PlayerRole::Spectator;
+                player.ships.clear();
+                emit!(PlayerEliminated {
+                    game_id,
+                    player: player.pubkey,
+                    flagship_id,
+                    turn_number,
+                });
+            }
+        }
+
+        sunk
+    }
+
+    /// Open the grace window on a resignation just made, where the config
+    /// allows withdrawing one. Call once the turn has moved on past them.
+    pub fn open_resignation_grace(&mut self, player: Pubkey, now: i64) {
+        if !self.resign_grace || self.status != GameStatus::Active {
+            return;
+        }
+        self.pending_resignation = Some(ResignationGrace {
+            player,
+            expires_at: now.saturating_add(RESIGN_GRACE_SECONDS),
+            turn_number: self.turn_number,
+            turn_slot: self.turn_slot,
+        });
+    }
+
+    /// Put a resigned player back in the game, if their grace window is still
+    /// open and no one has played on since. A turn the resignation passed on
+    /// stays passed.
+    pub fn withdraw_resignation(&mut self, player: &Pubkey, now: i64) -> Result<()> {
+        let grace = self
+            .pending_resignation
+            .filter(|g| g.player == *player)
+            .ok_or(GameError::NoResignationToCancel)?;
+        require!(
+            now <= grace.expires_at
+                && (self.turn_number, self.turn_slot) == (grace.turn_number, grace.turn_slot),
+            GameError::ResignationGraceOver
+        );
+
+        let seat = self
+            .players
+            .iter_mut()
+            .find(|p| p.pubkey == *player && p.departure == Departure::Resigned)
+            .ok_or(GameError::NoResignationToCancel)?;
+        seat.is_active = true;
+        seat.departure = Departure::Stayed;
+        seat.departed_turn = 0;
+        self.pending_resignation = None;
+        Ok(())
+    }
+
+    /// Award the game to the only active player left, if there is just one.
+    /// Tutorials only end at their last checkpoint.
+    pub fn complete_if_last_standing(&mut self, now: i64, victory: VictoryType) -> bool {
+        if self.options.objective == GameObjective::Tutorial {
+            return false;
+        }
+        let remaining: Vec<Pubkey> = self
+            .players
+            .iter()
+            .filter(|p| p.is_active)
+            .map(|p| p.pubkey)
+            .collect();
+        let [winner_pubkey] = remaining[..] else {
+            return false;
+        };
+
+        self.complete_with_winner(now, winner_pubkey, victory);
+        true
+    }
+
+    pub fn complete_with_winner(&mut self, now: i64, winner_pubkey: Pubkey, victory: VictoryType) {
+        self.status = GameStatus::Completed;
+        self.winner = Some(winner_pubkey);
+        self.completed_at = Some(now);
+        self.refresh_pot_eligibility();
+        self.record_standings(&[winner_pubkey]);
+        let final_state_hash = self.seal_final_state();
+
+        emit!(GameCompleted {
+            game_id: self.game_id,
+            winner: winner_pubkey,
+            victory,
+            victory_type: victory.to_string(),
+            scoring: self.scoring,
+            final_state_hash,
+        });
+    }
+
+    /// Hash the completed game's canonical state, for indexers and replays
+    /// to check they reconstructed the same ending. Call once the result and
+    /// standings are recorded.
+    pub fn seal_final_state(&mut self) -> [u8; 32] {
+        self.final_state_hash = state_hash(self);
+        self.final_state_hash
+    }
+
+    /// Whether the lobby still takes players: waiting, and not yet started
+    pub fn is_joinable(&self) -> bool {
+        self.status == GameStatus::Waiting && self.start_reveal_slot.is_none()
+    }
+
+    /// Close the lobby and name the future slot whose hash will seed the
+    /// game; `reveal_seed` begins it once that slot has passed
+    pub fn commit_start(&mut self, slot: u64) {
+        let reveal_slot = slot + REVEAL_DELAY_SLOTS;
+        self.start_reveal_slot = Some(reveal_slot);
+        emit!(StartCommitted {
+            game_id: self.game_id,
+            reveal_slot,
+        });
+    }
+
+    /// Copy the balance values to play under, from the mode's rulebook when
+    /// one is passed and the built-in rules otherwise. Called once, at start.
+    pub fn adopt_rulebook(&mut self, rulebook: Option<&Rulebook>) {
+        (self.rulebook_version, self.rules) = match rulebook {
+            Some(rulebook) => (rulebook.version, rulebook.params.clone()),
+            None => (0, RulebookParams::default()),
+        };
+    }
+}
+
+/// Move a filled lobby into play: generate the map, deploy fleets and
+/// fix the seat order, all derived from `seed`
+pub fn begin_game(game: &mut PirateGame, seed: u64, now: i64, slot: u64) -> Result<()> {
+    game.seed = seed;
+    game.start_reveal_slot = None;
+    game.rng_audit = RngAudit {
+        seed_slot: slot,
+        ..RngAudit::default()
+    };
+    game.rng = RngStream::new(seed);
+
+    // Generate map
+    game.territory_map = generate_strategic_map(seed);
+    if let GameObjective::KingOfTheHill { .. } = game.options.objective {
+        place_golden_port(&mut game.territory_map);
+    }
+
+    // Deploy starting fleets
+    deploy_starting_fleets(game, now)?;
+
+    // Randomize seat order so the first-mover advantage isn't fixed by join order
+    game.turn_order = shuffled_indices(game.player_count, seed);
+    game.turn_slot = 0;
+    game.turn_number = 1;
+    game.current_player_index = game.seat_for_slot(0);
+    game.turn_started_at = now;
+
+    game.status = GameStatus::Active;
+    game.started_at = Some(now);
+
+    Ok(())
 }
 
-pub fn deploy_starting_fleets(game: &mut PirateGame) -> Result<()> {
-    let starting_positions = [
-        (1, 1),
-        (2, 1), // Player 0: top-left
-        (8, 1),
-        (9, 1), // Player 1: top-right
-        (1, 8),
-        (1, 9), // Player 2: bottom-left
-        (8, 8),
-        (9, 8), // Player 3: bottom-right
-    ];
+/// Place each seat's starting fleet on the open Water cells nearest its
+/// preferred spawns, never on land, a hazard or another ship
+pub fn deploy_starting_fleets(game: &mut PirateGame, timestamp: i64) -> Result<()> {
+    game.spawn_positions.clear();
+    let mut occupied: Vec<(u8, u8)> = Vec::new();
 
     for i in 0..game.player_count as usize {
         if i >= game.players.len() {
@@ -108,44 +1221,153 @@ pub fn deploy_starting_fleets(game: &mut PirateGame) -> Result<()> {
         let player_pubkey = game.players[i].pubkey;
 
         // Ensure we don't exceed starting positions array
-        let base_idx = i.saturating_mul(2);
-        require!(base_idx + 1 < starting_positions.len(), GameError::GameFull);
-
-        let pos1 = starting_positions[base_idx];
-        let pos2 = starting_positions[base_idx + 1];
-
-        // Create starting ships
-        let timestamp = Clock::get()?.unix_timestamp;
-
-        let sloop = ShipData {
-            id: format!("{}_{}", player_pubkey, timestamp),
-            ship_type: ShipType::Sloop,
-            health: 100,
-            max_health: 100,
-            attack: 20,
-            defense: 10,
-            speed: 3,
-            position_x: pos1.0,
-            position_y: pos1.1,
-            last_action_turn: 0,
-        };
+        let preferred = *SPAWN_POSITIONS.get(i).ok_or(GameError::GameFull)?;
 
-        let frigate = ShipData {
-            id: format!("{}_{}_{}", player_pubkey, timestamp, "frigate"),
-            ship_type: ShipType::Frigate,
-            health: 200,
-            max_health: 200,
-            attack: 40,
-            defense: 25,
-            speed: 2,
-            position_x: pos2.0,
-            position_y: pos2.1,
-            last_action_turn: 0,
+        // Extra ships beyond the two preferred cells spread out from the second one
+        let fleet = game.options.loadout.fleet.clone();
+        let flagship_slot = match game.options.objective {
+            GameObjective::CaptureTheFlagship => Some(designated_flagship_slot(&fleet)),
+            _ => None,
         };
+        if let GameObjective::TreasureRace { .. } = game.options.objective {
+            let port =
+                home_port(&game.territory_map, preferred[0]).ok_or(GameError::NoValidSpawn)?;
+            game.players[i].home_port = Some(port);
+        }
+        for (slot, ship_type) in fleet.iter().enumerate() {
+            let from = preferred[slot.min(preferred.len() - 1)];
+            let pos = nearest_open_water(&game.territory_map, from, &occupied)
+                .ok_or(GameError::NoValidSpawn)?;
+            occupied.push(pos);
+            game.spawn_positions.push(SpawnPosition {
+                player_index: i as u8,
+                x: pos.0,
+                y: pos.1,
+            });
 
-        game.players[i].ships.push(sloop);
-        game.players[i].ships.push(frigate);
+            let (health, attack, defense, speed) = game.rules.ship_stats(ship_type);
+            game.players[i].ships.push(ShipData {
+                id: format!("{}_{}_{}", player_pubkey, timestamp, slot),
+                ship_type: ship_type.clone(),
+                health,
+                max_health: health,
+                attack,
+                defense,
+                speed,
+                position_x: pos.0,
+                position_y: pos.1,
+                last_action_turn: 0,
+                is_designated_flagship: flagship_slot == Some(slot),
+                cargo_gold: 0,
+                skin_mint: None,
+            });
+        }
     }
 
     Ok(())
 }
+
+/// Loadout slot carrying a capture-the-flagship game's flag: the first
+/// Flagship in the fleet, or the lead ship if it has none
+pub fn designated_flagship_slot(fleet: &[ShipType]) -> usize {
+    fleet
+        .iter()
+        .position(|ship_type| *ship_type == ShipType::Flagship)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GameOptions, GameStatus};
+    use crate::state::jackpot::JackpotCommit;
+    use crate::state::liveness::LIVENESS_DEPOSIT_LAMPORTS;
+    use crate::state::payout::PayoutSchedule;
+    use crate::test_utils::waiting_game;
+    use anchor_lang::prelude::Pubkey;
+
+    #[test]
+    fn games_holding_a_liveness_deposit_are_not_settled() {
+        let mut game = waiting_game(2);
+        for player in game.players.iter_mut() {
+            player.profile_settled = true;
+        }
+        assert!(game.is_fully_settled());
+
+        // claim_all_payouts only closes a settled game, so the deposit keeps
+        // the account open until a crank returns or forfeits it
+        game.players[1].liveness_deposit = LIVENESS_DEPOSIT_LAMPORTS;
+        assert!(!game.is_fully_settled());
+        game.players[1].liveness_deposit = 0;
+        assert!(game.is_fully_settled());
+    }
+
+    #[test]
+    fn games_waiting_on_a_jackpot_roll_are_not_settled() {
+        let mut game = waiting_game(2);
+        for player in game.players.iter_mut() {
+            player.profile_settled = true;
+        }
+        game.pending_jackpot = Some(JackpotCommit {
+            winner: game.players[0].pubkey,
+            recipient: Pubkey::new_unique(),
+            odds_bps: 100,
+            reveal_slot: 10,
+        });
+        assert!(!game.is_fully_settled());
+        game.pending_jackpot = None;
+        assert!(game.is_fully_settled());
+    }
+
+    #[test]
+    fn deterministic_games_never_roll_for_the_jackpot() {
+        let mut options = GameOptions {
+            jackpot_eligible: true,
+            ..GameOptions::default()
+        };
+        assert!(options.rolls_for_jackpot());
+        options.deterministic = true;
+        assert!(!options.rolls_for_jackpot());
+    }
+
+    #[test]
+    fn only_empty_lobbies_and_settled_games_can_be_reset() {
+        let mut game = waiting_game(0);
+        assert!(game.is_resettable());
+
+        let mut game_with_seats = waiting_game(2);
+        assert!(!game_with_seats.is_resettable());
+        game_with_seats.status = GameStatus::Active;
+        assert!(!game_with_seats.is_resettable());
+
+        // A finished game keeps its standings until every winner has claimed
+        game = waiting_game(2);
+        game.status = GameStatus::Completed;
+        game.total_pot = 1_000;
+        for player in game.players.iter_mut() {
+            player.pot_eligible = true;
+            player.profile_settled = true;
+        }
+        let winner = game.players[0].pubkey;
+        game.record_standings(&[winner]);
+        assert!(!game.is_resettable());
+        game.payout_claims = 1;
+        assert!(game.is_resettable());
+    }
+
+    #[test]
+    fn the_creator_fee_comes_off_the_top_of_the_pot() {
+        let mut game = waiting_game(2);
+        game.options.creator_fee_bps = 200;
+        game.options.payout_schedule = PayoutSchedule([7_000, 3_000, 0, 0]);
+        game.total_pot = 1_000_000;
+        for player in game.players.iter_mut() {
+            player.pot_eligible = true;
+        }
+        let winner = game.players[0].pubkey;
+        game.record_standings(&[winner]);
+
+        assert_eq!(game.creator_fee_due, 20_000);
+        // Places divide what is left
+        assert_eq!(game.payout_pot, 980_000);
+    }
+}
@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+pub const INVITE_SEED: &[u8] = b"invite";
+
+// ============================================================================
+// INVITE INBOX
+// ============================================================================
+//
+// An invite is a PDA keyed by the invited wallet and the game, so a client
+// can list a wallet's pending invitations with a single filtered account
+// query. The sender pays the rent and gets it back when the invite is
+// accepted or declined.
+
+#[account]
+pub struct Invite {
+    pub target: Pubkey,
+    pub sender: Pubkey,
+    pub game_id: u64,
+    pub sent_at: i64,
+    pub bump: u8,
+}
+
+impl Invite {
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 8 + 1;
+}
@@ -0,0 +1,118 @@
+use crate::constants::BASIS_POINTS;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+pub const JACKPOT_SEED: &[u8] = b"jackpot";
+
+// ============================================================================
+// PLATFORM FEE & PROGRESSIVE JACKPOT
+// ============================================================================
+//
+// Every SOL pot pays a platform fee when the winner claims it. A slice of
+// that fee feeds a single global jackpot, and winners of jackpot-eligible
+// games roll for it with odds that grow with the size of their pot.
+//
+// The roll is not made in the claim. The claim commits it to a slot a few
+// ahead, whose hash nobody knows yet, and anyone may `reveal_jackpot_roll`
+// once that slot has passed, so a claimant cannot wrap the claim and revert
+// until the roll comes up. A roll left unrevealed until its slot ages out of
+// the SlotHashes window lapses, so holding it back never helps either.
+
+/// Platform fee taken from a SOL pot at claim time (5%)
+pub const PLATFORM_FEE_BPS: u32 = 500;
+
+/// Share of the platform fee routed into the jackpot (20% of the fee)
+pub const JACKPOT_FEE_SHARE_BPS: u32 = 2_000;
+
+/// Jackpot win chance per whole SOL in the pot (1% per SOL)
+pub const JACKPOT_ODDS_BPS_PER_SOL: u64 = 100;
+
+/// Ceiling on the jackpot win chance (10%)
+pub const JACKPOT_MAX_ODDS_BPS: u32 = 1_000;
+
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+#[account]
+pub struct Jackpot {
+    pub balance: u64, // Lamports held above rent exemption
+    pub last_winner: Option<Pubkey>,
+    pub last_won_amount: u64,
+    pub bump: u8,
+}
+
+impl Jackpot {
+    pub const SPACE: usize = 8 + 8 + (1 + 32) + 8 + 1;
+}
+
+/// How a claimed pot divides between the winner, the platform and the jackpot
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PotSplit {
+    pub payout: u64,
+    pub platform_fee: u64,
+    pub jackpot_contribution: u64,
+}
+
+/// `bps` basis points of a lamport amount, rounded down
+fn lamports_bps(amount: u64, bps: u32) -> u64 {
+    (amount as u128 * bps as u128 / BASIS_POINTS as u128) as u64
+}
+
+pub fn split_pot(pot: u64) -> PotSplit {
+    let fee = lamports_bps(pot, PLATFORM_FEE_BPS);
+    let jackpot_contribution = lamports_bps(fee, JACKPOT_FEE_SHARE_BPS);
+    PotSplit {
+        payout: pot - fee,
+        platform_fee: fee - jackpot_contribution,
+        jackpot_contribution,
+    }
+}
+
+/// Win chance for a pot, in basis points
+pub fn jackpot_odds_bps(pot: u64) -> u32 {
+    let odds = pot as u128 * JACKPOT_ODDS_BPS_PER_SOL as u128 / LAMPORTS_PER_SOL as u128;
+    odds.min(JACKPOT_MAX_ODDS_BPS as u128) as u32
+}
+
+/// First place's jackpot roll, waiting on the hash of `reveal_slot`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct JackpotCommit {
+    pub winner: Pubkey,
+    pub recipient: Pubkey, // Where the claim sent the payout; winnings follow it
+    pub odds_bps: u32,
+    pub reveal_slot: u64,
+}
+
+/// Roll in `0..BASIS_POINTS` from a committed slot hash, bound to the game
+/// and winner. A validator could still bias it by skipping its slot, which
+/// the capped odds keep unprofitable for today's pot sizes.
+pub fn jackpot_roll(slot_hash: &[u8; 32], game_id: u64, seed: u64, winner: &Pubkey) -> u64 {
+    let digest = hashv(&[
+        slot_hash,
+        &game_id.to_le_bytes(),
+        &seed.to_le_bytes(),
+        winner.as_ref(),
+    ]);
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest.to_bytes()[..8]);
+    u64::from_le_bytes(bytes) % BASIS_POINTS as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rolls_are_fixed_by_the_committed_hash() {
+        let winner = Pubkey::new_unique();
+        let roll = jackpot_roll(&[7; 32], 1, 42, &winner);
+        assert!(roll < BASIS_POINTS as u64);
+        // Claiming again in another slot cannot change the outcome
+        assert_eq!(roll, jackpot_roll(&[7; 32], 1, 42, &winner));
+
+        let rolls: Vec<u64> = (0..16u8)
+            .map(|fill| jackpot_roll(&[fill; 32], 1, 42, &winner))
+            .collect();
+        assert!(rolls.iter().any(|r| *r != roll));
+        assert_ne!(roll, jackpot_roll(&[7; 32], 1, 42, &Pubkey::new_unique()));
+    }
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+
+pub const DECISION_LEADERBOARD_SEED: &[u8] = b"decision_leaderboard";
+
+// ============================================================================
+// DECISION-TIME LEADERBOARD
+// ============================================================================
+//
+// One global board of the winners with the fastest average decision time.
+// Times are the time bank's own measurements: the gap between a turn opening
+// on chain and the seat's instruction landing, in the cluster's clock, so a
+// client reporting a flattering decision_time_ms has no effect. Once a game
+// is completed, anyone may submit each winning seat; a seat is ranked at most
+// once, and a player holds one row, their best game.
+
+/// Rows the board keeps
+pub const LEADERBOARD_SIZE: usize = 10;
+
+/// Timed decisions a winner needs before their average is ranked, so a
+/// one-move win cannot top the board
+pub const MIN_RANKED_DECISIONS: u16 = 10;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct LeaderboardEntry {
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub average_decision_ms: u64,
+    pub decisions: u16,
+}
+
+impl LeaderboardEntry {
+    pub const SPACE: usize = 32 + 8 + 8 + 2;
+}
+
+#[account]
+pub struct DecisionLeaderboard {
+    pub entries: Vec<LeaderboardEntry>, // Fastest first
+    pub bump: u8,
+}
+
+impl DecisionLeaderboard {
+    pub const SPACE: usize = 8 + 4 + LEADERBOARD_SIZE * LeaderboardEntry::SPACE + 1;
+
+    /// Offer an entry to the board. Returns its 0-based rank if it made the
+    /// board, or None if it was too slow or the player already holds a
+    /// faster row. Ties keep the earlier entry ahead.
+    pub fn submit(&mut self, entry: LeaderboardEntry) -> Option<usize> {
+        if let Some(index) = self.entries.iter().position(|e| e.player == entry.player) {
+            if self.entries[index].average_decision_ms <= entry.average_decision_ms {
+                return None;
+            }
+            self.entries.remove(index);
+        }
+
+        let rank = self
+            .entries
+            .iter()
+            .position(|e| e.average_decision_ms > entry.average_decision_ms)
+            .unwrap_or(self.entries.len());
+        if rank >= LEADERBOARD_SIZE {
+            return None;
+        }
+        self.entries.insert(rank, entry);
+        self.entries.truncate(LEADERBOARD_SIZE);
+        Some(rank)
+    }
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+// ============================================================================
+// VAULT LEDGER
+// ============================================================================
+//
+// The game account is the SOL vault: everything it holds above rent
+// exemption is pot, plus any stake raise and liveness deposits held in
+// escrow. Every instruction that moves lamports in or out of it records the
+// movement here, so the balance can be reconciled against the ledger at any
+// time with `reconcile_vault`. Money in is entry fees, raise escrow, liveness
+// deposits, slashed agent bonds and unspent fee-session budgets coming back.
+// Money out is either paid out (prizes, move rebates, raise refunds, settled
+// liveness deposits, fee-session budgets) or taken as fees (platform fee,
+// jackpot contribution and creator fee).
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct VaultLedger {
+    pub total_deposited: u64,
+    pub total_paid_out: u64,
+    pub fees_accrued: u64,
+}
+
+impl VaultLedger {
+    pub fn deposit(&mut self, lamports: u64) {
+        self.total_deposited = self.total_deposited.saturating_add(lamports);
+    }
+
+    pub fn pay_out(&mut self, lamports: u64) {
+        self.total_paid_out = self.total_paid_out.saturating_add(lamports);
+    }
+
+    pub fn accrue_fee(&mut self, lamports: u64) {
+        self.fees_accrued = self.fees_accrued.saturating_add(lamports);
+    }
+
+    /// Lamports the vault should hold above rent exemption, or None if more
+    /// has left it than ever came in
+    pub fn balance(&self) -> Option<u64> {
+        self.total_deposited
+            .checked_sub(self.total_paid_out)?
+            .checked_sub(self.fees_accrued)
+    }
+}
@@ -0,0 +1,82 @@
+use crate::constants::SOL_ENTRY_FEE_LAMPORTS;
+use crate::state::game::GameMode;
+use anchor_lang::prelude::*;
+
+pub const LFG_BOARD_SEED: &[u8] = b"lfg_board";
+
+// ============================================================================
+// LOOKING-FOR-GAME BOARD
+// ============================================================================
+//
+// A single board where players post what game they want: a mode, a stake
+// level and the band of opponent ratings they will face, until an expiry.
+// Nothing is escrowed. A player holding an entry can pair it with any
+// compatible one through `match_from_lfg`, which takes both entries off the
+// board and creates a two-seat lobby invited to just those two, staked at
+// the agreed level; each then joins it as usual. Expired entries are swept
+// whenever the board is written.
+
+/// Entries the board holds at once
+pub const MAX_LFG_ENTRIES: usize = 16;
+
+/// Longest an entry may stay up
+pub const MAX_LFG_EXPIRY_SECONDS: i64 = 24 * 60 * 60;
+
+/// Highest stake level: the SOL entry fee doubled this many times
+pub const MAX_LFG_STAKE_LEVEL: u8 = 2;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct LfgEntry {
+    pub player: Pubkey,
+    pub rating: u32, // The poster's rating when they posted
+    pub min_rating: u32,
+    pub max_rating: u32,
+    pub mode: GameMode,
+    pub stake_level: u8,
+    pub expires_at: i64,
+}
+
+impl LfgEntry {
+    pub const SPACE: usize = 32 + 4 + 4 + 4 + 1 + 1 + 8;
+
+    /// Entry fee each seat pays at this entry's stake level
+    pub fn stake_lamports(&self) -> u64 {
+        SOL_ENTRY_FEE_LAMPORTS << self.stake_level
+    }
+
+    pub fn is_live(&self, now: i64) -> bool {
+        now < self.expires_at
+    }
+
+    /// Same game wanted, and each player inside the other's band
+    pub fn matches(&self, other: &LfgEntry) -> bool {
+        self.player != other.player
+            && self.mode == other.mode
+            && self.stake_level == other.stake_level
+            && (self.min_rating..=self.max_rating).contains(&other.rating)
+            && (other.min_rating..=other.max_rating).contains(&self.rating)
+    }
+}
+
+#[account]
+pub struct LfgBoard {
+    pub entries: Vec<LfgEntry>,
+    pub bump: u8,
+}
+
+impl LfgBoard {
+    pub const SPACE: usize = 8 + 4 + MAX_LFG_ENTRIES * LfgEntry::SPACE + 1;
+
+    pub fn sweep_expired(&mut self, now: i64) {
+        self.entries.retain(|e| e.is_live(now));
+    }
+
+    /// Take `player`'s live entry off the board
+    pub fn take(&mut self, player: &Pubkey, now: i64) -> Option<LfgEntry> {
+        let index = self
+            .entries
+            .iter()
+            .position(|e| e.player == *player && e.is_live(now))?;
+        Some(self.entries.remove(index))
+    }
+}
@@ -0,0 +1,111 @@
+use crate::errors::GameError;
+use crate::state::game::{GameStatus, PirateGame};
+use crate::state::player::{Departure, PlayerData};
+use anchor_lang::prelude::*;
+
+pub const CRANK_FUND_SEED: &[u8] = b"crank_fund";
+
+// ============================================================================
+// LIVENESS DEPOSITS
+// ============================================================================
+//
+// Every join holds a small deposit in the game vault, apart from the pot and
+// whatever the entry fee is, so joining a lobby and walking away costs
+// something even in a free game. Once the game is completed a crank settles
+// each seat: players who saw the game out, timing out no more than twice,
+// get their deposit back; the rest forfeit it to the program-wide CrankFund,
+// which pays the tip for every settlement it can afford. A sequential
+// timeout forfeits the seat outright; in simultaneous games each missed
+// reveal counts as a timeout. A refund goes to whichever wallet paid the
+// deposit, which for a delegate join is the session key, not the owner.
+
+/// Deposit held per seat (0.001 SOL)
+pub const LIVENESS_DEPOSIT_LAMPORTS: u64 = 1_000_000;
+
+/// Timeouts a seat may run up and still have its deposit back
+pub const MAX_FORGIVEN_TIMEOUTS: u8 = 2;
+
+/// Paid from the CrankFund to whoever settles a deposit
+pub const CRANK_TIP_LAMPORTS: u64 = 5_000;
+
+/// Whether a seat has earned its deposit back: it was there at the end, and
+/// timed out no more than MAX_FORGIVEN_TIMEOUTS times
+pub fn liveness_refundable(player: &PlayerData) -> bool {
+    player.departure == Departure::Stayed && player.timeouts <= MAX_FORGIVEN_TIMEOUTS
+}
+
+/// Clear `player`'s deposit from a completed game for settlement, returning
+/// the amount and whether it is refunded. `wallet` must be the seat's payer.
+pub fn take_liveness_deposit(
+    game: &mut PirateGame,
+    player: &Pubkey,
+    wallet: &Pubkey,
+) -> Result<(u64, bool)> {
+    require!(
+        game.status == GameStatus::Completed,
+        GameError::GameNotCompleted
+    );
+    let seat = game
+        .get_player_mut(player)
+        .ok_or(GameError::PlayerNotInGame)?;
+    let amount = seat.liveness_deposit;
+    require!(amount > 0, GameError::NoLivenessDeposit);
+    require!(
+        *wallet == seat.liveness_payer,
+        GameError::LivenessPayerMismatch
+    );
+    let refunded = liveness_refundable(seat);
+    seat.liveness_deposit = 0;
+
+    game.ledger.pay_out(amount);
+    Ok((amount, refunded))
+}
+
+#[account]
+pub struct CrankFund {
+    pub forfeited_total: u64, // Deposits forfeited into the fund, ever
+    pub tips_paid: u64,       // Lamports paid out as crank tips, ever
+    pub bump: u8,
+}
+
+impl CrankFund {
+    pub const SPACE: usize = 8 + 8 + 8 + 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::GameBuilder;
+
+    #[test]
+    fn deposits_go_back_to_the_wallet_that_paid_them() {
+        let mut game = GameBuilder::new(2).build();
+        let owner = game.players[0].pubkey;
+        let session_key = Pubkey::new_unique();
+        game.players[0].liveness_deposit = LIVENESS_DEPOSIT_LAMPORTS;
+        game.players[0].liveness_payer = session_key;
+        game.ledger.deposit(LIVENESS_DEPOSIT_LAMPORTS);
+
+        assert_eq!(
+            take_liveness_deposit(&mut game, &owner, &session_key),
+            Err(GameError::GameNotCompleted.into())
+        );
+        game.status = GameStatus::Completed;
+
+        // A delegate join is refunded to the session key, not the owner
+        assert_eq!(
+            take_liveness_deposit(&mut game, &owner, &owner),
+            Err(GameError::LivenessPayerMismatch.into())
+        );
+        assert_eq!(
+            take_liveness_deposit(&mut game, &owner, &session_key),
+            Ok((LIVENESS_DEPOSIT_LAMPORTS, true))
+        );
+        assert_eq!(game.players[0].liveness_deposit, 0);
+        assert_eq!(game.ledger.balance(), Some(0));
+        assert_eq!(
+            take_liveness_deposit(&mut game, &owner, &session_key),
+            Err(GameError::NoLivenessDeposit.into())
+        );
+    }
+}
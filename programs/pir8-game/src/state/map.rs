@@ -1,8 +1,12 @@
 use crate::constants::*;
 use crate::errors::GameError;
+use crate::state::distance::{manhattan, squared_euclidean};
 use crate::state::player::{PlayerData, Resources};
+use crate::state::rng::splitmix64;
+use crate::state::rulebook::RulebookParams;
 use anchor_lang::prelude::*;
 
+/// Events and exports carry the discriminant, so variants are append-only
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
 pub enum TerritoryCellType {
     Water,
@@ -12,44 +16,102 @@ pub enum TerritoryCellType {
     Storm,
     Reef,
     Whirlpool,
+    /// King-of-the-hill objective cell at the center of the map
+    GoldenPort,
+    /// Cargo spilled from a sunk treasure-race ship, salvageable until empty
+    Flotsam,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
 pub struct TerritoryCell {
     pub cell_type: TerritoryCellType,
     pub owner: Option<Pubkey>,
+    pub remaining_yield: u16, // Gold left in a Treasure or Flotsam cell; zero for every other type
+    pub blockaded_until_turn: u16, // Last round a Port is blockaded (no income or construction)
 }
 
+impl TerritoryCellType {
+    pub fn is_land(&self) -> bool {
+        matches!(
+            self,
+            TerritoryCellType::Island
+                | TerritoryCellType::Port
+                | TerritoryCellType::Treasure
+                | TerritoryCellType::GoldenPort
+        )
+    }
+}
+
+impl TerritoryCell {
+    pub fn is_blockaded(&self, turn_number: u32) -> bool {
+        self.cell_type == TerritoryCellType::Port && self.blockaded_until_turn as u32 >= turn_number
+    }
+}
+
+/// Preferred starting cells for each seat's two ships, one seat per map quadrant
+pub const SPAWN_POSITIONS: [[(u8, u8); 2]; MAX_PLAYERS as usize] = [
+    [(1, 1), (2, 1)], // Player 0: top-left
+    [(8, 1), (9, 1)], // Player 1: top-right
+    [(1, 8), (1, 9)], // Player 2: bottom-left
+    [(8, 8), (9, 8)], // Player 3: bottom-right
+];
+
+/// King-of-the-hill objective cell, the map cell nearest the center
+pub const GOLDEN_PORT_POSITION: (u8, u8) = ((MAP_SIZE as u8 - 1) / 2, (MAP_SIZE as u8 - 1) / 2);
+
+/// Cell type reported for coordinates off the map
+pub const UNKNOWN_CELL_TYPE: u8 = u8::MAX;
+
+/// Every spawn must have a Port and a resource cell within this many tiles
+pub const SPAWN_FAIRNESS_RADIUS: u8 = 3;
+
+/// Gold a fresh Treasure cell holds before it is exhausted
+pub const TREASURE_GOLD_POOL: u16 = 100;
+/// Gold restored to each unexhausted Treasure cell at the end of every round
+pub const TREASURE_REGEN_PER_ROUND: u16 = 2;
+
 // ============================================================================
 // HELPERS
 // ============================================================================
 
-pub fn get_territory_resources(x: u8, y: u8, territory_map: &Vec<TerritoryCell>) -> Resources {
+pub fn get_territory_resources(
+    x: u8,
+    y: u8,
+    territory_map: &[TerritoryCell],
+    rules: &RulebookParams,
+) -> Resources {
     let index = (x as usize * MAP_SIZE) + y as usize;
-    if let Some(cell) = territory_map.get(index) {
-        return match cell.cell_type {
-            TerritoryCellType::Island => Resources {
-                gold: 0,
-                crew: 0,
-                cannons: 0,
-                supplies: 3,
-            },
-            TerritoryCellType::Port => Resources {
-                gold: 5,
-                crew: 2,
-                cannons: 0,
-                supplies: 0,
-            },
-            TerritoryCellType::Treasure => Resources {
-                gold: 10,
-                crew: 0,
-                cannons: 0,
-                supplies: 0,
-            },
-            _ => Resources::default(),
-        };
-    }
-    Resources::default()
+    territory_map
+        .get(index)
+        .map(|cell| rules.cell_income(cell.cell_type))
+        .unwrap_or_default()
+}
+
+/// Closest Water cell to `from` that no ship in `occupied` holds. Ties are
+/// broken by (x, y) so the search is deterministic.
+pub fn nearest_open_water(
+    territory_map: &[TerritoryCell],
+    from: (u8, u8),
+    occupied: &[(u8, u8)],
+) -> Option<(u8, u8)> {
+    (0..territory_map.len())
+        .map(index_to_coordinate)
+        .filter(|&(x, y)| {
+            territory_map[x as usize * MAP_SIZE + y as usize].cell_type == TerritoryCellType::Water
+                && !occupied.contains(&(x, y))
+        })
+        .min_by_key(|&(x, y)| (manhattan((x, y), from), x, y))
+}
+
+/// Convert a flattened map index back into (x, y)
+pub fn index_to_coordinate(index: usize) -> (u8, u8) {
+    ((index / MAP_SIZE) as u8, (index % MAP_SIZE) as u8)
+}
+
+/// Which ring of the map a cell sits on, counting in from the edge (0)
+pub fn ring_of((x, y): (u8, u8)) -> u8 {
+    let far = MAP_SIZE as u8 - 1;
+    x.min(y).min(far - x).min(far - y)
 }
 
 pub fn has_adjacent_controlled_port(player: &PlayerData, x: u8, y: u8) -> bool {
@@ -82,20 +144,21 @@ pub fn has_adjacent_controlled_port(player: &PlayerData, x: u8, y: u8) -> bool {
 pub fn generate_strategic_map(seed: u64) -> Vec<TerritoryCell> {
     let mut map: Vec<TerritoryCell> = Vec::with_capacity(MAP_SIZE * MAP_SIZE);
 
-    // Dynamic center calculation based on MAP_SIZE
-    let center = (MAP_SIZE as f32 - 1.0) / 2.0;
-    // Scale zones based on map size (baseline 5x5)
-    let scale = MAP_SIZE as f32 / 5.0;
+    // Work in doubled coordinates so the map center stays integral for any MAP_SIZE
+    let center = (MAP_SIZE as u8 - 1, MAP_SIZE as u8 - 1);
+    // Zone radii scale with map size (baseline 5x5): inner 1.5 * scale, mid 2.5 * scale,
+    // compared as squared doubled distances: (2r)^2 = (3M/5)^2 and (M)^2
+    let inner_radius_sq_x25 = 9 * (MAP_SIZE * MAP_SIZE) as u32;
+    let mid_radius_sq = (MAP_SIZE * MAP_SIZE) as u32;
 
     // Generate strategic layout
     for x in 0..MAP_SIZE {
         for y in 0..MAP_SIZE {
-            let distance_from_center =
-                ((x as f32 - center).powi(2) + (y as f32 - center).powi(2)).sqrt();
+            let doubled_distance_sq = squared_euclidean((2 * x as u8, 2 * y as u8), center);
             let cell_seed = seed.wrapping_add((x * MAP_SIZE + y) as u64);
-            let rand_val = (cell_seed * 1103515245 + 12345) % 100;
+            let rand_val = cell_seed.wrapping_mul(1103515245).wrapping_add(12345) % 100;
 
-            let cell_type = if distance_from_center < (1.5 * scale) {
+            let cell_type = if doubled_distance_sq * 25 < inner_radius_sq_x25 {
                 // Center - valuable territories
                 if rand_val < 40 {
                     TerritoryCellType::Treasure
@@ -104,7 +167,7 @@ pub fn generate_strategic_map(seed: u64) -> Vec<TerritoryCell> {
                 } else {
                     TerritoryCellType::Water
                 }
-            } else if distance_from_center < (2.5 * scale) {
+            } else if doubled_distance_sq < mid_radius_sq {
                 // Mid area - mixed
                 if rand_val < 20 {
                     TerritoryCellType::Island
@@ -127,13 +190,119 @@ pub fn generate_strategic_map(seed: u64) -> Vec<TerritoryCell> {
             map.push(TerritoryCell {
                 cell_type,
                 owner: None,
+                remaining_yield: 0,
+                blockaded_until_turn: 0,
             });
         }
     }
 
+    enforce_spawn_fairness(&mut map, seed);
+
+    for cell in map.iter_mut() {
+        if cell.cell_type == TerritoryCellType::Treasure {
+            cell.remaining_yield = TREASURE_GOLD_POOL;
+        }
+    }
+
     map
 }
 
+/// Raise the Golden Port at the center of the map, replacing whatever was there
+pub fn place_golden_port(territory_map: &mut [TerritoryCell]) {
+    let (x, y) = GOLDEN_PORT_POSITION;
+    territory_map[x as usize * MAP_SIZE + y as usize] = TerritoryCell {
+        cell_type: TerritoryCellType::GoldenPort,
+        owner: None,
+        remaining_yield: 0,
+        blockaded_until_turn: 0,
+    };
+}
+
+/// Slowly refill every Treasure cell that hasn't been exhausted yet
+pub fn regenerate_treasure(territory_map: &mut [TerritoryCell]) {
+    for cell in territory_map.iter_mut() {
+        // Spilled cargo can push a pool past the cap; it only refills below it
+        if cell.cell_type == TerritoryCellType::Treasure
+            && cell.remaining_yield < TREASURE_GOLD_POOL
+        {
+            cell.remaining_yield = cell
+                .remaining_yield
+                .saturating_add(TREASURE_REGEN_PER_ROUND)
+                .min(TREASURE_GOLD_POOL);
+        }
+    }
+}
+
+/// Take up to `gold` from the cell's pool and return how much was actually
+/// mined. Non-Treasure cells are unlimited. A Treasure cell mined dry
+/// becomes a plain Island.
+pub fn mine_treasure(cell: &mut TerritoryCell, gold: u32) -> u32 {
+    if cell.cell_type != TerritoryCellType::Treasure {
+        return gold;
+    }
+
+    let mined = gold.min(cell.remaining_yield as u32);
+    cell.remaining_yield -= mined as u16;
+    if cell.remaining_yield == 0 {
+        cell.cell_type = TerritoryCellType::Island;
+    }
+    mined
+}
+
+/// Patch the generated map so every quadrant starts on equal footing: spawn
+/// cells are open Water, and a Port plus a resource cell (Island or Treasure)
+/// lie within `SPAWN_FAIRNESS_RADIUS` of the spawn inside the same quadrant.
+/// Missing cells are placed at a seed-derived candidate, so a seed always
+/// produces the same map.
+fn enforce_spawn_fairness(map: &mut [TerritoryCell], seed: u64) {
+    let half = (MAP_SIZE / 2) as u8;
+
+    for (quadrant, spawns) in SPAWN_POSITIONS.iter().enumerate() {
+        for &(x, y) in spawns.iter() {
+            map[x as usize * MAP_SIZE + y as usize].cell_type = TerritoryCellType::Water;
+        }
+
+        let (sx, sy) = spawns[0];
+        let nearby: Vec<usize> = (0..MAP_SIZE * MAP_SIZE)
+            .filter(|&index| {
+                let (x, y) = index_to_coordinate(index);
+                let same_quadrant = (x < half) == (sx < half) && (y < half) == (sy < half);
+                let distance = manhattan((x, y), (sx, sy));
+                same_quadrant
+                    && distance <= SPAWN_FAIRNESS_RADIUS as u32
+                    && !spawns.contains(&(x, y))
+            })
+            .collect();
+
+        let mut state = splitmix64(seed ^ (quadrant as u64 + 1));
+
+        if !nearby
+            .iter()
+            .any(|&i| map[i].cell_type == TerritoryCellType::Port)
+        {
+            let index = nearby[(state % nearby.len() as u64) as usize];
+            map[index].cell_type = TerritoryCellType::Port;
+        }
+
+        let is_resource = |cell: &TerritoryCell| {
+            matches!(
+                cell.cell_type,
+                TerritoryCellType::Island | TerritoryCellType::Treasure
+            )
+        };
+        if !nearby.iter().any(|&i| is_resource(&map[i])) {
+            let candidates: Vec<usize> = nearby
+                .iter()
+                .copied()
+                .filter(|&i| map[i].cell_type != TerritoryCellType::Port)
+                .collect();
+            state = splitmix64(state);
+            let index = candidates[(state % candidates.len() as u64) as usize];
+            map[index].cell_type = TerritoryCellType::Island;
+        }
+    }
+}
+
 /// Check if a coordinate is already scanned using bit-packing
 pub fn is_coordinate_scanned(scanned: &[u8], x: u8, y: u8) -> bool {
     if x >= MAP_SIZE as u8 || y >= MAP_SIZE as u8 {
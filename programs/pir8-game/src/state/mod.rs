@@ -1,7 +1,65 @@
+pub mod beacon;
+pub mod cargo;
+pub mod config;
+pub mod contract;
+pub mod diff;
+pub mod distributor;
+pub mod distance;
+pub mod export;
+pub mod fork;
 pub mod game;
+pub mod invite;
+pub mod jackpot;
+pub mod leaderboard;
+pub mod ledger;
+pub mod lfg;
+pub mod liveness;
 pub mod map;
+pub mod naming;
+pub mod payout;
 pub mod player;
+pub mod profile;
+pub mod raise;
+pub mod revenue;
+pub mod rng;
+pub mod rulebook;
+pub mod scan_log;
+pub mod scoring;
+pub mod session;
+pub mod shipyard;
+pub mod simultaneous;
+pub mod tutorial;
+pub mod vesting;
 
+pub use beacon::*;
+pub use cargo::*;
+pub use config::*;
+pub use contract::*;
+pub use diff::*;
+pub use distributor::*;
+pub use distance::*;
+pub use export::*;
+pub use fork::*;
 pub use game::*;
+pub use invite::*;
+pub use jackpot::*;
+pub use leaderboard::*;
+pub use ledger::*;
+pub use lfg::*;
+pub use liveness::*;
 pub use map::*;
+pub use naming::*;
+pub use payout::*;
 pub use player::*;
+pub use profile::*;
+pub use raise::*;
+pub use revenue::*;
+pub use rng::*;
+pub use rulebook::*;
+pub use scan_log::*;
+pub use scoring::*;
+pub use session::*;
+pub use shipyard::*;
+pub use simultaneous::*;
+pub use tutorial::*;
+pub use vesting::*;
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+
+pub const GAME_METADATA_SEED: &[u8] = b"game_metadata";
+pub const NAME_REGISTRY_SEED: &[u8] = b"name_registry";
+
+// ============================================================================
+// VANITY LOBBY NAMES
+// ============================================================================
+//
+// A lobby's creator can give it a short slug like "blackbeard-cup", so a
+// tournament page or stream can link to it by name. The slug is kept in the
+// game's GameMetadata PDA, and a NameRegistry PDA derived from the slug's
+// hash records which game holds it. Anyone can resolve a slug on-chain by
+// deriving that PDA and reading its game_id. A slug belongs to the first
+// game that claims it, and a game is named at most once.
+
+pub const MAX_LOBBY_NAME_LEN: usize = 32;
+
+/// Lowercase letters, digits and inner hyphens, at most 32 bytes; one spelling
+/// per name, so two slugs never differ only by case
+pub fn is_valid_lobby_name(name: &str) -> bool {
+    (1..=MAX_LOBBY_NAME_LEN).contains(&name.len())
+        && !name.starts_with('-')
+        && !name.ends_with('-')
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+}
+
+/// Seed of a slug's NameRegistry PDA; hashed so any length fits in one seed
+pub fn lobby_name_hash(name: &str) -> [u8; 32] {
+    hash(name.as_bytes()).to_bytes()
+}
+
+#[account]
+pub struct GameMetadata {
+    pub game_id: u64,
+    pub name: String, // Empty until the creator names the lobby
+    pub bump: u8,
+}
+
+impl GameMetadata {
+    pub const SPACE: usize = 8 + 8 + 4 + MAX_LOBBY_NAME_LEN + 1;
+}
+
+/// The game holding one slug
+#[account]
+pub struct NameRegistry {
+    pub game_id: u64,
+    pub name: String, // Empty until claimed
+    pub bump: u8,
+}
+
+impl NameRegistry {
+    pub const SPACE: usize = 8 + 8 + 4 + MAX_LOBBY_NAME_LEN + 1;
+}
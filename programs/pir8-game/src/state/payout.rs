@@ -0,0 +1,72 @@
+use crate::constants::{BASIS_POINTS, MAX_PLAYERS};
+use anchor_lang::prelude::*;
+
+// ============================================================================
+// PAYOUT SCHEDULE
+// ============================================================================
+//
+// The pot is divided by final place rather than going entirely to the
+// winner. Players tied on a place share the combined shares of every place
+// they cover (two players tied for 2nd split the 2nd and 3rd place shares).
+
+/// Share of the pot per place, in basis points, best place first
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct PayoutSchedule(pub [u16; MAX_PLAYERS as usize]);
+
+/// Winner takes all
+pub const WINNER_TAKES_ALL: PayoutSchedule = PayoutSchedule([10_000, 0, 0, 0]);
+
+impl Default for PayoutSchedule {
+    fn default() -> Self {
+        WINNER_TAKES_ALL
+    }
+}
+
+impl PayoutSchedule {
+    /// Shares must add up to the whole pot and never grow with worse places
+    pub fn is_valid(&self) -> bool {
+        let total: u32 = self.0.iter().map(|&bps| bps as u32).sum();
+        total == BASIS_POINTS && self.0.windows(2).all(|w| w[0] >= w[1])
+    }
+
+    /// Share for one player at `place` when `tied` players occupy it
+    pub fn share_bps(&self, place: u8, tied: u8) -> u32 {
+        let start = (place as usize).min(self.0.len());
+        let end = (start + tied as usize).min(self.0.len());
+        let covered: u32 = self.0[start..end].iter().map(|&bps| bps as u32).sum();
+        covered / tied.max(1) as u32
+    }
+}
+
+/// Final place of a pot-eligible player; tied players share a place
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Standing {
+    pub player: Pubkey,
+    pub place: u8, // 0 = first
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tied_players_split_every_place_they_cover() {
+        let schedule = PayoutSchedule([5_000, 3_000, 2_000, 0]);
+        assert!(schedule.is_valid());
+
+        assert_eq!(schedule.share_bps(0, 1), 5_000);
+        assert_eq!(schedule.share_bps(1, 2), 2_500);
+        // A three-way tie for 2nd covers 2nd, 3rd and the empty 4th
+        assert_eq!(schedule.share_bps(1, 3), 1_666);
+        // A tie running past the last place only shares the places that exist
+        assert_eq!(schedule.share_bps(3, 2), 0);
+        assert_eq!(schedule.share_bps(0, 4), 2_500);
+    }
+
+    #[test]
+    fn schedules_must_cover_the_pot_best_place_first() {
+        assert!(WINNER_TAKES_ALL.is_valid());
+        assert!(!PayoutSchedule([5_000, 3_000, 1_000, 0]).is_valid());
+        assert!(!PayoutSchedule([3_000, 5_000, 2_000, 0]).is_valid());
+    }
+}
@@ -1,8 +1,11 @@
 use crate::constants::*;
 use crate::errors::GameError;
+use crate::rules::resource_value;
+use crate::state::scan_log::ScanLogEntry;
+use crate::state::scoring::ScoringWeights;
 use anchor_lang::prelude::*;
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq)]
 pub struct Resources {
     pub gold: u32,
     pub crew: u32,
@@ -10,6 +13,24 @@ pub struct Resources {
     pub supplies: u32,
 }
 
+/// How a player left a game before it ended, for reputation at settlement
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum Departure {
+    #[default]
+    Stayed,
+    TimedOut,
+    Resigned,
+}
+
+/// Whether a seat still commands a fleet or only watches
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum PlayerRole {
+    #[default]
+    Captain,
+    /// Eliminated; follows the game and may cast one ghost curse
+    Spectator,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
 pub enum ShipType {
     Sloop,
@@ -30,6 +51,19 @@ pub struct ShipData {
     pub position_x: u8,
     pub position_y: u8,
     pub last_action_turn: u32,
+    /// Capture-the-flagship games: sinking this ship eliminates its owner
+    pub is_designated_flagship: bool,
+    /// Treasure-race gold in the hold, spilled where the ship sinks
+    pub cargo_gold: u32,
+    /// Cosmetic NFT the owner dressed this ship in; no effect on play
+    pub skin_mint: Option<Pubkey>,
+}
+
+impl ShipData {
+    /// The single source of truth for whether a ship still counts in play
+    pub fn is_alive(&self) -> bool {
+        self.health > 0
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
@@ -45,6 +79,8 @@ pub struct PlayerData {
     // Scanning system
     pub scan_charges: u8,             // Remaining scans (starts with 3)
     pub scanned_coordinates: Vec<u8>, // Bit-packed indices of 10x10 grid (max 13 bytes)
+    pub last_scan: Option<ScanLogEntry>, // Most recent scan, for the seat's ScanLog
+    pub spyglass_charges: u8,         // Remaining spyglass sweeps (starts with SPYGLASS_CHARGES)
 
     // ===== GHOST FLEET MECHANICS =====
     // Private/stealth mode for strategic advantage
@@ -55,7 +91,52 @@ pub struct PlayerData {
     // Timing bonuses
     pub speed_bonus_accumulated: u64,  // Total timing bonus points
     pub average_decision_time_ms: u64, // Running average decision time
-    pub total_moves: u8,               // Move counter for average calculation
+    pub total_moves: u16,              // Actions taken this game (pot eligibility)
+    pub timed_moves: u8,               // Moves with a reported decision time (average calculation)
+
+    // Progression
+    pub xp_earned: u32,        // XP banked this game, capped by MAX_XP_PER_GAME
+    pub profile_settled: bool, // Whether xp_earned has been moved into the profile
+    pub decision_time_ranked: bool, // Whether this win was submitted to the decision-time leaderboard
+    pub pot_eligible: bool,    // Set at completion; idle players are excluded from scoring and payout
+
+    // Economy
+    pub last_collected_turn: u32, // Round of the last resource collection (one per round)
+
+    // Clock
+    pub time_bank_seconds: u32, // Thinking time left; running out forfeits the game
+    pub clocked_decisions: u16, // Turn instructions timed by the time bank
+    pub clocked_seconds: u32,   // Seconds those decisions took, by on-chain clock
+
+    // Departure
+    pub departure: Departure, // Set when the player times out or resigns
+    pub departed_turn: u32,   // Round the player left in
+    pub timeouts: u8,         // Turns timed out and reveals missed
+    pub parley_used: bool,    // Parley called; one per game
+
+    // Liveness
+    pub liveness_deposit: u64, // Lamports held until settlement; see state::liveness
+    pub liveness_payer: Pubkey, // Wallet that paid the deposit and gets it back
+
+    // Fee sponsorship
+    pub fee_session_opened: bool, // Fee session funded from the pot; one per seat per game
+
+    // Agent
+    pub strategy_hash: Option<[u8; 32]>, // Agent's committed strategy when it took the seat
+    pub bonded_agent: bool, // Seat taken by an agent holding a bond; its strikes are slashed
+
+    // Objectives
+    pub victory_points: u16, // King-of-the-hill points from holding the Golden Port
+    pub delivered_gold: u32, // Treasure-race gold landed at the home port
+    pub victory_warnings: u8, // Victory::bit flags of conditions last announced as near
+    pub home_port: Option<u8>, // Treasure-race delivery port, as a map index
+
+    // Spectating
+    pub role: PlayerRole,       // Spectator once eliminated
+    pub ghost_curse_used: bool, // Spectators get one ghost curse per game
+
+    // Replay protection
+    pub action_nonce: u64, // Nonce the seat's next gameplay instruction must carry
 }
 
 impl Default for PlayerData {
@@ -69,6 +150,8 @@ impl Default for PlayerData {
             is_active: false,
             scan_charges: 3,                 // Start with 3 scans
             scanned_coordinates: Vec::new(), // No scanned tiles initially
+            last_scan: None,
+            spyglass_charges: SPYGLASS_CHARGES,
             // Ghost Fleet - starts inactive
             is_ghost_fleet: false,
             ghost_fleet_turns_remaining: 0,
@@ -76,10 +159,54 @@ impl Default for PlayerData {
             speed_bonus_accumulated: 0,      // No bonuses yet
             average_decision_time_ms: 0,     // No moves yet
             total_moves: 0,                  // No moves yet
+            timed_moves: 0,
+            xp_earned: 0,
+            profile_settled: false,
+            decision_time_ranked: false,
+            pot_eligible: false,
+            last_collected_turn: 0,
+            time_bank_seconds: TIME_BANK_SECONDS,
+            clocked_decisions: 0,
+            clocked_seconds: 0,
+            departure: Departure::Stayed,
+            departed_turn: 0,
+            timeouts: 0,
+            parley_used: false,
+            liveness_deposit: 0,
+            liveness_payer: Pubkey::default(),
+            fee_session_opened: false,
+            strategy_hash: None,
+            bonded_agent: false,
+            victory_points: 0,
+            delivered_gold: 0,
+            victory_warnings: 0,
+            home_port: None,
+            role: PlayerRole::Captain,
+            ghost_curse_used: false,
+            action_nonce: 0,
         }
     }
 }
 
+impl PlayerData {
+    /// Average decision time measured by the chain's clock rather than
+    /// reported by the client, in milliseconds at one-second resolution
+    pub fn clocked_decision_ms(&self) -> Option<u64> {
+        (self.clocked_decisions > 0)
+            .then(|| self.clocked_seconds as u64 * 1_000 / self.clocked_decisions as u64)
+    }
+}
+
+/// Fresh seat for a joining player, funded with the game's starting resources
+pub fn initialize_player(pubkey: Pubkey, resources: &Resources) -> PlayerData {
+    PlayerData {
+        pubkey,
+        resources: resources.clone(),
+        is_active: true,
+        ..PlayerData::default()
+    }
+}
+
 #[account]
 pub struct AgentRegistry {
     pub owner: Pubkey,
@@ -91,6 +218,11 @@ pub struct AgentRegistry {
     pub games_played: u64,
     pub wins: u64,
     pub last_active: i64,
+    pub strategy_hash: [u8; 32], // Commitment to the current strategy version; zero until set
+    pub strategy_updated_at: i64,
+    pub bond: u64,              // Lamports posted by the operator, held above rent
+    pub bond_locked_until: i64, // Joining a game locks the bond until then
+    pub strikes: u16,           // Timeouts and missed reveals attributed to the agent
 }
 
 impl AgentRegistry {
@@ -98,21 +230,112 @@ impl AgentRegistry {
     // Discriminator: 8
     // Owner: 32
     // Delegate (Option<Pubkey>): 1 + 32 = 33
-    // Name (String): 4 + len (assume max 32) = 36
-    // Version (String): 4 + len (assume max 16) = 20
-    // Twitter (Option<String>): 1 + 4 + len (assume max 32) = 37
-    // Website (Option<String>): 1 + 4 + len (assume max 64) = 69
+    // Name (String): 4 + MAX_AGENT_NAME_LEN (32) = 36
+    // Version (String): 4 + MAX_AGENT_VERSION_LEN (16) = 20
+    // Twitter (Option<String>): 1 + 4 + MAX_AGENT_TWITTER_LEN (32) = 37
+    // Website (Option<String>): 1 + 4 + MAX_AGENT_WEBSITE_LEN (64) = 69
     // u64 fields x 3: 24
     // last_active i64: 8
-    // Total approx: ~300 bytes.
-    pub const SPACE: usize =
-        8 + 32 + 33 + (4 + 32) + (4 + 16) + (1 + 4 + 32) + (1 + 4 + 64) + 24 + 8;
+    // Strategy hash + updated_at: 32 + 8
+    // Bond + locked_until + strikes: 8 + 8 + 2
+    // Total approx: ~360 bytes.
+    pub const SPACE: usize = 8
+        + 32
+        + 33
+        + (4 + MAX_AGENT_NAME_LEN)
+        + (4 + MAX_AGENT_VERSION_LEN)
+        + (1 + 4 + MAX_AGENT_TWITTER_LEN)
+        + (1 + 4 + MAX_AGENT_WEBSITE_LEN)
+        + 24
+        + 8
+        + 32
+        + 8
+        + 8
+        + 8
+        + 2;
+
+    /// Trim and check the operator-supplied profile strings, then store them.
+    /// A leading '@' is dropped from the handle, and blank optional fields
+    /// are stored as None. Nothing is written unless every field fits.
+    pub fn set_metadata(
+        &mut self,
+        name: &str,
+        version: &str,
+        twitter: Option<&str>,
+        website: Option<&str>,
+    ) -> Result<()> {
+        let name = name.trim();
+        let version = version.trim();
+        let twitter = twitter
+            .map(|t| t.trim().trim_start_matches('@'))
+            .filter(|t| !t.is_empty());
+        let website = website.map(str::trim).filter(|w| !w.is_empty());
+
+        let fields = [Some(name), Some(version), twitter, website];
+        require!(
+            !name.is_empty()
+                && fields
+                    .iter()
+                    .flatten()
+                    .all(|f| !f.chars().any(char::is_control)),
+            GameError::InvalidAgentMetadata
+        );
+        require!(
+            name.len() <= MAX_AGENT_NAME_LEN,
+            GameError::AgentNameTooLong
+        );
+        require!(
+            version.len() <= MAX_AGENT_VERSION_LEN,
+            GameError::AgentVersionTooLong
+        );
+        require!(
+            twitter.map_or(0, str::len) <= MAX_AGENT_TWITTER_LEN,
+            GameError::AgentTwitterTooLong
+        );
+        require!(
+            website.map_or(0, str::len) <= MAX_AGENT_WEBSITE_LEN,
+            GameError::AgentWebsiteTooLong
+        );
+
+        self.name = name.to_string();
+        self.version = version.to_string();
+        self.twitter = twitter.map(str::to_string);
+        self.website = website.map(str::to_string);
+        Ok(())
+    }
+
+    /// The strategy hash a game should record, if one has been committed
+    pub fn committed_strategy(&self) -> Option<[u8; 32]> {
+        (self.strategy_hash != [0; 32]).then_some(self.strategy_hash)
+    }
+
+    /// Hold the bond for the length of a game the agent has just joined
+    pub fn lock_bond(&mut self, now: i64) {
+        if self.bond > 0 {
+            self.bond_locked_until = self
+                .bond_locked_until
+                .max(now.saturating_add(AGENT_BOND_LOCK_SECONDS));
+        }
+    }
+
+    /// Count a timeout or missed reveal against the agent and take the slash
+    /// out of the bond. Returns the lamports to move into the pot.
+    pub fn record_strike(&mut self) -> u64 {
+        self.strikes = self.strikes.saturating_add(1);
+        if self.strikes <= AGENT_BOND_FREE_STRIKES {
+            return 0;
+        }
+        let slashed = self.bond * AGENT_BOND_SLASH_BPS as u64 / BASIS_POINTS as u64;
+        self.bond -= slashed;
+        slashed
+    }
 }
 
 // ============================================================================
 // HELPERS
 // ============================================================================
 
+/// Built-in ship stats, the ones a mode's Rulebook starts from
 pub fn get_ship_stats(ship_type: &ShipType) -> (u32, u32, u32, u32) {
     // Returns (health, attack, defense, speed)
     match ship_type {
@@ -123,6 +346,7 @@ pub fn get_ship_stats(ship_type: &ShipType) -> (u32, u32, u32, u32) {
     }
 }
 
+/// Built-in build costs, the ones a mode's Rulebook starts from
 pub fn get_ship_costs(ship_type: &ShipType) -> Resources {
     let (gold, crew, cannons, supplies) = match ship_type {
         ShipType::Sloop => SLOOP_COST,
@@ -139,53 +363,101 @@ pub fn get_ship_costs(ship_type: &ShipType) -> Resources {
     }
 }
 
-pub fn get_ship_resource_multiplier(ship_type: &ShipType) -> f32 {
+/// Resource yield multiplier for a ship type, in basis points, from the
+/// game's per-type table (Sloop, Frigate, Galleon, Flagship)
+pub fn get_ship_resource_multiplier(ship_type: &ShipType, multipliers_bps: &[u32; 4]) -> u32 {
     match ship_type {
-        ShipType::Sloop => 1.0,
-        ShipType::Frigate => 1.2,
-        ShipType::Galleon => 1.5,
-        ShipType::Flagship => 1.3,
+        ShipType::Sloop => multipliers_bps[0],
+        ShipType::Frigate => multipliers_bps[1],
+        ShipType::Galleon => multipliers_bps[2],
+        ShipType::Flagship => multipliers_bps[3],
     }
 }
 
-pub fn calculate_speed_bonus(decision_time_ms: u64) -> u64 {
-    match decision_time_ms {
-        0..=5000 => 100,     // <5s: +100 points
-        5001..=10000 => 50,  // <10s: +50 points
-        10001..=15000 => 25, // <15s: +25 points
-        _ => 0,              // >15s: no bonus
-    }
-}
+pub use crate::rules::{apply_multiplier, calculate_attack_damage};
 
 pub fn update_average_decision_time(player: &mut PlayerData, new_decision_time_ms: u64) {
-    if player.total_moves == 0 {
+    if player.timed_moves == 0 {
         player.average_decision_time_ms = new_decision_time_ms;
     } else {
         // Safe multiplication: avg_time * count is bounded by u64
         let total_time = player
             .average_decision_time_ms
-            .saturating_mul(player.total_moves as u64);
+            .saturating_mul(player.timed_moves as u64);
 
         // Safe addition: total_time + new_time, saturate on overflow
         let combined = total_time.saturating_add(new_decision_time_ms);
 
         // Compute new average: combined / (moves + 1)
-        let move_count = (player.total_moves as u64).saturating_add(1);
+        let move_count = (player.timed_moves as u64).saturating_add(1);
         player.average_decision_time_ms = combined / move_count;
     }
+    player.timed_moves = player.timed_moves.saturating_add(1);
+}
+
+/// Accept `nonce` only if it is the seat's expected action nonce, then move
+/// the expectation on, so a wallet retrying a landed transaction is rejected
+/// instead of applying the action twice
+pub fn consume_action_nonce(player: &mut PlayerData, nonce: u64) -> Result<()> {
+    require!(nonce == player.action_nonce, GameError::StaleActionNonce);
+    player.action_nonce += 1;
+    Ok(())
+}
+
+/// Count a gameplay action towards the player's pot eligibility
+pub fn record_action(player: &mut PlayerData) {
     player.total_moves = player.total_moves.saturating_add(1);
 }
 
+/// Weighted end-of-game score under the game's scoring weights
+pub fn final_score(player: &PlayerData, weights: &ScoringWeights) -> u32 {
+    let active_ships = player.ships.iter().filter(|s| s.is_alive()).count();
+    let total_health: u32 = player
+        .ships
+        .iter()
+        .filter(|s| s.is_alive())
+        .map(|s| s.health)
+        .sum();
+    let territories = player.controlled_territories.len();
+    let resource_value = resource_value(
+        player.resources.gold,
+        player.resources.crew,
+        player.resources.cannons,
+        player.resources.supplies,
+    );
+
+    weights.score(
+        active_ships as u32,
+        total_health,
+        territories as u32,
+        resource_value,
+    )
+}
+
+/// Whether a player did enough to share in scoring and the pot
+pub fn is_pot_eligible(player: &PlayerData) -> bool {
+    player.is_active && player.total_moves >= MIN_ACTIONS_FOR_POT
+}
+
 // ============================================================================
 // GHOST FLEET MECHANICS
 // ============================================================================
 
-/// Base scan range for players
-pub const BASE_SCAN_RANGE: u8 = 3;
+/// Radius around a ghost curse's target cell that the squall reaches
+pub const GHOST_CURSE_RADIUS: u32 = 1;
+/// Hull a ghost curse strips from each ship it reaches; it never sinks one
+pub const GHOST_CURSE_DAMAGE: u32 = 5;
+
+/// Radius from the nearest ship within which a scan reveals everything
+pub const BASE_SCAN_RANGE: u8 = 2;
 
-/// Reduced scan range when Ghost Fleet is active
+/// Reduced full-detail radius when Ghost Fleet is active
 pub const GHOST_FLEET_SCAN_RANGE: u8 = 1;
 
+/// Radius within which a scan still identifies the cell type; beyond it a
+/// scan only tells land from water
+pub const SCAN_CELL_TYPE_RANGE: u8 = 5;
+
 /// Ghost Fleet activation cost in gold
 pub const GHOST_FLEET_COST_GOLD: u32 = 200;
 
@@ -202,25 +474,53 @@ pub fn get_effective_scan_range(player: &PlayerData) -> u8 {
     }
 }
 
+/// How much a scan reveals, by distance from the scanner's nearest ship
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum ScanDepth {
+    Surface,  // Land or water only
+    CellType, // Plus the cell type
+    Full,     // Plus owner and remaining yield
+}
+
+/// Scan depth for a player whose nearest living ship is `distance` away
+/// (Chebyshev); a player with no ships afloat only sees the surface
+pub fn scan_depth(player: &PlayerData, distance: Option<u32>) -> ScanDepth {
+    match distance {
+        Some(d) if d <= get_effective_scan_range(player) as u32 => ScanDepth::Full,
+        Some(d) if d <= SCAN_CELL_TYPE_RANGE as u32 => ScanDepth::CellType,
+        _ => ScanDepth::Surface,
+    }
+}
+
 /// Check if it's harder to scan against a Ghost Fleet player
 /// Returns true if the target player has Ghost Fleet active
 pub fn is_harder_to_scan(target: &PlayerData) -> bool {
     target.is_ghost_fleet && target.ghost_fleet_turns_remaining > 0
 }
 
-/// Ambush damage bonus multiplier when attacking from Ghost Fleet
-pub const AMBUSH_DAMAGE_BONUS: f32 = 1.5;
+/// Ambush damage bonus multiplier when attacking from Ghost Fleet (1.5x)
+pub const AMBUSH_DAMAGE_BONUS_BPS: u32 = 15_000;
 
-/// Get the attack damage bonus for being in Ghost Fleet mode
+/// Get the attack damage bonus for being in Ghost Fleet mode, in basis points
 /// Ghost Fleet enables ambush damage bonus from stealth
-pub fn get_ambush_damage_bonus(player: &PlayerData) -> f32 {
+pub fn get_ambush_damage_bonus(player: &PlayerData) -> u32 {
     if player.is_ghost_fleet && player.ghost_fleet_turns_remaining > 0 {
-        AMBUSH_DAMAGE_BONUS
+        AMBUSH_DAMAGE_BONUS_BPS
     } else {
-        1.0 // No bonus
+        BASIS_POINTS // No bonus
     }
 }
 
+// ============================================================================
+// SPYGLASS
+// ============================================================================
+
+/// Spyglass sweeps each player starts with
+pub const SPYGLASS_CHARGES: u8 = 2;
+
+/// Chebyshev radius around the observing ship that a sweep reveals
+pub const SPYGLASS_RADIUS: u32 = 3;
+
 /// Check if player can afford to activate Ghost Fleet
 pub fn can_afford_ghost_fleet(player: &PlayerData) -> bool {
     player.resources.gold >= GHOST_FLEET_COST_GOLD
@@ -229,7 +529,10 @@ pub fn can_afford_ghost_fleet(player: &PlayerData) -> bool {
 /// Activate Ghost Fleet mode
 pub fn activate_ghost_fleet(player: &mut PlayerData) -> Result<()> {
     require!(!player.is_ghost_fleet, GameError::GhostFleetAlreadyActive);
-    require!(player.resources.gold >= GHOST_FLEET_COST_GOLD, GameError::InsufficientResources);
+    require!(
+        player.resources.gold >= GHOST_FLEET_COST_GOLD,
+        GameError::InsufficientResources
+    );
 
     player.resources.gold -= GHOST_FLEET_COST_GOLD;
     player.is_ghost_fleet = true;
@@ -254,3 +557,24 @@ pub fn tick_ghost_fleet(player: &mut PlayerData) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::game::PirateGame;
+    use crate::test_utils::GameBuilder;
+
+    #[test]
+    fn long_games_keep_counting_actions() {
+        let mut game = GameBuilder::new(MAX_PLAYERS).build();
+        let player = &mut game.players[0];
+        for _ in 0..300 {
+            record_action(player);
+        }
+        assert_eq!(player.total_moves, 300);
+        assert!(is_pot_eligible(player));
+
+        // The wider counter still fits a full table in the account
+        assert!(8 + game.try_to_vec().unwrap().len() <= PirateGame::SPACE);
+    }
+}
@@ -0,0 +1,333 @@
+use crate::state::player::{Departure, PlayerData};
+use anchor_lang::prelude::*;
+
+pub const PROFILE_SEED: &[u8] = b"profile";
+
+/// Starting Elo rating for a fresh profile
+pub const DEFAULT_RATING: u32 = 1200;
+
+// ============================================================================
+// PROGRESSION
+// ============================================================================
+
+/// XP awarded per gameplay action
+pub const XP_MOVE: u32 = 2;
+pub const XP_ATTACK: u32 = 5;
+pub const XP_SINK: u32 = 20;
+pub const XP_CLAIM: u32 = 10;
+pub const XP_COLLECT: u32 = 3;
+pub const XP_BUILD: u32 = 15;
+pub const XP_SCAN: u32 = 2;
+pub const XP_GHOST_FLEET: u32 = 5;
+
+/// Anti-farm cap on action XP a single game can award a player
+pub const MAX_XP_PER_GAME: u32 = 300;
+
+/// Bonuses added at settlement, outside the per-game cap
+pub const XP_GAME_COMPLETED: u32 = 25;
+pub const XP_GAME_WON: u32 = 100;
+
+/// Total XP required to reach each level (index 0 = level 1)
+pub const LEVEL_XP_THRESHOLDS: [u64; 10] =
+    [0, 100, 300, 600, 1_000, 1_500, 2_500, 4_000, 6_000, 10_000];
+
+/// Cosmetic unlock bits stored in `PlayerProfile::cosmetic_flags`
+pub const COSMETIC_FLAG_COLORS: u32 = 1 << 0; // Level 2
+pub const COSMETIC_SAIL_PATTERNS: u32 = 1 << 1; // Level 4
+pub const COSMETIC_FIGUREHEADS: u32 = 1 << 2; // Level 6
+pub const COSMETIC_GOLDEN_TRIM: u32 = 1 << 3; // Level 10
+
+/// Number of selectable flag colors; color 0 is the undyed default
+pub const FLAG_COLOR_COUNT: u8 = 16;
+
+/// Title bits stored in `PlayerProfile::titles`, earned from the profile record
+pub const TITLE_CAPTAIN: u32 = 1 << 0; // First win
+pub const TITLE_SEA_DOG: u32 = 1 << 1; // 10 games played
+pub const TITLE_DREAD_PIRATE: u32 = 1 << 2; // 25 wins
+pub const TITLE_ADMIRAL: u32 = 1 << 3; // Level 10
+
+pub fn level_for_xp(xp: u64) -> u8 {
+    LEVEL_XP_THRESHOLDS.iter().filter(|t| xp >= **t).count() as u8
+}
+
+pub fn cosmetics_for_level(level: u8) -> u32 {
+    let mut flags = 0;
+    if level >= 2 {
+        flags |= COSMETIC_FLAG_COLORS;
+    }
+    if level >= 4 {
+        flags |= COSMETIC_SAIL_PATTERNS;
+    }
+    if level >= 6 {
+        flags |= COSMETIC_FIGUREHEADS;
+    }
+    if level >= 10 {
+        flags |= COSMETIC_GOLDEN_TRIM;
+    }
+    flags
+}
+
+pub fn titles_for_record(games_played: u32, wins: u32, level: u8) -> u32 {
+    let mut titles = 0;
+    if wins >= 1 {
+        titles |= TITLE_CAPTAIN;
+    }
+    if games_played >= 10 {
+        titles |= TITLE_SEA_DOG;
+    }
+    if wins >= 25 {
+        titles |= TITLE_DREAD_PIRATE;
+    }
+    if level >= 10 {
+        titles |= TITLE_ADMIRAL;
+    }
+    titles
+}
+
+/// Bank in-game XP for a player, respecting the per-game cap
+pub fn grant_xp(player: &mut PlayerData, amount: u32) {
+    player.xp_earned = player.xp_earned.saturating_add(amount).min(MAX_XP_PER_GAME);
+}
+
+// ============================================================================
+// REPUTATION
+// ============================================================================
+
+/// Reputation a fresh profile starts with, and its ceiling
+pub const DEFAULT_REPUTATION: u16 = 500;
+pub const MAX_REPUTATION: u16 = 1_000;
+
+/// Reputation gained for playing a game through to the end
+pub const REPUTATION_GAME_COMPLETED: u16 = 5;
+/// Reputation lost for letting the time bank run out
+pub const REPUTATION_TIMEOUT_PENALTY: u16 = 25;
+/// Reputation lost for resigning before EARLY_RESIGN_TURNS rounds
+pub const REPUTATION_EARLY_RESIGN_PENALTY: u16 = 40;
+pub const EARLY_RESIGN_TURNS: u32 = 5;
+
+/// Reputation change for how a player left a game, as (gain, loss)
+pub fn reputation_change(departure: Departure, departed_turn: u32) -> (u16, u16) {
+    match departure {
+        Departure::Stayed => (REPUTATION_GAME_COMPLETED, 0),
+        Departure::TimedOut => (0, REPUTATION_TIMEOUT_PENALTY),
+        Departure::Resigned if departed_turn < EARLY_RESIGN_TURNS => {
+            (0, REPUTATION_EARLY_RESIGN_PENALTY)
+        }
+        Departure::Resigned => (0, 0),
+    }
+}
+
+// ============================================================================
+// LOSING-STREAK PITY BONUS
+// ============================================================================
+//
+// A wallet that has lost PITY_STREAK_LOSSES or more settled games in a row
+// starts each casual game with extra gold, as the lobby's config set it at
+// creation, until it wins again. Ranked lobbies never grant it.
+
+pub const PITY_STREAK_LOSSES: u16 = 3;
+
+// ============================================================================
+// RATE LIMITS
+// ============================================================================
+//
+// Creates and joins are throttled per wallet over a sliding hour. Each
+// counter keeps the current and previous fixed hour; the previous hour's
+// count is weighted by how much of it still falls inside the sliding window.
+
+pub const RATE_WINDOW_SECONDS: i64 = 3600;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct RateWindow {
+    pub window_start: i64, // Start of the current fixed hour
+    pub current: u16,
+    pub previous: u16,
+}
+
+impl RateWindow {
+    /// Move the fixed hours forward to the one containing `now`
+    fn roll(&mut self, now: i64) {
+        let start = now - now.rem_euclid(RATE_WINDOW_SECONDS);
+        if start == self.window_start {
+            return;
+        }
+        self.previous = if start - self.window_start == RATE_WINDOW_SECONDS {
+            self.current
+        } else {
+            0
+        };
+        self.current = 0;
+        self.window_start = start;
+    }
+
+    /// Estimated actions over the hour ending at `now`
+    pub fn count(&self, now: i64) -> u32 {
+        let into_window = (now - self.window_start).clamp(0, RATE_WINDOW_SECONDS);
+        let previous_weight = (RATE_WINDOW_SECONDS - into_window) as u32;
+        self.previous as u32 * previous_weight / RATE_WINDOW_SECONDS as u32 + self.current as u32
+    }
+
+    /// Count an action at `now`, or return false if `limit` is already reached
+    pub fn try_record(&mut self, now: i64, limit: u16) -> bool {
+        self.roll(now);
+        if self.count(now) >= limit as u32 {
+            return false;
+        }
+        self.current = self.current.saturating_add(1);
+        true
+    }
+}
+
+// ============================================================================
+// WAGER LIMITS
+// ============================================================================
+//
+// Where the config sets a daily wager cap, the SOL a wallet pays in entry
+// fees is tracked over a sliding 24 hours, the same way as the throttles:
+// the current and previous fixed day, the previous one weighted by how much
+// of it the window still covers.
+
+pub const WAGER_WINDOW_SECONDS: i64 = 86_400;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct WagerWindow {
+    pub window_start: i64, // Start of the current fixed day
+    pub current: u64,      // Lamports wagered in the current day
+    pub previous: u64,
+}
+
+impl WagerWindow {
+    /// Move the fixed days forward to the one containing `now`
+    fn roll(&mut self, now: i64) {
+        let start = now - now.rem_euclid(WAGER_WINDOW_SECONDS);
+        if start == self.window_start {
+            return;
+        }
+        self.previous = if start - self.window_start == WAGER_WINDOW_SECONDS {
+            self.current
+        } else {
+            0
+        };
+        self.current = 0;
+        self.window_start = start;
+    }
+
+    /// Estimated lamports wagered over the 24 hours ending at `now`
+    pub fn total(&self, now: i64) -> u64 {
+        // Wagers are only recorded on joins, so the stored days may be stale
+        let mut window = *self;
+        window.roll(now);
+        let into_window = (now - window.window_start).clamp(0, WAGER_WINDOW_SECONDS);
+        let previous_weight = (WAGER_WINDOW_SECONDS - into_window) as u128;
+        let previous = window.previous as u128 * previous_weight / WAGER_WINDOW_SECONDS as u128;
+        (previous as u64).saturating_add(window.current)
+    }
+
+    /// Count a wager at `now`, or return false if it would take the window
+    /// past `cap`
+    pub fn try_record(&mut self, now: i64, amount: u64, cap: Option<u64>) -> bool {
+        self.roll(now);
+        if let Some(cap) = cap {
+            if self.total(now).saturating_add(amount) > cap {
+                return false;
+            }
+        }
+        self.current = self.current.saturating_add(amount);
+        true
+    }
+}
+
+/// Cross-game record for a wallet, created the first time it joins a game
+#[account]
+pub struct PlayerProfile {
+    pub player: Pubkey,
+    pub rating: u32,
+    pub games_played: u32,
+    pub wins: u32,
+    pub xp: u64,
+    pub level: u8,
+    pub cosmetic_flags: u32,
+    pub bump: u8,
+    // Identity shown to other players
+    pub flag_color: u8,
+    pub avatar_seed: u64,
+    pub titles: u32,        // Earned title bits
+    pub title: u8,          // Displayed title: 0 = none, otherwise title bit index + 1
+    pub reputation: u16,    // Sportsmanship, out of MAX_REPUTATION
+    pub losing_streak: u16, // Settled games lost since the last win
+    // Anti-spam throttles
+    pub create_window: RateWindow,
+    pub join_window: RateWindow,
+    // Responsible-gaming limit
+    pub wager_window: WagerWindow,
+}
+
+impl PlayerProfile {
+    // Discriminator: 8
+    // player: 32
+    // rating, games_played, wins: 4 x 3 = 12
+    // xp: 8, level: 1, cosmetic_flags: 4
+    // bump: 1
+    // flag_color: 1, avatar_seed: 8, titles: 4, title: 1
+    // reputation: 2, losing_streak: 2
+    // create_window, join_window: (8 + 2 + 2) x 2 = 24
+    // wager_window: 8 + 8 + 8 = 24
+    pub const SPACE: usize = 8 + 32 + 12 + 8 + 1 + 4 + 1 + 1 + 8 + 4 + 1 + 2 + 2 + 24 + 24;
+
+    /// Fill in defaults for a profile created by `init_if_needed`
+    pub fn ensure_initialized(&mut self, player: Pubkey, bump: u8) {
+        if self.player == Pubkey::default() {
+            self.player = player;
+            self.rating = DEFAULT_RATING;
+            self.level = 1;
+            self.reputation = DEFAULT_REPUTATION;
+            self.bump = bump;
+        }
+    }
+
+    /// Add XP and recompute level and cosmetic unlocks. Returns true on level-up.
+    pub fn add_xp(&mut self, amount: u64) -> bool {
+        let previous_level = self.level;
+        self.xp = self.xp.saturating_add(amount);
+        self.level = level_for_xp(self.xp);
+        self.cosmetic_flags |= cosmetics_for_level(self.level);
+        self.level > previous_level
+    }
+
+    /// Apply a reputation change, clamped to 0..=MAX_REPUTATION
+    pub fn adjust_reputation(&mut self, gain: u16, loss: u16) {
+        self.reputation = self
+            .reputation
+            .saturating_add(gain)
+            .saturating_sub(loss)
+            .min(MAX_REPUTATION);
+    }
+
+    /// Extend or end the losing streak with a settled result
+    pub fn record_result(&mut self, won: bool) {
+        self.losing_streak = if won {
+            0
+        } else {
+            self.losing_streak.saturating_add(1)
+        };
+    }
+
+    /// Gold a lobby offering `bonus_gold` adds to this wallet's start
+    pub fn pity_bonus(&self, bonus_gold: u32) -> u32 {
+        if self.losing_streak >= PITY_STREAK_LOSSES {
+            bonus_gold
+        } else {
+            0
+        }
+    }
+
+    /// Recompute earned titles from the profile record
+    pub fn refresh_titles(&mut self) {
+        self.titles |= titles_for_record(self.games_played, self.wins, self.level);
+    }
+
+    /// Whether a displayed title value refers to a title this profile holds
+    pub fn has_title(&self, title: u8) -> bool {
+        title == 0 || (title <= 32 && self.titles & (1 << (title - 1)) != 0)
+    }
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+// ============================================================================
+// STAKE RAISES
+// ============================================================================
+//
+// Mid-game, any captain of a SOL game may propose doubling the stake. The
+// proposer pays the raise up front and every other active captain must match
+// it within the response window; the escrowed lamports join the pot only once
+// the vote is unanimous. A single decline, or letting the window lapse,
+// cancels the raise and refunds every backer.
+
+/// Times a game's stake may be doubled
+pub const MAX_STAKE_RAISES: u8 = 3;
+/// Rounds after the proposal in which the other captains may accept
+pub const RAISE_RESPONSE_ROUNDS: u32 = 1;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct StakeRaise {
+    pub proposer: Pubkey,
+    pub amount: u64,          // Lamports each captain adds to the pot
+    pub proposed_turn: u32,   // Round the raise was proposed in
+    pub backers: Vec<Pubkey>, // Captains who have paid in, proposer first
+}
+
+impl StakeRaise {
+    /// Last round in which the raise may still be accepted
+    pub fn expires_turn(&self) -> u32 {
+        self.proposed_turn + RAISE_RESPONSE_ROUNDS
+    }
+
+    /// Lamports held in the game account on behalf of the backers
+    pub fn escrow(&self) -> u64 {
+        self.amount.saturating_mul(self.backers.len() as u64)
+    }
+}
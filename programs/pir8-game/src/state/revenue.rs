@@ -0,0 +1,77 @@
+use crate::constants::BASIS_POINTS;
+use anchor_lang::prelude::*;
+
+pub const REVENUE_SPLITTER_SEED: &[u8] = b"revenue_splitter";
+
+// ============================================================================
+// REVENUE SPLITTER
+// ============================================================================
+//
+// A single PDA that collects the platform's cut of claimed pots and shares it
+// out on chain. Claims pay the platform fee either to the config authority,
+// as before, or into the splitter; anyone may then call `distribute_fees` to
+// sweep everything above rent exemption to the dev wallet, the seasonal prize
+// pool and the jackpot, in the shares the authority configured.
+
+/// Recipients and their shares of each sweep, in basis points of the sweep
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct RevenueSplit {
+    pub dev_wallet: Pubkey,
+    pub prize_pool: Pubkey,
+    pub dev_share_bps: u16,
+    pub prize_pool_share_bps: u16,
+    pub jackpot_share_bps: u16,
+}
+
+impl RevenueSplit {
+    pub const SPACE: usize = 32 + 32 + 2 + 2 + 2;
+
+    /// Shares that add up to the whole sweep
+    pub fn is_valid(&self) -> bool {
+        self.dev_share_bps as u32 + self.prize_pool_share_bps as u32 + self.jackpot_share_bps as u32
+            == BASIS_POINTS
+    }
+
+    /// Divide a sweep; the dev wallet takes any rounding dust
+    pub fn split(&self, amount: u64) -> FeeSplit {
+        let share = |bps: u16| (amount as u128 * bps as u128 / BASIS_POINTS as u128) as u64;
+        let prize_pool = share(self.prize_pool_share_bps);
+        let jackpot = share(self.jackpot_share_bps);
+        FeeSplit {
+            dev: amount - prize_pool - jackpot,
+            prize_pool,
+            jackpot,
+        }
+    }
+}
+
+/// How one sweep divides between the recipients
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FeeSplit {
+    pub dev: u64,
+    pub prize_pool: u64,
+    pub jackpot: u64,
+}
+
+#[account]
+pub struct RevenueSplitter {
+    pub authority: Pubkey,
+    pub split: RevenueSplit,
+    pub total_distributed: u64, // Lamports swept over the splitter's life
+    pub bump: u8,
+}
+
+impl RevenueSplitter {
+    pub const SPACE: usize = 8 + 32 + RevenueSplit::SPACE + 8 + 1;
+}
+
+/// Address of the splitter PDA
+pub fn revenue_splitter_address() -> Pubkey {
+    Pubkey::find_program_address(&[REVENUE_SPLITTER_SEED], &crate::ID).0
+}
+
+/// Where a claim may send the platform fee: the config authority or the
+/// revenue splitter
+pub fn is_fee_treasury(config_authority: &Pubkey, treasury: &Pubkey) -> bool {
+    treasury == config_authority || *treasury == revenue_splitter_address()
+}
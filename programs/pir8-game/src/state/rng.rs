@@ -0,0 +1,160 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+pub use crate::rules::splitmix64;
+
+// ============================================================================
+// DETERMINISTIC RANDOMNESS
+// ============================================================================
+//
+// A game's map and seat order are pure functions of `PirateGame::seed`,
+// which comes from the hash of a slot that was still in the future when the
+// lobby was started (see `reveal_seed`), so neither the starter nor the last
+// captain to join can grind for a map or a first move they like.
+// Every roll after that (weather and simultaneous-round initiative) is drawn
+// from the game's RngStream, which is seeded from the same seed and counts
+// its draws, so the seed and counter alone replay every roll. The audit
+// record on the game pins down the remaining inputs so an observer can
+// replay every random outcome of a finished game.
+//
+// Games created with `GameOptions::deterministic` (tournament play) take no
+// other randomness: everything after the seed, from weather to contracts to
+// initiative, comes from the stream, and they never roll for the jackpot,
+// whose roll comes from a later slot hash. A replay from the seed and the
+// recorded instructions is bit-identical.
+
+/// Keeps stream draws independent of the map and seat-order draws
+const RNG_STREAM_DOMAIN: u64 = 0x5354_5245_414D_5331; // "STREAMS1"
+
+/// Keeps game seeds independent of anything else drawn from the same slot hash
+const SEED_DOMAIN: u64 = 0x5345_4544_534C_4F54; // "SEEDSLOT"
+
+/// Slots between a commitment and the slot whose hash resolves it, so the
+/// hash is unknown to whoever commits
+pub const REVEAL_DELAY_SLOTS: u64 = 4;
+
+/// Lineage of a game's randomness
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct RngAudit {
+    pub seed_slot: u64,    // Slot whose hash fixed the seed
+    pub jackpot_slot: u64, // Slot whose hash fed the jackpot roll, 0 if none was made
+}
+
+/// The game's source of in-play randomness. Draw `n` is a pure function of
+/// the seed and `n`, so the recorded seed and counter replay every roll.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct RngStream {
+    pub seed: u64,
+    pub counter: u64, // Draws taken so far
+}
+
+impl RngStream {
+    pub fn new(seed: u64) -> Self {
+        Self { seed, counter: 0 }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let draw = splitmix64(splitmix64(self.seed ^ RNG_STREAM_DOMAIN) ^ self.counter);
+        self.counter += 1;
+        draw
+    }
+
+    /// Draw in `0..bound`; `bound` must be nonzero
+    pub fn next_below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+}
+
+/// Hash of the first slot at or after `target` in raw SlotHashes sysvar
+/// data, with the slot it belongs to; a skipped target resolves to the next
+/// slot produced. None if no slot from `target` on has been hashed yet, or
+/// the sysvar's window has already moved past it.
+pub fn slot_hash_at(data: &[u8], target: u64) -> Result<Option<(u64, [u8; 32])>> {
+    // Layout: u64 entry count, then (slot: u64, hash: [u8; 32]) newest first
+    let count = read_u64(data, 0)?;
+    let mut found = None;
+    for entry in 0..count as usize {
+        let offset = 8 + entry * 40;
+        let slot = read_u64(data, offset)?;
+        if slot < target {
+            return Ok(found);
+        }
+        let bytes = data
+            .get(offset + 8..offset + 40)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(bytes);
+        found = Some((slot, hash));
+        if slot == target {
+            return Ok(found);
+        }
+    }
+    // Every entry is newer than the target, which has aged out of the window
+    Ok(None)
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
+    let bytes = data
+        .get(offset..offset + 8)
+        .ok_or(ProgramError::InvalidAccountData)?;
+    let mut value = [0u8; 8];
+    value.copy_from_slice(bytes);
+    Ok(u64::from_le_bytes(value))
+}
+
+/// A game's seed, from the hash of the slot its start committed to
+pub fn seed_from_slot_hash(hash: &[u8; 32], game_id: u64) -> u64 {
+    let digest = hashv(&[hash, &game_id.to_le_bytes(), &SEED_DOMAIN.to_le_bytes()]);
+    let mut seed = [0u8; 8];
+    seed.copy_from_slice(&digest.to_bytes()[..8]);
+    u64::from_le_bytes(seed)
+}
+
+/// Deterministic Fisher-Yates shuffle of `0..count` driven by `seed`
+pub fn shuffled_indices(count: u8, seed: u64) -> Vec<u8> {
+    let mut order: Vec<u8> = (0..count).collect();
+    let mut state = seed;
+    for i in (1..order.len()).rev() {
+        state = splitmix64(state);
+        let j = (state % (i as u64 + 1)) as usize;
+        order.swap(i, j);
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// SlotHashes data for (slot, hash fill byte) entries, newest first
+    fn slot_hashes(entries: &[(u64, u8)]) -> Vec<u8> {
+        let mut data = (entries.len() as u64).to_le_bytes().to_vec();
+        for (slot, fill) in entries {
+            data.extend_from_slice(&slot.to_le_bytes());
+            data.extend_from_slice(&[*fill; 32]);
+        }
+        data
+    }
+
+    #[test]
+    fn committed_slots_resolve_to_their_hash_or_the_next_produced() {
+        // Slot 102 was skipped by its leader
+        let data = slot_hashes(&[(104, 4), (103, 3), (101, 1), (100, 0)]);
+        assert_eq!(slot_hash_at(&data, 103).unwrap(), Some((103, [3; 32])));
+        assert_eq!(slot_hash_at(&data, 102).unwrap(), Some((103, [3; 32])));
+        assert_eq!(slot_hash_at(&data, 100).unwrap(), Some((100, [0; 32])));
+        // Not hashed yet
+        assert_eq!(slot_hash_at(&data, 105).unwrap(), None);
+        // Aged out: the window can no longer show which slot came first
+        assert_eq!(slot_hash_at(&data, 99).unwrap(), None);
+        assert!(slot_hash_at(&data[..20], 101).is_err());
+    }
+
+    #[test]
+    fn seeds_depend_on_the_slot_hash_and_the_game() {
+        let seed = seed_from_slot_hash(&[1; 32], 7);
+        assert_eq!(seed, seed_from_slot_hash(&[1; 32], 7));
+        assert_ne!(seed, seed_from_slot_hash(&[2; 32], 7));
+        assert_ne!(seed, seed_from_slot_hash(&[1; 32], 8));
+    }
+}
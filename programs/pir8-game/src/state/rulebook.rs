@@ -0,0 +1,193 @@
+use crate::constants::MAP_SIZE;
+use crate::rules::{cell_income, VictoryThresholds, DEFAULT_VICTORY_THRESHOLDS};
+use crate::state::game::GameMode;
+use crate::state::map::TerritoryCellType;
+use crate::state::player::{get_ship_costs, get_ship_stats, Resources, ShipType};
+use anchor_lang::prelude::*;
+
+pub const RULEBOOK_SEED: &[u8] = b"rulebook";
+
+// ============================================================================
+// PER-MODE RULEBOOK
+// ============================================================================
+//
+// The balance numbers a game is played with: ship stats and build costs,
+// territory yields, speed-bonus brackets and victory thresholds. Each game
+// mode has its own Rulebook PDA, which the config authority can patch between
+// seasons; every update bumps its version. A game copies the rulebook for its
+// mode when it starts and plays on that copy, so a balance patch never
+// touches a game already under way. Games started without the rulebook
+// account, and modes that have none, use the built-in values (version 0).
+
+/// Ships in rulebook order: Sloop, Frigate, Galleon, Flagship
+pub fn ship_slot(ship_type: &ShipType) -> usize {
+    match ship_type {
+        ShipType::Sloop => 0,
+        ShipType::Frigate => 1,
+        ShipType::Galleon => 2,
+        ShipType::Flagship => 3,
+    }
+}
+
+const SHIP_TYPES: [ShipType; 4] = [
+    ShipType::Sloop,
+    ShipType::Frigate,
+    ShipType::Galleon,
+    ShipType::Flagship,
+];
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct ShipStats {
+    pub health: u32,
+    pub attack: u32,
+    pub defense: u32,
+    pub speed: u32,
+}
+
+/// Moves decided within `max_ms` earn `points`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct SpeedBonusBracket {
+    pub max_ms: u64,
+    pub points: u64,
+}
+
+/// Balance values a game is played under
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct RulebookParams {
+    pub ship_stats: [ShipStats; 4],
+    pub ship_costs: [Resources; 4],
+    /// Base yield of one collection from an Island, Port and Treasure cell
+    pub cell_income: [Resources; 3],
+    /// Fastest bracket first; slower moves than the last earn nothing
+    pub speed_bonus_brackets: [SpeedBonusBracket; 3],
+    pub fleet_dominance_percent: u32,
+    pub territory_control_percent: u32,
+    pub economic_victory_value: u32,
+}
+
+impl RulebookParams {
+    pub const SPACE: usize = 4 * 16 + 4 * 16 + 3 * 16 + 3 * 16 + 4 + 4 + 4;
+
+    /// (health, attack, defense, speed)
+    pub fn ship_stats(&self, ship_type: &ShipType) -> (u32, u32, u32, u32) {
+        let stats = self.ship_stats[ship_slot(ship_type)];
+        (stats.health, stats.attack, stats.defense, stats.speed)
+    }
+
+    pub fn ship_costs(&self, ship_type: &ShipType) -> Resources {
+        self.ship_costs[ship_slot(ship_type)].clone()
+    }
+
+    pub fn cell_income(&self, cell_type: TerritoryCellType) -> Resources {
+        match cell_type {
+            TerritoryCellType::Island => self.cell_income[0].clone(),
+            TerritoryCellType::Port => self.cell_income[1].clone(),
+            TerritoryCellType::Treasure => self.cell_income[2].clone(),
+            _ => Resources::default(),
+        }
+    }
+
+    pub fn speed_bonus(&self, decision_time_ms: u64) -> u64 {
+        self.speed_bonus_brackets
+            .iter()
+            .find(|bracket| decision_time_ms <= bracket.max_ms)
+            .map(|bracket| bracket.points)
+            .unwrap_or(0)
+    }
+
+    pub fn victory_thresholds(&self) -> VictoryThresholds {
+        VictoryThresholds {
+            fleet_dominance_percent: self.fleet_dominance_percent,
+            territory_control_percent: self.territory_control_percent,
+            economic_victory_value: self.economic_victory_value,
+        }
+    }
+
+    /// Ships that can sail and take a hit, brackets in order, and victory
+    /// thresholds that can actually be met
+    pub fn is_valid(&self) -> bool {
+        let ships_ok = self
+            .ship_stats
+            .iter()
+            .all(|s| s.health > 0 && (1..MAP_SIZE as u32).contains(&s.speed));
+        let brackets_ok = self
+            .speed_bonus_brackets
+            .windows(2)
+            .all(|pair| pair[0].max_ms < pair[1].max_ms);
+        ships_ok
+            && brackets_ok
+            && (1..=100).contains(&self.fleet_dominance_percent)
+            && (1..=100).contains(&self.territory_control_percent)
+            && self.economic_victory_value > 0
+    }
+}
+
+impl Default for RulebookParams {
+    fn default() -> Self {
+        let income = |cell_type: u8| {
+            let (gold, crew, cannons, supplies) = cell_income(cell_type);
+            Resources {
+                gold,
+                crew,
+                cannons,
+                supplies,
+            }
+        };
+        Self {
+            ship_stats: SHIP_TYPES.map(|ship_type| {
+                let (health, attack, defense, speed) = get_ship_stats(&ship_type);
+                ShipStats {
+                    health,
+                    attack,
+                    defense,
+                    speed,
+                }
+            }),
+            ship_costs: SHIP_TYPES.map(|ship_type| get_ship_costs(&ship_type)),
+            cell_income: [
+                income(TerritoryCellType::Island as u8),
+                income(TerritoryCellType::Port as u8),
+                income(TerritoryCellType::Treasure as u8),
+            ],
+            speed_bonus_brackets: [
+                SpeedBonusBracket {
+                    max_ms: 5_000,
+                    points: 100,
+                },
+                SpeedBonusBracket {
+                    max_ms: 10_000,
+                    points: 50,
+                },
+                SpeedBonusBracket {
+                    max_ms: 15_000,
+                    points: 25,
+                },
+            ],
+            fleet_dominance_percent: DEFAULT_VICTORY_THRESHOLDS.fleet_dominance_percent,
+            territory_control_percent: DEFAULT_VICTORY_THRESHOLDS.territory_control_percent,
+            economic_victory_value: DEFAULT_VICTORY_THRESHOLDS.economic_victory_value,
+        }
+    }
+}
+
+#[account]
+pub struct Rulebook {
+    pub authority: Pubkey,
+    pub mode: GameMode,
+    pub version: u32, // Starts at 1; bumped by every update
+    pub params: RulebookParams,
+    pub bump: u8,
+}
+
+impl Rulebook {
+    pub const SPACE: usize = 8 + 32 + 1 + 4 + RulebookParams::SPACE + 1;
+}
+
+/// Seed byte of a mode's rulebook PDA
+pub fn rulebook_seed(mode: &GameMode) -> [u8; 1] {
+    [match mode {
+        GameMode::Casual => 0,
+        GameMode::Competitive => 1,
+        GameMode::AgentArena => 2,
+    }]
+}
@@ -0,0 +1,78 @@
+use crate::constants::MAP_SIZE;
+use crate::state::game::PirateGame;
+use crate::state::map::TerritoryCellType;
+use crate::state::player::ScanDepth;
+use anchor_lang::prelude::*;
+
+pub const SCAN_LOG_SEED: &[u8] = b"scan_log";
+
+// ============================================================================
+// SCAN LOGS
+// ============================================================================
+//
+// Scan results stay out of events, so an agent that restarts mid-game has no
+// transaction history to rebuild its intel from. A player can open a scan
+// log for a game; any turn instruction sent with it appends the scans it
+// made: the cell, the turn and what the scan revealed at its depth. The
+// seat's own bitmask still decides which cells have been scanned. The log is
+// the reloadable record of what they showed, so scans made without it
+// passed go unrecorded, apart from the seat's most recent one, which the next
+// instruction carrying the log picks up.
+
+/// Entries a log can hold: one per cell of the map
+pub const MAX_SCAN_LOG_ENTRIES: usize = MAP_SIZE * MAP_SIZE;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ScanLogEntry {
+    pub coordinate: u8, // Map index, x * MAP_SIZE + y
+    pub turn_number: u32,
+    pub depth: ScanDepth,
+    pub revealed: TerritoryCellType, // Water or Island only, at Surface depth
+}
+
+impl ScanLogEntry {
+    pub const SPACE: usize = 1 + 4 + 1 + 1;
+}
+
+/// What a scan at `depth` shows of a cell: a surface scan tells only land
+/// from water
+pub fn revealed_cell_type(cell_type: TerritoryCellType, depth: ScanDepth) -> TerritoryCellType {
+    match depth {
+        ScanDepth::Surface if cell_type.is_land() => TerritoryCellType::Island,
+        ScanDepth::Surface => TerritoryCellType::Water,
+        ScanDepth::CellType | ScanDepth::Full => cell_type,
+    }
+}
+
+#[account]
+pub struct ScanLog {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub entries: Vec<ScanLogEntry>, // In the order the scans were made
+    pub bump: u8,
+}
+
+impl ScanLog {
+    pub const SPACE: usize = 8 + 8 + 32 + 4 + MAX_SCAN_LOG_ENTRIES * ScanLogEntry::SPACE + 1;
+
+    /// Append a scan unless its cell is already logged
+    pub fn record(&mut self, entry: ScanLogEntry) {
+        if self.entries.len() < MAX_SCAN_LOG_ENTRIES
+            && !self
+                .entries
+                .iter()
+                .any(|e| e.coordinate == entry.coordinate)
+        {
+            self.entries.push(entry);
+        }
+    }
+}
+
+/// Log the player's latest scan into the scan log passed with a turn
+/// instruction, if any
+pub fn log_scan(scan_log: &mut Option<Account<ScanLog>>, game: &PirateGame, player: &Pubkey) {
+    let last_scan = game.get_player(player).and_then(|p| p.last_scan);
+    if let (Some(scan_log), Some(entry)) = (scan_log.as_mut(), last_scan) {
+        scan_log.record(entry);
+    }
+}
@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+
+// ============================================================================
+// SCORING WEIGHTS
+// ============================================================================
+//
+// The end-of-game score that decides time-limit finishes and orders the
+// standings is a weighted sum of four terms: surviving ships, their hull
+// points, controlled territories and the resource value of the hold. The
+// creator picks a preset (or custom weights within the bounds below) and the
+// resolved weights are stored on the game, then echoed in `GameCompleted`.
+
+/// Points per unit of each scoring term
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ScoringWeights {
+    pub ship: u16,
+    pub health: u16,
+    pub territory: u16,
+    pub resources: u16,
+}
+
+/// The original formula: ships * 100 + health * 2 + territories * 150 + resources
+pub const STANDARD_SCORING: ScoringWeights = ScoringWeights {
+    ship: 100,
+    health: 2,
+    territory: 150,
+    resources: 1,
+};
+
+/// Upper bound on each weight, so no single term can swamp the rest
+pub const MAX_SHIP_WEIGHT: u16 = 500;
+pub const MAX_HEALTH_WEIGHT: u16 = 10;
+pub const MAX_TERRITORY_WEIGHT: u16 = 500;
+pub const MAX_RESOURCE_WEIGHT: u16 = 5;
+
+impl Default for ScoringWeights {
+    fn default() -> Self {
+        STANDARD_SCORING
+    }
+}
+
+impl ScoringWeights {
+    /// Every weight within its bound, and at least one of them counting
+    pub fn is_valid(&self) -> bool {
+        self.ship <= MAX_SHIP_WEIGHT
+            && self.health <= MAX_HEALTH_WEIGHT
+            && self.territory <= MAX_TERRITORY_WEIGHT
+            && self.resources <= MAX_RESOURCE_WEIGHT
+            && (self.ship | self.health | self.territory | self.resources) != 0
+    }
+
+    pub fn score(&self, ships: u32, health: u32, territories: u32, resource_value: u32) -> u32 {
+        (ships.saturating_mul(self.ship as u32))
+            .saturating_add(health.saturating_mul(self.health as u32))
+            .saturating_add(territories.saturating_mul(self.territory as u32))
+            .saturating_add(resource_value.saturating_mul(self.resources as u32))
+    }
+}
+
+/// Scoring a creator chooses at creation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum ScoringPreset {
+    /// `STANDARD_SCORING`
+    #[default]
+    Standard,
+    /// Surviving ships and hull count for more, territory for less
+    Warfare,
+    /// Territory counts for double
+    Territorial,
+    /// The hold counts for triple; ships for half
+    Mercantile,
+    /// Creator-chosen weights, checked against the bounds
+    Custom(ScoringWeights),
+}
+
+impl ScoringPreset {
+    pub fn weights(&self) -> ScoringWeights {
+        match self {
+            ScoringPreset::Standard => STANDARD_SCORING,
+            ScoringPreset::Warfare => ScoringWeights {
+                ship: 200,
+                health: 4,
+                territory: 75,
+                resources: 1,
+            },
+            ScoringPreset::Territorial => ScoringWeights {
+                territory: 300,
+                ..STANDARD_SCORING
+            },
+            ScoringPreset::Mercantile => ScoringWeights {
+                ship: 50,
+                resources: 3,
+                ..STANDARD_SCORING
+            },
+            ScoringPreset::Custom(weights) => *weights,
+        }
+    }
+}
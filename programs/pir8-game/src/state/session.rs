@@ -0,0 +1,132 @@
+use crate::errors::GameError;
+use crate::state::game::{GameStatus, PirateGame};
+use crate::state::player::PlayerRole;
+use anchor_lang::prelude::*;
+
+pub const FEE_SESSION_SEED: &[u8] = b"fee_session";
+
+// ============================================================================
+// SPONSORED FEE SESSIONS
+// ============================================================================
+//
+// A captain in a running game can carve a small fee budget out of the pot
+// for their delegate key. Solana only accepts a system-owned signer as fee
+// payer, so the delegate fronts each transaction fee and the session
+// reimburses it from the budget. Whatever is left always returns to the pot
+// when the session is closed: by its owner at any time, or by anyone once
+// the game is over. Payouts wait until every session is closed, so the
+// unspent budget is divided with the rest of the pot. Each seat may fund
+// one session per game, so closing and reopening cannot keep drawing on
+// a pot the other players paid into.
+
+/// Budget moved from the pot into a session (0.001 SOL, ~200 signatures)
+pub const FEE_SESSION_BUDGET_LAMPORTS: u64 = 1_000_000;
+
+/// Reimbursement per sponsored transaction (one base signature fee)
+pub const SPONSORED_TX_FEE_LAMPORTS: u64 = 5_000;
+
+#[account]
+pub struct FeeSession {
+    pub game: Pubkey,
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    pub budget_lamports: u64,
+    pub spent_lamports: u64,
+    pub bump: u8,
+}
+
+impl FeeSession {
+    pub const SPACE: usize = 8 + 32 + 32 + 32 + 8 + 8 + 1;
+
+    pub fn remaining_lamports(&self) -> u64 {
+        self.budget_lamports.saturating_sub(self.spent_lamports)
+    }
+}
+
+/// Book a new session's budget out of the pot for `owner`'s seat and mark
+/// the seat's one session as used. The caller moves the lamports.
+pub fn take_fee_budget(game: &mut PirateGame, owner: &Pubkey) -> Result<u64> {
+    require!(game.status == GameStatus::Active, GameError::GameNotActive);
+    let budget = FEE_SESSION_BUDGET_LAMPORTS.min(game.total_pot);
+    let seat = game
+        .get_player_mut(owner)
+        .ok_or(GameError::PlayerNotInGame)?;
+    require!(
+        seat.role == PlayerRole::Captain,
+        GameError::SpectatorCannotAct
+    );
+    require!(!seat.fee_session_opened, GameError::FeeSessionUsed);
+    require!(budget > 0, GameError::FeeSessionUnfunded);
+    seat.fee_session_opened = true;
+
+    game.total_pot -= budget;
+    game.ledger.pay_out(budget);
+    game.open_fee_sessions += 1;
+    Ok(budget)
+}
+
+/// Book a closing session's unspent budget back into the game. After
+/// completion it joins the pot the payout schedule divides.
+pub fn refund_fee_budget(game: &mut PirateGame, unspent: u64) {
+    game.total_pot = game.total_pot.saturating_add(unspent);
+    if game.status == GameStatus::Completed {
+        game.payout_pot = game.payout_pot.saturating_add(unspent);
+    }
+    game.ledger.deposit(unspent);
+    game.open_fee_sessions = game.open_fee_sessions.saturating_sub(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::GameBuilder;
+
+    #[test]
+    fn unspent_budget_returns_to_the_payouts() {
+        let mut game = GameBuilder::new(2).build();
+        game.total_pot = 10 * FEE_SESSION_BUDGET_LAMPORTS;
+        game.ledger.deposit(game.total_pot);
+
+        // Opening moves the budget out; the delegate spends some of it
+        let owner = game.players[0].pubkey;
+        assert_eq!(
+            take_fee_budget(&mut game, &owner),
+            Ok(FEE_SESSION_BUDGET_LAMPORTS)
+        );
+        let unspent = FEE_SESSION_BUDGET_LAMPORTS - 2 * SPONSORED_TX_FEE_LAMPORTS;
+
+        game.status = GameStatus::Completed;
+        game.payout_pot = game.total_pot;
+        refund_fee_budget(&mut game, unspent);
+
+        let spent = 2 * SPONSORED_TX_FEE_LAMPORTS;
+        assert_eq!(game.total_pot, 10 * FEE_SESSION_BUDGET_LAMPORTS - spent);
+        assert_eq!(game.payout_pot, game.total_pot);
+        assert_eq!(game.ledger.balance(), Some(game.total_pot));
+        assert_eq!(game.open_fee_sessions, 0);
+    }
+
+    #[test]
+    fn each_seat_funds_one_session_per_game() {
+        let mut game = GameBuilder::new(2).build();
+        game.total_pot = 10 * FEE_SESSION_BUDGET_LAMPORTS;
+        game.ledger.deposit(game.total_pot);
+        let (first, second) = (game.players[0].pubkey, game.players[1].pubkey);
+
+        take_fee_budget(&mut game, &first).unwrap();
+        refund_fee_budget(&mut game, FEE_SESSION_BUDGET_LAMPORTS);
+
+        // Closing the session does not let its owner open another
+        assert_eq!(
+            take_fee_budget(&mut game, &first),
+            Err(GameError::FeeSessionUsed.into())
+        );
+        assert_eq!(game.total_pot, 10 * FEE_SESSION_BUDGET_LAMPORTS);
+        assert_eq!(game.open_fee_sessions, 0);
+
+        // The other seats still have theirs
+        take_fee_budget(&mut game, &second).unwrap();
+        assert_eq!(game.total_pot, 9 * FEE_SESSION_BUDGET_LAMPORTS);
+        assert_eq!(game.open_fee_sessions, 1);
+    }
+}
@@ -0,0 +1,124 @@
+use crate::constants::*;
+use crate::events::ShipBuilt;
+use crate::state::game::PirateGame;
+use crate::state::player::{ShipData, ShipType};
+use anchor_lang::prelude::*;
+
+// ============================================================================
+// SHIPYARD QUEUE
+// ============================================================================
+//
+// Small ships launch immediately. Galleons and Flagships are paid for up
+// front and spend several rounds on the slipway; each port builds one ship
+// at a time. Orders advance during round rollover (not while the port is
+// blockaded) and launch once the port is still held by the builder, the
+// berth is clear and the fleet has room.
+
+/// Share of the build cost returned when an order is cancelled (50%)
+pub const CONSTRUCTION_REFUND_BPS: u32 = 5_000;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ConstructionOrder {
+    pub owner: Pubkey,
+    pub ship_type: ShipType,
+    pub port_x: u8,
+    pub port_y: u8,
+    pub turns_remaining: u8,
+}
+
+/// Rounds a ship spends under construction; zero means it launches instantly
+pub fn build_turns(ship_type: &ShipType) -> u8 {
+    match ship_type {
+        ShipType::Sloop | ShipType::Frigate => 0,
+        ShipType::Galleon => 2,
+        ShipType::Flagship => 3,
+    }
+}
+
+/// Count down every order and launch the ones that are finished
+pub fn advance_construction(game: &mut PirateGame) {
+    let turn_number = game.turn_number;
+    let game_id = game.game_id;
+    let mut index = 0;
+
+    while index < game.construction_queue.len() {
+        let port_index = {
+            let order = &game.construction_queue[index];
+            order.port_x as usize * MAP_SIZE + order.port_y as usize
+        };
+        let blockaded = game
+            .territory_map
+            .get(port_index)
+            .map(|cell| cell.is_blockaded(turn_number))
+            .unwrap_or(false);
+        if blockaded {
+            // Blockaded yards make no progress
+            index += 1;
+            continue;
+        }
+
+        let order = &mut game.construction_queue[index];
+        order.turns_remaining = order.turns_remaining.saturating_sub(1);
+        if order.turns_remaining > 0 {
+            index += 1;
+            continue;
+        }
+
+        let order = order.clone();
+        let port_held = game
+            .territory_map
+            .get(port_index)
+            .map(|cell| cell.owner == Some(order.owner))
+            .unwrap_or(false);
+        let berth_clear = !game
+            .players
+            .iter()
+            .flat_map(|p| p.ships.iter())
+            .any(|s| s.position_x == order.port_x && s.position_y == order.port_y);
+
+        let (health, attack, defense, speed) = game.rules.ship_stats(&order.ship_type);
+        let Some(player) = game
+            .players
+            .iter_mut()
+            .find(|p| p.pubkey == order.owner && p.is_active)
+        else {
+            // Owner left the game; the order is abandoned
+            game.construction_queue.remove(index);
+            continue;
+        };
+
+        if !port_held || !berth_clear || player.ships.len() >= MAX_SHIPS_PER_PLAYER {
+            // Finished hull waits in the yard until it can launch
+            index += 1;
+            continue;
+        }
+
+        player.ships.push(ShipData {
+            id: format!("{}_{}_{}", order.owner, turn_number, player.ships.len()),
+            ship_type: order.ship_type.clone(),
+            health,
+            max_health: health,
+            attack,
+            defense,
+            speed,
+            position_x: order.port_x,
+            position_y: order.port_y,
+            last_action_turn: turn_number,
+            is_designated_flagship: false,
+            cargo_gold: 0,
+            skin_mint: None,
+        });
+
+        if !game.compact_events {
+            emit!(ShipBuilt {
+                game_id,
+                player: order.owner,
+                ship_type: order.ship_type,
+                position_x: order.port_x,
+                position_y: order.port_y,
+            });
+        }
+
+        game.construction_queue.remove(index);
+    }
+}
@@ -0,0 +1,526 @@
+use crate::constants::*;
+use crate::events::{RoundResolved, ShipAttacked, ShipMoved};
+use crate::state::contract::credit_sinking;
+use crate::state::distance::DistanceMetric;
+use crate::state::game::PirateGame;
+use crate::state::player::{
+    calculate_attack_damage, get_ambush_damage_bonus, record_action, PlayerRole,
+};
+use crate::state::profile::{grant_xp, XP_ATTACK, XP_MOVE, XP_SINK};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+// ============================================================================
+// SIMULTANEOUS (WEGO) ROUNDS
+// ============================================================================
+//
+// Every active player commits a hash of their orders for the round, then
+// reveals them. Once the last reveal arrives the round resolves in two
+// phases, both processed in initiative order (ship speed, then a tiebreak
+// drawn from the game's RngStream): movement first, then combat against post-movement positions.
+//
+// Nothing about resolution depends on which transaction landed first. Orders
+// are gathered in seat order, and each tiebreak draw is mixed with entropy
+// from the round's commitments and revealed salts, which no one can know
+// before the last commitment is in, so neither the public seed nor a block
+// producer reordering reveals decides who acts first.
+// A captain who hasn't revealed once the reveal window closes passes the
+// round: their commitment stands but their orders are empty. Likewise a
+// captain who hasn't committed once the commit window closes sits the round
+// out, so no single seat can hold a round, and the pot, open forever.
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum RoundPhase {
+    Commit,
+    Reveal,
+}
+
+/// Reference to a ship by its position in a player's fleet at round start
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ShipRef {
+    pub player_index: u8,
+    pub ship_index: u8,
+}
+
+/// Orders for one ship: where to sail, then optionally who to fire on
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct ShipOrder {
+    pub ship_index: u8,
+    pub to_x: u8,
+    pub to_y: u8,
+    pub via: Vec<u8>, // Map indices of the cells passed on the way, as for move_ship
+    pub attack_target: Option<ShipRef>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct OrderCommitment {
+    pub player_index: u8,
+    pub commitment: [u8; 32],
+    pub revealed: bool,
+    pub orders: Vec<ShipOrder>,
+    pub salt: [u8; 32], // Zero until revealed
+}
+
+/// Hash a player commits to: sha256(borsh(orders) || salt)
+pub fn hash_orders(orders: &[ShipOrder], salt: &[u8; 32]) -> Result<[u8; 32]> {
+    let encoded = orders.to_vec().try_to_vec()?;
+    Ok(hashv(&[&encoded, salt]).to_bytes())
+}
+
+struct ResolvedOrder {
+    player_index: usize,
+    ship_id: String,
+    to: (u8, u8),
+    via: Vec<u8>,
+    target_id: Option<String>,
+    initiative: (u32, u64),
+}
+
+/// Seed for the round's tiebreaks: the game seed mixed with every commitment
+/// and revealed salt, in seat order
+pub fn reveal_entropy(game: &PirateGame) -> u64 {
+    let mut commitments: Vec<&OrderCommitment> = game.pending_orders.iter().collect();
+    commitments.sort_by_key(|c| c.player_index);
+
+    let seed = game.seed.to_le_bytes();
+    let round = game.turn_number.to_le_bytes();
+    let mut parts: Vec<&[u8]> = vec![&seed, &round];
+    for commitment in commitments.iter() {
+        parts.push(&commitment.commitment);
+        if commitment.revealed {
+            parts.push(&commitment.salt);
+        }
+    }
+    let digest = hashv(&parts).to_bytes();
+    u64::from_le_bytes(digest[..8].try_into().unwrap())
+}
+
+/// Whether the commit window has run out, so the reveals can open without
+/// the captains still to commit
+pub fn commit_window_closed(game: &PirateGame, now: i64) -> bool {
+    game.round_phase == RoundPhase::Commit
+        && now >= game.turn_started_at.saturating_add(COMMIT_WINDOW_SECONDS)
+}
+
+/// Active captains with no commitment in for the round
+pub fn uncommitted_captains(game: &PirateGame) -> Vec<usize> {
+    game.players
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| p.is_active && p.role == PlayerRole::Captain)
+        .map(|(index, _)| index)
+        .filter(|&index| {
+            !game
+                .pending_orders
+                .iter()
+                .any(|c| c.player_index as usize == index)
+        })
+        .collect()
+}
+
+/// Open the reveal window at `now` once every active captain has committed.
+/// Re-checked whenever a captain commits or leaves.
+pub fn open_reveals_if_committed(game: &mut PirateGame, now: i64) {
+    if game.round_phase == RoundPhase::Commit && uncommitted_captains(game).is_empty() {
+        game.round_phase = RoundPhase::Reveal;
+        game.turn_started_at = now;
+    }
+}
+
+/// Whether the reveal window has run out, so the round can resolve without
+/// the captains still to reveal
+pub fn reveal_window_closed(game: &PirateGame, now: i64) -> bool {
+    game.round_phase == RoundPhase::Reveal
+        && now >= game.turn_started_at.saturating_add(REVEAL_WINDOW_SECONDS)
+}
+
+/// Resolve every revealed order for the round and roll over to the next one.
+/// Unrevealed commitments carry no orders and so pass.
+pub fn resolve_simultaneous_round(game: &mut PirateGame) -> Result<()> {
+    let round = game.turn_number;
+    let entropy = reveal_entropy(game);
+    game.pending_orders.sort_by_key(|c| c.player_index);
+
+    // Translate index-based orders into ship ids before anything moves or sinks
+    let mut resolved: Vec<ResolvedOrder> = Vec::new();
+    for commitment in game.pending_orders.iter() {
+        let player_index = commitment.player_index as usize;
+        let Some(player) = game.players.get(player_index) else {
+            continue;
+        };
+        for order in commitment.orders.iter() {
+            let Some(ship) = player.ships.get(order.ship_index as usize) else {
+                continue;
+            };
+            let target_id = order.attack_target.and_then(|t| {
+                game.players
+                    .get(t.player_index as usize)
+                    .and_then(|p| p.ships.get(t.ship_index as usize))
+                    .map(|s| s.id.clone())
+            });
+            resolved.push(ResolvedOrder {
+                player_index,
+                ship_id: ship.id.clone(),
+                to: (order.to_x, order.to_y),
+                via: order.via.clone(),
+                target_id,
+                initiative: (ship.speed, game.rng.next_u64() ^ entropy),
+            });
+        }
+    }
+
+    // Highest initiative acts first
+    resolved.sort_by_key(|o| std::cmp::Reverse(o.initiative));
+
+    let mut moves_applied = 0u8;
+    for order in resolved.iter() {
+        if resolve_movement(game, order) {
+            moves_applied += 1;
+        }
+    }
+
+    let mut attacks_applied = 0u8;
+    for order in resolved.iter() {
+        if resolve_attack(game, order) {
+            attacks_applied += 1;
+        }
+    }
+
+    emit!(RoundResolved {
+        game_id: game.game_id,
+        turn_number: round,
+        moves_applied,
+        attacks_applied,
+    });
+
+    // Roll over to the next commit phase
+    game.pending_orders.clear();
+    game.round_phase = RoundPhase::Commit;
+    game.turn_number += 1;
+    game.end_round();
+    game.warn_victory_proximity();
+
+    Ok(())
+}
+
+/// Apply one move if it is still legal, under the same route rules as
+/// `move_ship` against the positions left by the ships that acted first. A
+/// blocked route, or a destination already held by a ship (including one
+/// that moved there with higher initiative), leaves the mover in place.
+fn resolve_movement(game: &mut PirateGame, order: &ResolvedOrder) -> bool {
+    let (to_x, to_y) = order.to;
+
+    let Some((from_x, from_y, speed)) = game.players[order.player_index]
+        .ships
+        .iter()
+        .find(|s| s.id == order.ship_id)
+        .map(|s| (s.position_x, s.position_y, s.speed))
+    else {
+        return false;
+    };
+
+    if (from_x, from_y) == (to_x, to_y) {
+        return false;
+    }
+
+    let player = game.players[order.player_index].pubkey;
+    if game
+        .check_route(&player, (from_x, from_y), &order.via, order.to, speed)
+        .is_err()
+    {
+        return false;
+    }
+
+    let occupied = game
+        .players
+        .iter()
+        .flat_map(|p| p.ships.iter())
+        .any(|s| s.position_x == to_x && s.position_y == to_y);
+    if occupied {
+        return false;
+    }
+
+    let current_turn = game.turn_number;
+    let player = &mut game.players[order.player_index];
+    let mut skin_mint = None;
+    if let Some(ship) = player.ships.iter_mut().find(|s| s.id == order.ship_id) {
+        ship.position_x = to_x;
+        ship.position_y = to_y;
+        ship.last_action_turn = current_turn;
+        skin_mint = ship.skin_mint;
+    }
+    record_action(player);
+    grant_xp(player, XP_MOVE);
+
+    if !game.compact_events {
+        emit!(ShipMoved {
+            game_id: game.game_id,
+            player: player.pubkey,
+            ship_id: order.ship_id.clone(),
+            from_x,
+            from_y,
+            to_x,
+            to_y,
+            skin_mint,
+        });
+    }
+
+    true
+}
+
+/// Fire on the ordered target if both ships survive and are adjacent after movement
+fn resolve_attack(game: &mut PirateGame, order: &ResolvedOrder) -> bool {
+    let Some(target_id) = order.target_id.as_ref() else {
+        return false;
+    };
+
+    let attacker_player = &game.players[order.player_index];
+    let Some((attack, ax, ay)) = attacker_player
+        .ships
+        .iter()
+        .find(|s| s.id == order.ship_id)
+        .map(|s| (s.attack, s.position_x, s.position_y))
+    else {
+        return false;
+    };
+    let attacker = attacker_player.pubkey;
+    let ambush_bonus = get_ambush_damage_bonus(attacker_player);
+    let metric = DistanceMetric::for_mode(&game.mode);
+
+    let mut outcome = None;
+    for (player_index, player) in game.players.iter_mut().enumerate() {
+        if player_index == order.player_index {
+            continue; // Can't attack own ships
+        }
+        if let Some(ship) = player.ships.iter_mut().find(|s| &s.id == target_id) {
+            if metric.between((ship.position_x, ship.position_y), (ax, ay)) > 1 {
+                return false;
+            }
+            let damage = calculate_attack_damage(attack, ship.defense, ambush_bonus);
+            ship.health = ship.health.saturating_sub(damage);
+            outcome = Some((damage, ship.health == 0, ship.ship_type.clone()));
+            break;
+        }
+    }
+
+    let Some((damage, destroyed, target_type)) = outcome else {
+        return false;
+    };
+
+    game.purge_sunk_ships();
+    if destroyed {
+        credit_sinking(game, &attacker, &target_type);
+    }
+    let xp = if destroyed {
+        XP_ATTACK + XP_SINK
+    } else {
+        XP_ATTACK
+    };
+    game.grant_xp(&attacker, xp);
+    if let Some(player) = game.get_player_mut(&attacker) {
+        record_action(player);
+    }
+
+    if !game.compact_events {
+        emit!(ShipAttacked {
+            game_id: game.game_id,
+            attacker,
+            attacker_ship_id: order.ship_id.clone(),
+            target_ship_id: target_id.clone(),
+            damage,
+            ship_destroyed: destroyed,
+        });
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::game::{GameMode, GameOptions};
+    use crate::state::map::TerritoryCellType;
+    use crate::test_utils::GameBuilder;
+
+    /// A simultaneous two-seat casual game on open water, down to one ship a
+    /// side, with the given speeds and positions
+    fn duel(speeds: [u32; 2], at: [(u8, u8); 2]) -> PirateGame {
+        let options = GameOptions {
+            simultaneous_turns: true,
+            ..GameOptions::default()
+        };
+        let mut game = GameBuilder::new(2).options(options).build();
+        for cell in game.territory_map.iter_mut() {
+            cell.cell_type = TerritoryCellType::Water;
+        }
+        for (player, (speed, (x, y))) in game.players.iter_mut().zip(speeds.into_iter().zip(at)) {
+            player.ships.truncate(1);
+            let ship = &mut player.ships[0];
+            ship.speed = speed;
+            (ship.position_x, ship.position_y) = (x, y);
+            // Sturdy enough that no test shot sinks it
+            (ship.health, ship.max_health) = (1_000, 1_000);
+        }
+        game
+    }
+
+    fn reveal(
+        game: &mut PirateGame,
+        player_index: u8,
+        via: &[(u8, u8)],
+        to: (u8, u8),
+        target: Option<u8>,
+    ) {
+        let orders = vec![ShipOrder {
+            ship_index: 0,
+            to_x: to.0,
+            to_y: to.1,
+            via: via
+                .iter()
+                .map(|&(x, y)| (x as usize * MAP_SIZE + y as usize) as u8)
+                .collect(),
+            attack_target: target.map(|player_index| ShipRef {
+                player_index,
+                ship_index: 0,
+            }),
+        }];
+        let salt = [player_index + 1; 32];
+        game.pending_orders.push(OrderCommitment {
+            player_index,
+            commitment: hash_orders(&orders, &salt).unwrap(),
+            revealed: true,
+            orders,
+            salt,
+        });
+    }
+
+    fn position(game: &PirateGame, player_index: usize) -> (u8, u8) {
+        let ship = &game.players[player_index].ships[0];
+        (ship.position_x, ship.position_y)
+    }
+
+    #[test]
+    fn the_faster_ship_takes_a_contested_cell() {
+        for (speeds, winner) in [([3, 2], 0), ([2, 3], 1)] {
+            let mut game = duel(speeds, [(0, 0), (0, 2)]);
+            reveal(&mut game, 0, &[], (0, 1), None);
+            reveal(&mut game, 1, &[], (0, 1), None);
+            let round = game.turn_number;
+            resolve_simultaneous_round(&mut game).unwrap();
+
+            assert_eq!(position(&game, winner), (0, 1));
+            let loser = 1 - winner;
+            assert_eq!(position(&game, loser), [(0, 0), (0, 2)][loser]);
+            assert_eq!(game.turn_number, round + 1);
+            assert!(game.pending_orders.is_empty());
+        }
+    }
+
+    #[test]
+    fn attacks_resolve_against_positions_after_movement() {
+        // Out of range at the start of the round, alongside once it sails
+        let mut game = duel([3, 2], [(0, 0), (0, 3)]);
+        let health = game.players[1].ships[0].health;
+        reveal(&mut game, 0, &[(0, 1)], (0, 2), Some(1));
+        resolve_simultaneous_round(&mut game).unwrap();
+        assert!(game.players[1].ships[0].health < health);
+
+        // A target that sails off in the same round escapes
+        let mut game = duel([3, 2], [(0, 0), (0, 3)]);
+        reveal(&mut game, 0, &[(0, 1)], (0, 2), Some(1));
+        reveal(&mut game, 1, &[(0, 4)], (0, 5), None);
+        resolve_simultaneous_round(&mut game).unwrap();
+        assert_eq!(position(&game, 1), (0, 5));
+        assert_eq!(game.players[1].ships[0].health, health);
+    }
+
+    #[test]
+    fn orders_beyond_a_ships_speed_are_ignored() {
+        let mut game = duel([2, 2], [(0, 0), (5, 5)]);
+        reveal(&mut game, 0, &[(0, 1), (0, 2)], (0, 3), None);
+        resolve_simultaneous_round(&mut game).unwrap();
+        assert_eq!(position(&game, 0), (0, 0));
+    }
+
+    #[test]
+    fn orders_follow_the_same_route_rules_as_move_ship() {
+        // An island in the way holds the ship where it is
+        let mut game = duel([3, 3], [(0, 0), (9, 9)]);
+        game.territory_map[1].cell_type = TerritoryCellType::Island;
+        reveal(&mut game, 0, &[(0, 1)], (0, 2), None);
+        resolve_simultaneous_round(&mut game).unwrap();
+        assert_eq!(position(&game, 0), (0, 0));
+
+        // So does a route that jumps a cell
+        let mut game = duel([3, 3], [(0, 0), (9, 9)]);
+        reveal(&mut game, 0, &[], (0, 2), None);
+        resolve_simultaneous_round(&mut game).unwrap();
+        assert_eq!(position(&game, 0), (0, 0));
+
+        // In ranked modes, sailing on through an enemy's zone of control
+        let mut game = duel([3, 3], [(0, 0), (1, 2)]);
+        game.mode = GameMode::Competitive;
+        reveal(&mut game, 0, &[(0, 1), (0, 2)], (0, 3), None);
+        resolve_simultaneous_round(&mut game).unwrap();
+        assert_eq!(position(&game, 0), (0, 0));
+        // ...while stopping at its edge is allowed
+        let mut game = duel([3, 3], [(0, 0), (1, 2)]);
+        game.mode = GameMode::Competitive;
+        reveal(&mut game, 0, &[(0, 1)], (0, 2), None);
+        resolve_simultaneous_round(&mut game).unwrap();
+        assert_eq!(position(&game, 0), (0, 2));
+    }
+
+    #[test]
+    fn reveals_open_once_every_captain_still_playing_has_committed() {
+        let options = GameOptions {
+            simultaneous_turns: true,
+            ..GameOptions::default()
+        };
+        let mut game = GameBuilder::new(3).options(options).build();
+        game.turn_started_at = 100;
+        for player_index in [0, 1] {
+            game.pending_orders.push(OrderCommitment {
+                player_index,
+                commitment: [player_index + 1; 32],
+                revealed: false,
+                orders: Vec::new(),
+                salt: [0; 32],
+            });
+        }
+
+        assert_eq!(uncommitted_captains(&game), [2]);
+        open_reveals_if_committed(&mut game, 110);
+        assert_eq!(game.round_phase, RoundPhase::Commit);
+        assert!(!commit_window_closed(
+            &game,
+            100 + COMMIT_WINDOW_SECONDS - 1
+        ));
+        assert!(commit_window_closed(&game, 100 + COMMIT_WINDOW_SECONDS));
+
+        // The straggler leaves: nobody is left to wait for
+        game.players[2].is_active = false;
+        open_reveals_if_committed(&mut game, 120);
+        assert_eq!(game.round_phase, RoundPhase::Reveal);
+        assert_eq!(game.turn_started_at, 120);
+        assert!(!commit_window_closed(&game, i64::MAX));
+    }
+
+    #[test]
+    fn commitments_bind_both_orders_and_salt() {
+        let orders = [ShipOrder {
+            ship_index: 0,
+            to_x: 1,
+            to_y: 1,
+            via: Vec::new(),
+            attack_target: None,
+        }];
+        let hash = hash_orders(&orders, &[1; 32]).unwrap();
+        assert_ne!(hash_orders(&orders, &[2; 32]).unwrap(), hash);
+        let moved = [ShipOrder {
+            to_y: 2,
+            ..orders[0].clone()
+        }];
+        assert_ne!(hash_orders(&moved, &[1; 32]).unwrap(), hash);
+    }
+}
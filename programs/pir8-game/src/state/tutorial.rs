@@ -0,0 +1,301 @@
+use crate::constants::*;
+use crate::events::TutorialCheckpointReached;
+use crate::state::config::DEFAULT_RESOURCE_MULTIPLIERS_BPS;
+use crate::state::game::{
+    GameMode, GameObjective, GameOptions, GameStatus, PirateGame, SpawnPosition, WeatherType,
+};
+use crate::state::ledger::VaultLedger;
+use crate::state::map::{TerritoryCell, TerritoryCellType, TREASURE_GOLD_POOL};
+use crate::state::player::{
+    get_ship_stats, initialize_player, PlayerData, PlayerRole, ShipData, ShipType,
+};
+use crate::state::rng::{RngAudit, RngStream};
+use crate::state::rulebook::RulebookParams;
+use crate::state::scoring::STANDARD_SCORING;
+use crate::state::simultaneous::RoundPhase;
+use anchor_lang::prelude::*;
+
+// ============================================================================
+// TUTORIAL
+// ============================================================================
+//
+// A single-player walkthrough on a fixed map: no seed, no fees and no second
+// player. The learner sits in seat 0 with a Sloop and a Frigate; seat 1 is an
+// unowned, inactive derelict that never takes a turn and exists only to be
+// shot at. Checkpoints are checked in order at the end of every round and the
+// count reached is kept on the game account; once all are reached,
+// `check_and_complete_game` awards the learner the win. Tutorials are untimed
+// and never count towards a profile.
+//
+// The layout, with x across and y down:
+//
+//        0 1 2 3 4 5 6 7 8 9
+//     0  ~ ~ ~ ~ ~ ~ ~ ~ ~ ~      ~  Water      #  Island
+//     1  ~ s ~ P ~ ~ ~ ~ ~ ~      P  Port       $  Treasure
+//     2  ~ f ~ ~ ~ ~ ~ # ~ ~      S  Storm      R  Reef
+//     3  ~ ~ ~ ~ ~ d ~ ~ ~ ~
+//     4  ~ ~ ~ ~ ~ $ ~ ~ ~ ~      s  learner's Sloop
+//     5  ~ ~ # ~ ~ ~ ~ ~ ~ ~      f  learner's Frigate
+//     6  ~ ~ ~ ~ ~ ~ ~ ~ S ~      d  derelict Sloop
+//     7  ~ ~ ~ ~ ~ ~ ~ ~ ~ ~
+//     8  ~ ~ ~ ~ R ~ ~ ~ ~ ~
+//     9  ~ ~ ~ ~ ~ ~ ~ ~ ~ ~
+
+/// Map rows, top to bottom; ships are placed separately
+const TUTORIAL_LAYOUT: [&[u8; MAP_SIZE]; MAP_SIZE] = [
+    b"~~~~~~~~~~",
+    b"~~~P~~~~~~",
+    b"~~~~~~~#~~",
+    b"~~~~~~~~~~",
+    b"~~~~~$~~~~",
+    b"~~#~~~~~~~",
+    b"~~~~~~~~S~",
+    b"~~~~~~~~~~",
+    b"~~~~R~~~~~",
+    b"~~~~~~~~~~",
+];
+
+/// The Port the learner sails to, claims, collects from and builds at
+pub const TUTORIAL_PORT: (u8, u8) = (3, 1);
+
+/// The learner's starting fleet and where each ship starts
+pub const TUTORIAL_FLEET: [(ShipType, (u8, u8)); 2] =
+    [(ShipType::Sloop, (1, 1)), (ShipType::Frigate, (1, 2))];
+
+/// The target for the gunnery lesson
+pub const TUTORIAL_DERELICT: (ShipType, (u8, u8)) = (ShipType::Sloop, (5, 3));
+
+/// Seats: the learner plays, the derelict only owns the target
+pub const TUTORIAL_LEARNER_SEAT: usize = 0;
+pub const TUTORIAL_DERELICT_SEAT: usize = 1;
+
+/// One lesson, reached once the board shows it has been done
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum TutorialCheckpoint {
+    /// End a turn with a ship on the tutorial Port
+    SetSail,
+    /// Claim the tutorial Port
+    ClaimPort,
+    /// Collect income from controlled territory
+    CollectResources,
+    /// Scan any cell
+    ScanWaters,
+    /// Launch or lay down a new ship
+    BuildShip,
+    /// Damage or sink the derelict
+    FireCannons,
+}
+
+/// Every checkpoint, in the order the learner must reach them
+pub const TUTORIAL_CHECKPOINTS: [TutorialCheckpoint; 6] = [
+    TutorialCheckpoint::SetSail,
+    TutorialCheckpoint::ClaimPort,
+    TutorialCheckpoint::CollectResources,
+    TutorialCheckpoint::ScanWaters,
+    TutorialCheckpoint::BuildShip,
+    TutorialCheckpoint::FireCannons,
+];
+
+impl TutorialCheckpoint {
+    pub fn is_reached(&self, game: &PirateGame) -> bool {
+        let (Some(learner), Some(derelict)) = (
+            game.players.get(TUTORIAL_LEARNER_SEAT),
+            game.players.get(TUTORIAL_DERELICT_SEAT),
+        ) else {
+            return false;
+        };
+        let (port_x, port_y) = TUTORIAL_PORT;
+
+        match self {
+            TutorialCheckpoint::SetSail => learner
+                .ships
+                .iter()
+                .any(|s| (s.position_x, s.position_y) == TUTORIAL_PORT),
+            TutorialCheckpoint::ClaimPort => game
+                .territory_map
+                .get(port_x as usize * MAP_SIZE + port_y as usize)
+                .map(|cell| cell.owner == Some(learner.pubkey))
+                .unwrap_or(false),
+            TutorialCheckpoint::CollectResources => learner.last_collected_turn > 0,
+            TutorialCheckpoint::ScanWaters => !learner.scanned_coordinates.is_empty(),
+            TutorialCheckpoint::BuildShip => {
+                learner.ships.len() > TUTORIAL_FLEET.len()
+                    || game
+                        .construction_queue
+                        .iter()
+                        .any(|o| o.owner == learner.pubkey)
+            }
+            TutorialCheckpoint::FireCannons => {
+                derelict.ships.iter().all(|s| s.health < s.max_health)
+            }
+        }
+    }
+}
+
+/// The fixed tutorial map
+pub fn tutorial_map() -> Vec<TerritoryCell> {
+    let mut map = Vec::with_capacity(MAP_SIZE * MAP_SIZE);
+    for x in 0..MAP_SIZE {
+        for row in TUTORIAL_LAYOUT.iter() {
+            let (cell_type, remaining_yield) = match row[x] {
+                b'#' => (TerritoryCellType::Island, 0),
+                b'P' => (TerritoryCellType::Port, 0),
+                b'$' => (TerritoryCellType::Treasure, TREASURE_GOLD_POOL),
+                b'S' => (TerritoryCellType::Storm, 0),
+                b'R' => (TerritoryCellType::Reef, 0),
+                _ => (TerritoryCellType::Water, 0),
+            };
+            map.push(TerritoryCell {
+                cell_type,
+                owner: None,
+                remaining_yield,
+                blockaded_until_turn: 0,
+            });
+        }
+    }
+    map
+}
+
+fn tutorial_ship(
+    owner: &Pubkey,
+    now: i64,
+    slot: usize,
+    ship_type: &ShipType,
+    at: (u8, u8),
+) -> ShipData {
+    let (health, attack, defense, speed) = get_ship_stats(ship_type);
+    ShipData {
+        id: format!("{}_{}_{}", owner, now, slot),
+        ship_type: ship_type.clone(),
+        health,
+        max_health: health,
+        attack,
+        defense,
+        speed,
+        position_x: at.0,
+        position_y: at.1,
+        last_action_turn: 0,
+        is_designated_flagship: false,
+        cargo_gold: 0,
+        skin_mint: None,
+    }
+}
+
+/// A tutorial already under way, with the learner to move in round one
+pub fn tutorial_game(game_id: u64, learner: Pubkey, bump: u8, now: i64) -> PirateGame {
+    let options = GameOptions {
+        objective: GameObjective::Tutorial,
+        ..GameOptions::default()
+    };
+
+    let mut player = initialize_player(learner, &options.loadout.resources);
+    let mut spawn_positions = Vec::new();
+    for (slot, (ship_type, at)) in TUTORIAL_FLEET.iter().enumerate() {
+        player
+            .ships
+            .push(tutorial_ship(&learner, now, slot, ship_type, *at));
+        spawn_positions.push(SpawnPosition {
+            player_index: TUTORIAL_LEARNER_SEAT as u8,
+            x: at.0,
+            y: at.1,
+        });
+    }
+
+    let (ship_type, at) = &TUTORIAL_DERELICT;
+    let derelict = PlayerData {
+        ships: vec![tutorial_ship(&Pubkey::default(), now, 0, ship_type, *at)],
+        role: PlayerRole::Spectator,
+        ..PlayerData::default()
+    };
+    spawn_positions.push(SpawnPosition {
+        player_index: TUTORIAL_DERELICT_SEAT as u8,
+        x: at.0,
+        y: at.1,
+    });
+
+    PirateGame {
+        game_id,
+        authority: learner,
+        status: GameStatus::Active,
+        mode: GameMode::Casual,
+        player_count: 2,
+        current_player_index: TUTORIAL_LEARNER_SEAT as u8,
+        turn_number: 1,
+        created_at: now,
+        started_at: Some(now),
+        completed_at: None,
+        winner: None,
+        weather_type: WeatherType::Calm,
+        weather_duration: 2,
+        bump,
+        total_pot: 0,
+        token_pot: 0,
+        ledger: VaultLedger::default(),
+        tips_total: 0,
+        options,
+        seed: 0,
+        rng_audit: RngAudit::default(),
+        rng: RngStream::default(),
+        start_reveal_slot: None,
+        turn_order: vec![TUTORIAL_LEARNER_SEAT as u8, TUTORIAL_DERELICT_SEAT as u8],
+        turn_slot: 0,
+        turn_started_at: now,
+        scanned_this_turn: false,
+        parley_this_turn: false,
+        round_phase: RoundPhase::Commit,
+        pending_orders: Vec::new(),
+        spawn_positions,
+        construction_queue: Vec::new(),
+        contracts: Vec::new(),
+        next_contract_id: 0,
+        overtime_players: Vec::new(),
+        standings: Vec::new(),
+        payout_claims: 0,
+        final_state_hash: [0; 32],
+        payout_pot: 0,
+        payout_token_pot: 0,
+        creator_fee_due: 0,
+        rebate_pool: 0,
+        vesting: Vec::new(),
+        open_fee_sessions: 0,
+        pending_jackpot: None,
+        stake_raises: 0,
+        pending_raise: None,
+        pending_resignation: None,
+        tutorial_checkpoint: 0,
+        forked_from: None,
+        signer_log: vec![learner],
+        resource_multipliers_bps: DEFAULT_RESOURCE_MULTIPLIERS_BPS,
+        scoring: STANDARD_SCORING,
+        compact_events: false,
+        resign_grace: false,
+        pity_bonus_gold: 0,
+        vesting_threshold_lamports: None,
+        rulebook_version: 0,
+        rules: RulebookParams::default(),
+        players: vec![player, derelict],
+        territory_map: tutorial_map(),
+    }
+}
+
+/// Record every checkpoint the learner has reached, in order
+pub fn advance_tutorial(game: &mut PirateGame) {
+    while let Some(checkpoint) = TUTORIAL_CHECKPOINTS.get(game.tutorial_checkpoint as usize) {
+        if !checkpoint.is_reached(game) {
+            break;
+        }
+        game.tutorial_checkpoint += 1;
+
+        emit!(TutorialCheckpointReached {
+            game_id: game.game_id,
+            player: game.players[TUTORIAL_LEARNER_SEAT].pubkey,
+            checkpoint: *checkpoint,
+            checkpoints_reached: game.tutorial_checkpoint,
+        });
+    }
+}
+
+/// Whether the learner has worked through every checkpoint
+pub fn tutorial_finished(game: &PirateGame) -> bool {
+    game.tutorial_checkpoint as usize >= TUTORIAL_CHECKPOINTS.len()
+}
@@ -0,0 +1,89 @@
+use crate::constants::BASIS_POINTS;
+use anchor_lang::prelude::*;
+
+// ============================================================================
+// PRIZE VESTING
+// ============================================================================
+//
+// When a game's SOL pot is above the threshold it copied from GameConfig at
+// creation, each winner's payout is released in two tranches: half when they
+// claim, half a week later. The held tranche stays in the game account and in
+// total_pot until released, so an exploited high-stakes game can be stopped
+// before the whole pot walks out: the config authority may freeze an unvested
+// tranche while it investigates, but never what has already been paid. A
+// freeze lapses on its own 30 days after the tranche unlocks, so a dispute
+// nobody resolves cannot hold the prize, or the game account, forever.
+
+/// Share of a vesting payout held back at claim time (50%)
+pub const VESTED_SHARE_BPS: u32 = 5_000;
+
+/// Delay before a held tranche may be released (7 days)
+pub const VESTING_DELAY_SECONDS: i64 = 7 * 24 * 60 * 60;
+
+/// Longest a freeze may hold a tranche past its unlock (30 days)
+pub const MAX_VESTING_FREEZE_SECONDS: i64 = 30 * 24 * 60 * 60;
+
+/// The unvested half of one winner's payout
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct VestingTranche {
+    pub player: Pubkey,
+    pub recipient: Pubkey, // Where the claim sent the first tranche
+    pub amount: u64,
+    pub unlocks_at: i64,
+    pub frozen_until: i64, // Set by the config authority while a dispute is open; 0 when thawed
+}
+
+impl VestingTranche {
+    pub const SPACE: usize = 32 + 32 + 8 + 8 + 8;
+
+    /// When a freeze lapses, however the dispute stands
+    pub fn freeze_deadline(&self) -> i64 {
+        self.unlocks_at.saturating_add(MAX_VESTING_FREEZE_SECONDS)
+    }
+
+    pub fn is_frozen(&self, now: i64) -> bool {
+        now < self.frozen_until
+    }
+
+    pub fn is_releasable(&self, now: i64) -> bool {
+        !self.is_frozen(now) && now >= self.unlocks_at
+    }
+}
+
+/// Lamports of a payout to hold back, given the game's pot and threshold
+pub fn vested_share(payout: u64, payout_pot: u64, threshold: Option<u64>) -> u64 {
+    match threshold {
+        Some(threshold) if payout_pot > threshold => {
+            (payout as u128 * VESTED_SHARE_BPS as u128 / BASIS_POINTS as u128) as u64
+        }
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_freeze_cannot_outlast_its_deadline() {
+        let mut tranche = VestingTranche {
+            player: Pubkey::new_unique(),
+            recipient: Pubkey::new_unique(),
+            amount: 500,
+            unlocks_at: VESTING_DELAY_SECONDS,
+            frozen_until: 0,
+        };
+        tranche.frozen_until = tranche.freeze_deadline();
+
+        assert!(tranche.is_frozen(VESTING_DELAY_SECONDS));
+        assert!(!tranche.is_releasable(VESTING_DELAY_SECONDS));
+        assert!(!tranche.is_releasable(tranche.freeze_deadline() - 1));
+        // Nobody resolved the dispute; the tranche releases anyway
+        assert!(!tranche.is_frozen(tranche.freeze_deadline()));
+        assert!(tranche.is_releasable(tranche.freeze_deadline()));
+
+        // Thawing early releases on the normal schedule
+        tranche.frozen_until = 0;
+        assert!(tranche.is_releasable(VESTING_DELAY_SECONDS));
+    }
+}
@@ -0,0 +1,191 @@
+use crate::instructions::gameplay::play_batch;
+use crate::state::config::DEFAULT_RESOURCE_MULTIPLIERS_BPS;
+use crate::state::game::{
+    begin_game, GameAction, GameMode, GameOptions, GameStatus, PirateGame, StartingLoadout,
+    WeatherType,
+};
+use crate::state::ledger::VaultLedger;
+use crate::state::map::TerritoryCell;
+use crate::state::player::initialize_player;
+use crate::state::rng::{RngAudit, RngStream};
+use crate::state::rulebook::RulebookParams;
+use crate::state::scoring::STANDARD_SCORING;
+use crate::state::simultaneous::RoundPhase;
+use anchor_lang::prelude::*;
+
+// ============================================================================
+// TEST UTILITIES
+// ============================================================================
+//
+// Builders for fully populated games and a way to play turns through the
+// program's own logic, without a validator. Compiled into the crate's tests
+// and, behind the `test-utils` feature, exported for downstream crates such
+// as agent developers' integration suites:
+//
+//     let mut game = GameBuilder::new(2).seed(7).build();
+//     let actions = vec![GameAction::CollectResources, GameAction::EndTurn];
+//     play_turn(&mut game, actions, 10).unwrap();
+//
+// Seats get fresh random wallets and no lamports move: these are account
+// states, not accounts, so the vault checks in `invariants` do not apply.
+
+/// A lobby of `player_count` seats, waiting to start
+pub fn waiting_game(player_count: u8) -> PirateGame {
+    PirateGame {
+        game_id: 1,
+        authority: Pubkey::new_unique(),
+        status: GameStatus::Waiting,
+        mode: GameMode::Casual,
+        player_count,
+        current_player_index: 0,
+        turn_number: 0,
+        created_at: 0,
+        started_at: None,
+        completed_at: None,
+        winner: None,
+        weather_type: WeatherType::Calm,
+        weather_duration: 2,
+        bump: 255,
+        total_pot: 0,
+        token_pot: 0,
+        ledger: VaultLedger::default(),
+        tips_total: 0,
+        options: GameOptions::default(),
+        seed: 0,
+        rng_audit: RngAudit::default(),
+        rng: RngStream::default(),
+        start_reveal_slot: None,
+        turn_order: Vec::new(),
+        turn_slot: 0,
+        turn_started_at: 0,
+        scanned_this_turn: false,
+        parley_this_turn: false,
+        round_phase: RoundPhase::Commit,
+        pending_orders: Vec::new(),
+        spawn_positions: Vec::new(),
+        construction_queue: Vec::new(),
+        contracts: Vec::new(),
+        next_contract_id: 0,
+        overtime_players: Vec::new(),
+        standings: Vec::new(),
+        payout_claims: 0,
+        final_state_hash: [0; 32],
+        payout_pot: 0,
+        payout_token_pot: 0,
+        creator_fee_due: 0,
+        rebate_pool: 0,
+        vesting: Vec::new(),
+        open_fee_sessions: 0,
+        pending_jackpot: None,
+        stake_raises: 0,
+        pending_raise: None,
+        pending_resignation: None,
+        tutorial_checkpoint: 0,
+        forked_from: None,
+        signer_log: Vec::new(),
+        resource_multipliers_bps: DEFAULT_RESOURCE_MULTIPLIERS_BPS,
+        scoring: STANDARD_SCORING,
+        compact_events: false,
+        resign_grace: false,
+        pity_bonus_gold: 0,
+        vesting_threshold_lamports: None,
+        rulebook_version: 0,
+        rules: RulebookParams::default(),
+        players: (0..player_count)
+            .map(|_| initialize_player(Pubkey::new_unique(), &StartingLoadout::default().resources))
+            .collect(),
+        territory_map: Vec::new(),
+    }
+}
+
+/// Builds a game already in play
+pub struct GameBuilder {
+    player_count: u8,
+    options: GameOptions,
+    mode: GameMode,
+    seed: u64,
+    started_at: i64,
+    turn_number: Option<u32>,
+    territory_map: Option<Vec<TerritoryCell>>,
+}
+
+impl GameBuilder {
+    pub fn new(player_count: u8) -> Self {
+        Self {
+            player_count,
+            options: GameOptions::default(),
+            mode: GameMode::Casual,
+            seed: 0,
+            started_at: 0,
+            turn_number: None,
+            territory_map: None,
+        }
+    }
+
+    pub fn options(mut self, options: GameOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn mode(mut self, mode: GameMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Seed for the map, seat order and every later roll
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Clock time the game starts at; the first turn's clock starts here
+    pub fn started_at(mut self, now: i64) -> Self {
+        self.started_at = now;
+        self
+    }
+
+    /// Jump to round `turn_number`, e.g. to test sudden death or tip cutoffs
+    pub fn turn(mut self, turn_number: u32) -> Self {
+        self.turn_number = Some(turn_number);
+        self
+    }
+
+    /// Play on this map instead of the one generated from the seed. Fleets
+    /// are still deployed on the generated map's spawns.
+    pub fn map(mut self, territory_map: Vec<TerritoryCell>) -> Self {
+        self.territory_map = Some(territory_map);
+        self
+    }
+
+    pub fn build(self) -> PirateGame {
+        let mut game = waiting_game(self.player_count);
+        game.mode = self.mode;
+        game.options = self.options;
+        game.adopt_rulebook(None);
+        begin_game(&mut game, self.seed, self.started_at, 0).expect("game failed to start");
+        if let Some(turn_number) = self.turn_number {
+            game.turn_number = turn_number;
+        }
+        if let Some(territory_map) = self.territory_map {
+            game.territory_map = territory_map;
+        }
+        game
+    }
+}
+
+/// Wallet of the seat whose turn it is
+pub fn current_player(game: &PirateGame) -> Pubkey {
+    game.players[game.current_player_index as usize].pubkey
+}
+
+/// Play `actions` as the current player's turn at clock time `now`, through
+/// the same logic as the `batch_actions` instruction: the turn is checked,
+/// the time bank charged, the batch applied and the turn passed on. The
+/// seat's next nonce is used. A failed turn leaves the game untouched, as a
+/// failed transaction would.
+pub fn play_turn(game: &mut PirateGame, actions: Vec<GameAction>, now: i64) -> Result<()> {
+    let player = current_player(game);
+    let nonce = game.players[game.current_player_index as usize].action_nonce;
+    let before = game.clone();
+    play_batch(game, player, actions, nonce, now).inspect_err(|_| *game = before)
+}
@@ -4,7 +4,7 @@ import { PublicKey } from '@solana/web3.js';
 const DEFAULT_RESOURCES: Resources = {
   gold: 1000,
   crew: 50,
-  cannons: 20,
+  cannons: 10,
   supplies: 100,
   wood: 0,
   rum: 0,
@@ -0,0 +1,348 @@
+/**
+ * Compute Budget Harness
+ *
+ * Simulates every turn-based instruction against worst-case game state and
+ * fails when one consumes more than COMPUTE_UNIT_BUDGET compute units.
+ * Worst case here means a full lobby of 4 players, each starting with a
+ * 6-ship fleet (the per-player cap), on the fully generated map.
+ *
+ * Setup:
+ * 1. The provider wallet must be the program's upgrade authority so the
+ *    harness can raise the config's starting-fleet ceiling (true on localnet)
+ * 2. Run: anchor test (picked up by the tests/**\/*.ts script in Anchor.toml)
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SYSVAR_SLOT_HASHES_PUBKEY,
+  SystemProgram,
+  Transaction,
+  sendAndConfirmTransaction,
+} from "@solana/web3.js";
+import assert from "assert";
+import { Pir8Game } from "../target/types/pir8_game";
+
+const COMPUTE_UNIT_BUDGET = 200_000;
+const MAX_SHIPS_PER_PLAYER = 6;
+const MAP_SIZE = 10;
+
+const BPF_LOADER_UPGRADEABLE = new PublicKey(
+  "BPFLoaderUpgradeab1e11111111111111111111111"
+);
+
+describe("PIR8 Compute Budget", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Pir8Game as Program<Pir8Game>;
+  const gameId = new anchor.BN(Date.now());
+  const players: Keypair[] = [];
+  const usage: { instruction: string; units: number }[] = [];
+
+  const [configPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("config")],
+    program.programId
+  );
+  const [gamePda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("pirate_game"), gameId.toArrayLike(Buffer, "le", 8)],
+    program.programId
+  );
+
+  /** Simulate a transaction and return the compute units it consumed */
+  async function simulate(
+    instruction: string,
+    tx: Transaction,
+    signers: Keypair[]
+  ): Promise<number> {
+    tx.feePayer = signers[0].publicKey;
+    tx.recentBlockhash = (
+      await provider.connection.getLatestBlockhash()
+    ).blockhash;
+    tx.sign(...signers);
+
+    const { value } = await provider.connection.simulateTransaction(tx);
+    assert.strictEqual(
+      value.err,
+      null,
+      `${instruction} failed in simulation: ${JSON.stringify(value.logs)}`
+    );
+    return value.unitsConsumed ?? 0;
+  }
+
+  /** Record an instruction's usage and enforce the budget */
+  async function measure(
+    instruction: string,
+    tx: Transaction,
+    signers: Keypair[]
+  ): Promise<void> {
+    const units = await simulate(instruction, tx, signers);
+    usage.push({ instruction, units });
+    assert.ok(
+      units <= COMPUTE_UNIT_BUDGET,
+      `${instruction} used ${units} CU (budget ${COMPUTE_UNIT_BUDGET})`
+    );
+  }
+
+  async function currentPlayer(): Promise<{
+    signer: Keypair;
+    index: number;
+    nonce: anchor.BN;
+  }> {
+    const game = await program.account.pirateGame.fetch(gamePda);
+    const seat = game.players[game.currentPlayerIndex];
+    const signer = players.find((p) => p.publicKey.equals(seat.pubkey));
+    // Every gameplay instruction must carry the seat's expected action nonce
+    return { signer, index: game.currentPlayerIndex, nonce: seat.actionNonce };
+  }
+
+  /** Resolve once the cluster has moved past `slot` */
+  async function waitPastSlot(slot: number): Promise<void> {
+    while ((await provider.connection.getSlot()) <= slot) {
+      await new Promise((resolve) => setTimeout(resolve, 400));
+    }
+  }
+
+  /** First adjacent cell a ship can legally move to, found by simulation */
+  async function findLegalMove(
+    signer: Keypair,
+    ship: { id: string; positionX: number; positionY: number },
+    nonce: anchor.BN
+  ): Promise<[number, number]> {
+    for (const [dx, dy] of [
+      [1, 0],
+      [-1, 0],
+      [0, 1],
+      [0, -1],
+    ]) {
+      const x = ship.positionX + dx;
+      const y = ship.positionY + dy;
+      if (x < 0 || y < 0 || x >= MAP_SIZE || y >= MAP_SIZE) continue;
+
+      const tx = await program.methods
+        .moveShip(ship.id, x, y, Buffer.from([]), null, nonce)
+        .accountsPartial({ game: gamePda, player: signer.publicKey })
+        .transaction();
+      try {
+        await simulate("move_ship", tx, [signer]);
+        return [x, y];
+      } catch {
+        // Hazard or occupied cell; try the next neighbour
+      }
+    }
+    throw new Error(`No legal move for ship ${ship.id}`);
+  }
+
+  before(async () => {
+    // Raise the starting-fleet ceiling so every seat can start at the ship cap
+    const [programData] = PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      BPF_LOADER_UPGRADEABLE
+    );
+    try {
+      await program.methods
+        .initializeConfig()
+        .accountsPartial({
+          config: configPda,
+          program: program.programId,
+          programData,
+          authority: provider.wallet.publicKey,
+        })
+        .rpc();
+    } catch (e) {
+      console.log("Config already initialized:", e.message);
+    }
+
+    const config = await program.account.gameConfig.fetch(configPda);
+    await program.methods
+      .updateConfig({
+        ...config.params,
+        maxStartingShips: MAX_SHIPS_PER_PLAYER,
+      })
+      .accountsPartial({ config: configPda, authority: provider.wallet.publicKey })
+      .rpc();
+
+    for (let i = 0; i < 4; i++) {
+      const player = Keypair.generate();
+      const sig = await provider.connection.requestAirdrop(
+        player.publicKey,
+        2 * LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(sig);
+      players.push(player);
+    }
+  });
+
+  it("create_game with a full starting fleet", async () => {
+    const creator = players[0];
+    const [creatorProfile] = PublicKey.findProgramAddressSync(
+      [Buffer.from("profile"), creator.publicKey.toBuffer()],
+      program.programId
+    );
+    const options = {
+      serpentineTurns: false,
+      simultaneousTurns: false,
+      access: {
+        minRating: null,
+        maxRating: null,
+        minLevel: null,
+        minReputation: null,
+        agentsOnly: false,
+        minAgentGames: null,
+        minAgentBond: null,
+        inviteList: [],
+        inviteCodeHash: null,
+      },
+      loadout: {
+        resources: { gold: 5_000, crew: 200, cannons: 50, supplies: 500 },
+        fleet: [
+          { flagship: {} },
+          { galleon: {} },
+          { galleon: {} },
+          { frigate: {} },
+          { frigate: {} },
+          { sloop: {} },
+        ],
+      },
+      stake: null,
+      jackpotEligible: false,
+      tieBreak: { overtime: {} },
+      payoutSchedule: [[10_000, 0, 0, 0]],
+      creatorFeeBps: 0,
+      objective: { conquest: {} },
+      scoring: { standard: {} },
+      deterministic: false,
+    };
+
+    const tx = await program.methods
+      .createGame(gameId, { competitive: {} }, options)
+      .accountsPartial({
+        game: gamePda,
+        config: configPda,
+        profile: creatorProfile,
+        authority: creator.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .transaction();
+    await measure("create_game", tx, [creator]);
+    await sendAndConfirmTransaction(provider.connection, tx, [creator]);
+  });
+
+  it("join_game, including the start commitment on the last seat", async () => {
+    for (const [i, player] of players.entries()) {
+      const [profile] = PublicKey.findProgramAddressSync(
+        [Buffer.from("profile"), player.publicKey.toBuffer()],
+        program.programId
+      );
+      const tx = await program.methods
+        .joinGame(null)
+        .accountsPartial({
+          game: gamePda,
+          profile,
+          agent: null,
+          config: configPda,
+          player: player.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .transaction();
+      // The fourth join commits the start to a slot a few ahead
+      await measure(i === players.length - 1 ? "join_game (start commit)" : "join_game", tx, [
+        player,
+      ]);
+      await sendAndConfirmTransaction(provider.connection, tx, [player]);
+    }
+
+    const lobby = await program.account.pirateGame.fetch(gamePda);
+    assert.ok("waiting" in lobby.status);
+    assert.ok(lobby.startRevealSlot !== null);
+  });
+
+  it("reveal_seed, generating the map and deploying every fleet", async () => {
+    const lobby = await program.account.pirateGame.fetch(gamePda);
+    await waitPastSlot(lobby.startRevealSlot.toNumber());
+
+    const revealer = players[0];
+    const tx = await program.methods
+      .revealSeed()
+      .accountsPartial({
+        game: gamePda,
+        recentSlothashes: SYSVAR_SLOT_HASHES_PUBKEY,
+        beacon: null,
+      })
+      .transaction();
+    await measure("reveal_seed", tx, [revealer]);
+    await sendAndConfirmTransaction(provider.connection, tx, [revealer]);
+
+    const game = await program.account.pirateGame.fetch(gamePda);
+    assert.ok("active" in game.status);
+    for (const player of game.players) {
+      assert.strictEqual(player.ships.length, MAX_SHIPS_PER_PLAYER);
+    }
+  });
+
+  it("single-action instructions stay within budget", async () => {
+    const { signer, index, nonce } = await currentPlayer();
+    const game = await program.account.pirateGame.fetch(gamePda);
+    const ship = game.players[index].ships[0];
+    const [toX, toY] = await findLegalMove(signer, ship, nonce);
+    const accounts = { game: gamePda, player: signer.publicKey };
+
+    const cases: [string, Transaction][] = [
+      [
+        "move_ship",
+        await program.methods
+          .moveShip(ship.id, toX, toY, Buffer.from([]), new anchor.BN(3000), nonce)
+          .accountsPartial(accounts)
+          .transaction(),
+      ],
+      [
+        "collect_resources",
+        await program.methods.collectResources(nonce).accountsPartial(accounts).transaction(),
+      ],
+      [
+        "scan_coordinate",
+        await program.methods
+          .scanCoordinate(MAP_SIZE - 1, MAP_SIZE - 1, nonce)
+          .accountsPartial(accounts)
+          .transaction(),
+      ],
+      [
+        "end_turn",
+        await program.methods.endTurn(nonce).accountsPartial(accounts).transaction(),
+      ],
+      [
+        "check_and_complete_game",
+        await program.methods.checkAndCompleteGame().accountsPartial(accounts).transaction(),
+      ],
+    ];
+
+    for (const [instruction, tx] of cases) {
+      await measure(instruction, tx, [signer]);
+    }
+  });
+
+  it("a full batch_actions turn stays within budget", async () => {
+    const { signer, index, nonce } = await currentPlayer();
+    const game = await program.account.pirateGame.fetch(gamePda);
+    const ship = game.players[index].ships[0];
+    const [toX, toY] = await findLegalMove(signer, ship, nonce);
+
+    const tx = await program.methods
+      .batchActions([
+        { moveShip: { shipId: ship.id, toX, toY, via: Buffer.from([]), decisionTimeMs: null } },
+        { collectResources: {} },
+        { scanCoordinate: { coordinateX: 0, coordinateY: MAP_SIZE - 1 } },
+        { endTurn: {} },
+      ], nonce)
+      .accountsPartial({ game: gamePda, player: signer.publicKey })
+      .transaction();
+    await measure("batch_actions", tx, [signer]);
+  });
+
+  after(() => {
+    console.table(usage);
+  });
+});
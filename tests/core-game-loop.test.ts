@@ -80,7 +80,7 @@ describe("PIR8 Core Game Loop", () => {
     const newY = ship.positionY;
 
     const tx = await program.methods
-      .moveShip(ship.id, newX, newY, new anchor.BN(3000))
+      .moveShip(ship.id, newX, newY, Buffer.from([]), new anchor.BN(3000), player.actionNonce)
       .accounts({
         game: globalGamePda,
         player: provider.wallet.publicKey,